@@ -9,10 +9,10 @@ use std::{
 use djio::{
     consume_midi_input_event,
     devices::{korg_kaoss_dj, pioneer_ddj_400, MIDI_DJ_CONTROLLER_DESCRIPTORS},
-    BoxedMidiOutputConnection, ControlInputEventSink, MidiDeviceDescriptor, MidiInputConnector,
-    MidiInputEventDecoder, MidiInputGateway, MidiInputHandler, MidiOutputGateway,
-    MidiPortDescriptor, MidirDevice, MidirDeviceManager, OutputResult, PortIndex,
-    PortIndexGenerator, TimeStamp,
+    select_device, BoxedMidiOutputConnection, ControlInputEventSink, DeviceSelection,
+    MidiDeviceDescriptor, MidiInputConnector, MidiInputEventDecoder, MidiInputGateway,
+    MidiInputHandler, MidiOutputGateway, MidiPortDescriptor, MidirDevice, MidirDeviceManager,
+    OutputResult, PortIndex, PortIndexGenerator, SelectDeviceError, TimeStamp,
 };
 
 #[derive(Debug, Clone, Default)]
@@ -242,36 +242,46 @@ fn run() -> anyhow::Result<()> {
     let device_manager = MidirDeviceManager::<MidiLogger>::new()?;
     let mut dj_controllers =
         device_manager.detect_dj_controllers(MIDI_DJ_CONTROLLER_DESCRIPTORS, &port_index_generator);
-    let (_descriptor, mut midir_device) = match dj_controllers.len() {
-        0 => anyhow::bail!("No supported DJ controllers found"),
-        1 => {
-            println!(
-                "Choosing the only available DJ Controller: {device_name}",
-                device_name = dj_controllers[0].1.descriptor().device.name(),
-            );
-            dj_controllers.remove(0)
-        }
-        _ => {
-            println!("\nAvailable devices:");
-            for (i, (_descriptor, device)) in dj_controllers.iter().enumerate() {
+    let (_descriptor, mut midir_device) =
+        match select_device(&mut dj_controllers, DeviceSelection::Unambiguous) {
+            Ok(selected) => {
                 println!(
-                    "{device_number}: {device_name}",
-                    device_number = i + 1,
-                    device_name = device.descriptor().device.name()
+                    "Choosing the only available DJ Controller: {device_name}",
+                    device_name = selected.1.descriptor().device.name(),
                 );
+                selected
+            }
+            Err(SelectDeviceError::Ambiguous { .. }) => {
+                println!("\nAvailable devices:");
+                for (i, (_descriptor, device)) in dj_controllers.iter().enumerate() {
+                    println!(
+                        "{device_number}: {device_name}",
+                        device_number = i + 1,
+                        device_name = device.descriptor().device.name()
+                    );
+                }
+                print!("Please select a device: ");
+                stdout().flush()?;
+                let mut input = String::new();
+                stdin().read_line(&mut input)?;
+                let device_number = input.trim().parse::<usize>()?;
+                let Some(index) = device_number.checked_sub(1) else {
+                    eprintln!("Unknown device number {device_number}");
+                    return Ok(());
+                };
+                match select_device(&mut dj_controllers, DeviceSelection::Index(index)) {
+                    Ok(selected) => selected,
+                    Err(err) => {
+                        eprintln!("Unknown device number {device_number}: {err}");
+                        return Ok(());
+                    }
+                }
             }
-            print!("Please select a device: ");
-            stdout().flush()?;
-            let mut input = String::new();
-            stdin().read_line(&mut input)?;
-            let device_number = input.trim().parse::<usize>()?;
-            if device_number < 1 || device_number > dj_controllers.len() {
-                eprintln!("Unknown device number {device_number}");
-                return Ok(());
+            Err(SelectDeviceError::NoDevices) => {
+                anyhow::bail!("No supported DJ controllers found")
             }
-            dj_controllers.remove(device_number - 1)
-        }
-    };
+            Err(err) => return Err(err.into()),
+        };
 
     let new_midi_input_gateway = Some(NewMidiInputGateway);
 
@@ -3,7 +3,21 @@
 
 use std::ops::Not as _;
 
-use djio::{devices::ni_traktor_kontrol_s4mk3, HidApi, HidUsagePage};
+use djio::{
+    devices::ni_traktor_kontrol_s4mk3, ControlInputEvent, ControlInputEventSink, HidApi,
+    HidUsagePage,
+};
+
+struct LogInputEventSink;
+
+impl ControlInputEventSink for LogInputEventSink {
+    fn sink_control_input_events(&mut self, events: &[ControlInputEvent]) {
+        for event in events {
+            let sensor = ni_traktor_kontrol_s4mk3::Sensor::try_from(event.input.index);
+            log::info!("Received {event:?}: {sensor:?}");
+        }
+    }
+}
 
 fn main() {
     pretty_env_logger::init();
@@ -81,7 +95,8 @@ fn run() -> anyhow::Result<()> {
             device_context.is_none(),
             "only a single device is supported"
         );
-        let mut new_device_context = ni_traktor_kontrol_s4mk3::DeviceContext::attach(device)?;
+        let mut new_device_context =
+            ni_traktor_kontrol_s4mk3::DeviceContext::attach(device, LogInputEventSink)?;
         log::info!(
             "Initializing device: {device_info:?}",
             device_info = new_device_context.info()
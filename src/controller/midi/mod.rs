@@ -1,7 +1,10 @@
 // SPDX-FileCopyrightText: The djio authors
 // SPDX-License-Identifier: MPL-2.0
 
-use crate::{BoxedMidiOutputConnection, Controller, MidiDeviceDescriptor, MidiOutputGateway};
+use crate::{
+    BoxedMidiOutputConnection, Controller, MidiDeviceDescriptor, MidiOutputGateway,
+    SelectDeviceError,
+};
 
 #[cfg(feature = "controller-thread")]
 pub(crate) mod context;
@@ -12,3 +15,220 @@ pub trait MidiController: Controller + MidiOutputGateway<BoxedMidiOutputConnecti
 }
 
 pub type BoxedMidiController<T> = Box<dyn MidiController<Types = T> + Send + 'static>;
+
+/// Creates a new, unconnected MIDI controller
+pub type NewMidiController<T> = Box<dyn Fn() -> BoxedMidiController<T> + Send + Sync + 'static>;
+
+/// Registry of supported MIDI controllers
+///
+/// Maps [`MidiDeviceDescriptor`]s to factories of [`BoxedMidiController`]s.
+/// Detected devices could then be turned into controllers without
+/// comparing device descriptors in application code.
+#[allow(missing_debug_implementations)]
+pub struct ControllerRegistry<T> {
+    factories: Vec<(MidiDeviceDescriptor, NewMidiController<T>)>,
+}
+
+impl<T> ControllerRegistry<T> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            factories: Vec::new(),
+        }
+    }
+
+    /// Register a controller factory for a device
+    ///
+    /// Replaces and returns the factory that has previously been
+    /// registered for the same device.
+    pub fn register(
+        &mut self,
+        descriptor: MidiDeviceDescriptor,
+        new_controller: NewMidiController<T>,
+    ) -> Option<NewMidiController<T>> {
+        if let Some((_, registered)) = self
+            .factories
+            .iter_mut()
+            .find(|(registered, _)| *registered == descriptor)
+        {
+            return Some(std::mem::replace(registered, new_controller));
+        }
+        self.factories.push((descriptor, new_controller));
+        None
+    }
+
+    /// All registered devices
+    pub fn descriptors(&self) -> impl Iterator<Item = &MidiDeviceDescriptor> {
+        self.factories.iter().map(|(descriptor, _)| descriptor)
+    }
+
+    #[must_use]
+    pub fn contains(&self, descriptor: &MidiDeviceDescriptor) -> bool {
+        self.descriptors()
+            .any(|registered| registered == descriptor)
+    }
+
+    /// Find the registered device of a MIDI port
+    ///
+    /// Fails if none or more than one of the registered devices
+    /// match the port name.
+    pub fn find_by_port_name(
+        &self,
+        port_name: &str,
+    ) -> Result<&MidiDeviceDescriptor, SelectDeviceError> {
+        if self.factories.is_empty() {
+            return Err(SelectDeviceError::NoDevices);
+        }
+        let mut matching = self
+            .descriptors()
+            .filter(|descriptor| descriptor.matches_port_name(port_name));
+        let Some(descriptor) = matching.next() else {
+            return Err(SelectDeviceError::NotFound);
+        };
+        let count = 1 + matching.count();
+        if count > 1 {
+            return Err(SelectDeviceError::Ambiguous { count });
+        }
+        Ok(descriptor)
+    }
+
+    /// Create a new controller for a device
+    ///
+    /// Returns `None` if the device is not supported.
+    #[must_use]
+    pub fn new_controller(
+        &self,
+        descriptor: &MidiDeviceDescriptor,
+    ) -> Option<BoxedMidiController<T>> {
+        self.factories
+            .iter()
+            .find(|(registered, _)| registered == descriptor)
+            .map(|(_, new_controller)| new_controller())
+    }
+}
+
+impl<T> Default for ControllerRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::{ControllerRegistry, MidiController, NewMidiController};
+    use crate::{
+        BoxedControllerTask, BoxedMidiOutputConnection, Controller, ControllerCapabilities,
+        ControllerDescriptor, ControllerTypes, DeviceDescriptor, MidiDeviceDescriptor,
+        MidiOutputGateway, MidiPortNameMatching, OutputResult, SelectDeviceError,
+    };
+
+    struct Types;
+
+    impl ControllerTypes for Types {
+        type Context = ();
+        type InputEvent = ();
+        type ControlAction = ();
+    }
+
+    const fn device(product_name: &'static str) -> MidiDeviceDescriptor {
+        MidiDeviceDescriptor::new(
+            DeviceDescriptor {
+                vendor_name: Cow::Borrowed("Vendor"),
+                product_name: Cow::Borrowed(product_name),
+                audio_interface: None,
+            },
+            product_name,
+        )
+    }
+
+    struct StubController(MidiDeviceDescriptor);
+
+    impl Controller for StubController {
+        type Types = Types;
+
+        fn device_descriptor(&self) -> DeviceDescriptor {
+            self.0.device.clone()
+        }
+
+        fn controller_descriptor(&self) -> ControllerDescriptor {
+            ControllerDescriptor {
+                num_decks: 0,
+                num_virtual_decks: 0,
+                num_mixer_channels: 0,
+                num_pads_per_deck: 0,
+                num_effect_units: 0,
+                capabilities: ControllerCapabilities::empty(),
+            }
+        }
+
+        fn attach_context_listener(&mut self, _context: &()) -> Option<BoxedControllerTask> {
+            None
+        }
+    }
+
+    impl MidiOutputGateway<BoxedMidiOutputConnection> for StubController {
+        fn attach_midi_output_connection(
+            &mut self,
+            midi_output_connection: &mut Option<BoxedMidiOutputConnection>,
+        ) -> OutputResult<()> {
+            midi_output_connection.take();
+            Ok(())
+        }
+
+        fn detach_midi_output_connection(&mut self) -> Option<BoxedMidiOutputConnection> {
+            None
+        }
+    }
+
+    impl MidiController for StubController {
+        fn midi_device_descriptor(&self) -> &MidiDeviceDescriptor {
+            &self.0
+        }
+    }
+
+    fn new_controller(descriptor: &MidiDeviceDescriptor) -> NewMidiController<Types> {
+        let descriptor = descriptor.clone();
+        Box::new(move || Box::new(StubController(descriptor.clone())))
+    }
+
+    #[test]
+    fn find_by_port_name() {
+        let mut registry = ControllerRegistry::<Types>::new();
+        assert_eq!(
+            Err(SelectDeviceError::NoDevices),
+            registry.find_by_port_name("DJ-1 MIDI 1")
+        );
+
+        let dj_1 = device("DJ-1");
+        let dj_2 = device("DJ-2");
+        assert!(registry
+            .register(dj_1.clone(), new_controller(&dj_1))
+            .is_none());
+        assert!(registry
+            .register(dj_2.clone(), new_controller(&dj_2))
+            .is_none());
+        assert!(registry
+            .register(dj_2.clone(), new_controller(&dj_2))
+            .is_some());
+        assert_eq!(2, registry.descriptors().count());
+
+        assert_eq!(Ok(&dj_1), registry.find_by_port_name("DJ-1 MIDI 1"));
+        assert_eq!(Ok(&dj_2), registry.find_by_port_name("DJ-2 MIDI 1"));
+        let controller = registry.new_controller(&dj_2).unwrap();
+        assert_eq!(&dj_2, controller.midi_device_descriptor());
+        assert_eq!(
+            Err(SelectDeviceError::NotFound),
+            registry.find_by_port_name("DJ-3 MIDI 1")
+        );
+
+        // Both devices match if the prefix is contained anywhere
+        let dj = device("DJ").with_port_name_matching(MidiPortNameMatching::Contains);
+        assert!(registry.register(dj.clone(), new_controller(&dj)).is_none());
+        assert_eq!(
+            Err(SelectDeviceError::Ambiguous { count: 2 }),
+            registry.find_by_port_name("DJ-1 MIDI 1")
+        );
+    }
+}
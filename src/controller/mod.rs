@@ -1,9 +1,9 @@
 // SPDX-FileCopyrightText: The djio authors
 // SPDX-License-Identifier: MPL-2.0
 
-use std::future::Future;
+use std::{borrow::Cow, future::Future};
 
-use crate::{DeviceDescriptor, PortIndex};
+use crate::{ControlIndex, ControlOutputGateway, DeviceDescriptor, OutputResult, PortIndex};
 
 #[cfg(feature = "midi")]
 pub(super) mod midi;
@@ -24,6 +24,43 @@ pub trait ControllerTypes {
     type ControlAction;
 }
 
+bitflags::bitflags! {
+    /// Optional hardware features of a controller
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct ControllerCapabilities: u32 {
+        /// Motorized jog wheels or platters
+        const MOTORIZED_JOG = 1 << 0;
+        /// Displays in the center of the jog wheels
+        const JOG_DISPLAY = 1 << 1;
+        /// Touch strips, e.g. for needle search or pitch bend
+        const TOUCH_STRIP = 1 << 2;
+        /// Level meters for the mixer channels
+        const VU_METERS = 1 << 3;
+    }
+}
+
+impl ControllerCapabilities {
+    #[must_use]
+    pub const fn has_motorized_jog(self) -> bool {
+        self.contains(Self::MOTORIZED_JOG)
+    }
+
+    #[must_use]
+    pub const fn has_jog_display(self) -> bool {
+        self.contains(Self::JOG_DISPLAY)
+    }
+
+    #[must_use]
+    pub const fn has_touch_strip(self) -> bool {
+        self.contains(Self::TOUCH_STRIP)
+    }
+
+    #[must_use]
+    pub const fn has_vu_meters(self) -> bool {
+        self.contains(Self::VU_METERS)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ControllerDescriptor {
     /// Number of physical decks
@@ -44,6 +81,60 @@ pub struct ControllerDescriptor {
 
     /// Number of effect units
     pub num_effect_units: u8,
+
+    /// Optional hardware features
+    pub capabilities: ControllerCapabilities,
+}
+
+/// Kind of an input control
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputControlKind {
+    Button,
+    /// Faders and knobs with an absolute position
+    Slider,
+    /// Endless knobs and jog wheels
+    Encoder,
+    /// Switches with multiple, discrete positions
+    Selector,
+    /// Performance pads
+    Pad,
+}
+
+/// Input control of a controller, e.g. a button or a fader
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputControlDescriptor {
+    pub index: ControlIndex,
+
+    /// Human-readable name
+    pub name: Cow<'static, str>,
+
+    pub kind: InputControlKind,
+}
+
+/// Kind of an output control
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutputControlKind {
+    /// See [`LedOutput`](crate::LedOutput)
+    OnOff,
+    /// See [`DimLedOutput`](crate::DimLedOutput)
+    Dim,
+    /// See [`RgbLedOutput`](crate::RgbLedOutput)
+    Rgb,
+}
+
+/// Output control of a controller, e.g. an LED
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutputControlDescriptor {
+    pub index: ControlIndex,
+
+    /// Human-readable name
+    pub name: Cow<'static, str>,
+
+    pub kind: OutputControlKind,
 }
 
 pub trait Controller {
@@ -77,6 +168,36 @@ pub trait Controller {
         context: &<Self::Types as ControllerTypes>::Context,
     ) -> Option<BoxedControllerTask>;
 
+    /// Reset the hardware into a known state
+    ///
+    /// Turns off all LEDs and recenters motorized elements without
+    /// detaching the controller, e.g. when the application loses focus.
+    ///
+    /// The default implementation does nothing.
+    fn reset(&mut self, _gateway: &mut dyn ControlOutputGateway) -> OutputResult<()> {
+        Ok(())
+    }
+
+    /// All input controls that emit input events
+    ///
+    /// Allows to learn mappings or to document the hardware.
+    ///
+    /// The default implementation returns an empty slice.
+    #[must_use]
+    fn input_controls(&self) -> &'static [InputControlDescriptor] {
+        &[]
+    }
+
+    /// All output controls that could be addressed
+    ///
+    /// Allows to render a virtual representation of the hardware.
+    ///
+    /// The default implementation returns an empty slice.
+    #[must_use]
+    fn output_controls(&self) -> &'static [OutputControlDescriptor] {
+        &[]
+    }
+
     /// Input port index
     ///
     /// Only needs to be implemented for controllers that generate input events.
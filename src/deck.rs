@@ -3,9 +3,12 @@
 
 //! Virtual DJ deck utilities.
 
-use std::time::Duration;
+use std::{collections::VecDeque, time::Duration};
 
-use crate::{ButtonInput, CenterSliderInput, LedState, SliderInput};
+use crate::{
+    ButtonInput, CenterSliderInput, LedState, ScratchVelocityTracker, SliderEncoderInput,
+    SliderInput, TimeStamp, VirtualLed,
+};
 
 pub const PLAYBACK_RATE_DEFAULT: f32 = 1.0;
 
@@ -20,11 +23,127 @@ pub struct Position {
     pub offset_secs: f64,
 }
 
+/// Constant tempo beat grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BeatGrid {
+    /// Offset of the first beat in seconds
+    ///
+    /// Could be negative if the track starts with an incomplete bar.
+    pub first_beat_secs: f64,
+
+    /// Tempo in beats per minute
+    pub bpm: f32,
+}
+
+impl BeatGrid {
+    /// Duration of a single beat in seconds
+    #[must_use]
+    pub fn beat_secs(&self) -> f64 {
+        debug_assert!(self.bpm > 0.0);
+        60.0 / f64::from(self.bpm)
+    }
+
+    /// Convert a position into a (fractional) number of beats
+    ///
+    /// Positions before the first beat result in negative values.
+    #[must_use]
+    pub fn position_to_beats(&self, position: Position) -> f64 {
+        let Position { offset_secs } = position;
+        (offset_secs - self.first_beat_secs) / self.beat_secs()
+    }
+
+    /// Convert a (fractional) number of beats into a position
+    #[must_use]
+    pub fn beats_to_position(&self, beats: f64) -> Position {
+        let offset_secs = beats.mul_add(self.beat_secs(), self.first_beat_secs);
+        Position { offset_secs }
+    }
+
+    /// Move a position onto the nearest beat
+    #[must_use]
+    pub fn snap_to_beat(&self, position: Position) -> Position {
+        self.beats_to_position(self.position_to_beats(position).round())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct Cue {
     pub position: Position,
 }
 
+/// Default number of hot cues
+pub const NUM_HOT_CUES_DEFAULT: usize = 8;
+
+/// Bank of hot cue slots
+///
+/// Slot indices that are out of range are treated like empty slots
+/// that cannot be modified.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HotCues<const N: usize = NUM_HOT_CUES_DEFAULT> {
+    slots: [Option<Cue>; N],
+}
+
+impl<const N: usize> HotCues<N> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { slots: [None; N] }
+    }
+
+    #[must_use]
+    pub const fn slots(&self) -> &[Option<Cue>; N] {
+        &self.slots
+    }
+
+    #[must_use]
+    pub const fn get(&self, index: usize) -> Option<Cue> {
+        if index < N {
+            self.slots[index]
+        } else {
+            None
+        }
+    }
+
+    /// Store a hot cue
+    ///
+    /// Returns the replaced hot cue.
+    pub fn set(&mut self, index: usize, position: Position) -> Option<Cue> {
+        self.slots.get_mut(index)?.replace(Cue { position })
+    }
+
+    /// Remove a hot cue
+    ///
+    /// Returns the removed hot cue.
+    pub fn clear(&mut self, index: usize) -> Option<Cue> {
+        self.slots.get_mut(index)?.take()
+    }
+
+    /// Position for jumping to a hot cue
+    ///
+    /// Returns `None` if the slot is empty.
+    #[must_use]
+    pub fn jump(&self, index: usize) -> Option<Position> {
+        self.get(index).map(|Cue { position }| position)
+    }
+
+    /// LED states of all slots, lit if set
+    #[must_use]
+    pub fn virtual_leds(&self) -> [VirtualLed; N] {
+        self.slots.map(|slot| {
+            VirtualLed::initial_state(if slot.is_some() {
+                LedState::On
+            } else {
+                LedState::Off
+            })
+        })
+    }
+}
+
+impl<const N: usize> Default for HotCues<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum PlayState {
     /// Paused
@@ -43,6 +162,58 @@ pub enum PlayState {
 }
 
 impl PlayState {
+    /// Next state after pressing the play/pause button
+    ///
+    /// Pressing play while previewing continues playback. An ended
+    /// track could not be played again without returning to the cue.
+    #[must_use]
+    pub const fn on_play_pause(self) -> Self {
+        match self {
+            Self::Paused { .. } | Self::Previewing { .. } => Self::Playing,
+            Self::Playing => Self::Paused {
+                playhead_on_cue: false,
+            },
+            Self::Ended => Self::Ended,
+        }
+    }
+
+    /// Next state after pressing the cue button (Pioneer-style)
+    ///
+    /// - While playing or after the track has ended the playhead returns
+    ///   to the cue and pauses.
+    /// - While paused apart from the cue the cue is set at the playhead.
+    /// - While paused on the cue the given `cue` is previewed until the
+    ///   button is released.
+    #[must_use]
+    pub const fn on_cue_press(self, cue: Cue) -> Self {
+        match self {
+            Self::Playing
+            | Self::Ended
+            | Self::Paused {
+                playhead_on_cue: false,
+            } => Self::Paused {
+                playhead_on_cue: true,
+            },
+            Self::Paused {
+                playhead_on_cue: true,
+            } => Self::Previewing { cue },
+            Self::Previewing { .. } => self,
+        }
+    }
+
+    /// Next state after releasing the cue button (Pioneer-style)
+    ///
+    /// Previewing stops and the playhead returns to the cue.
+    #[must_use]
+    pub const fn on_cue_release(self) -> Self {
+        match self {
+            Self::Previewing { .. } => Self::Paused {
+                playhead_on_cue: true,
+            },
+            Self::Paused { .. } | Self::Playing | Self::Ended => self,
+        }
+    }
+
     #[must_use]
     pub const fn pioneer_cue_led_state(&self) -> LedState {
         match self {
@@ -66,6 +237,35 @@ impl PlayState {
             PlayState::Ended => LedState::Off,
         }
     }
+
+    /// Cue LED state of Denon DJ controllers
+    ///
+    /// Unlike Pioneer the cue LED is turned off during playback.
+    #[must_use]
+    pub const fn denon_cue_led_state(&self) -> LedState {
+        match self {
+            PlayState::Paused {
+                playhead_on_cue: true,
+            }
+            | PlayState::Previewing { .. } => LedState::On,
+            PlayState::Paused {
+                playhead_on_cue: false,
+            } => LedState::BlinkSlow,
+            PlayState::Playing | PlayState::Ended => LedState::Off,
+        }
+    }
+
+    /// Play/pause LED state of Denon DJ controllers
+    ///
+    /// Unlike Pioneer the LED is lit while previewing and turned off
+    /// while paused.
+    #[must_use]
+    pub const fn denon_playpause_led_state(&self) -> LedState {
+        match self {
+            PlayState::Playing | PlayState::Previewing { .. } => LedState::On,
+            PlayState::Paused { .. } | PlayState::Ended => LedState::Off,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
@@ -74,6 +274,297 @@ pub struct Playhead {
     pub is_playing: bool,
 }
 
+impl Playhead {
+    /// Advance the position while playing
+    ///
+    /// Moves the position by the playback rate multiplied with the elapsed
+    /// time `dt`. Negative rates move the position backwards until the start
+    /// of the media.
+    ///
+    /// Playback stops at the end of the media and `playable` is transitioned
+    /// into [`PlayState::Ended`].
+    pub fn advance(&mut self, playable: &mut Playable, params: &PlaybackParams, dt: Duration) {
+        if !self.is_playing {
+            return;
+        }
+        let offset_secs =
+            f64::from(params.rate).mul_add(dt.as_secs_f64(), self.position.offset_secs);
+        let offset_secs = offset_secs.max(0.0);
+        if let Some(duration) = playable.duration {
+            let duration_secs = duration.as_secs_f64();
+            if offset_secs >= duration_secs {
+                self.position.offset_secs = duration_secs;
+                self.is_playing = false;
+                playable.play_state = PlayState::Ended;
+                return;
+            }
+        }
+        self.position.offset_secs = offset_secs;
+    }
+
+    /// Seek to a fraction of the media duration, e.g. for needle dropping
+    ///
+    /// The fraction is clamped to the range `0.0..=1.0`, e.g. the
+    /// [`SliderInput::position`] of a touch strip.
+    ///
+    /// Does nothing if the duration is unknown and returns `false`.
+    pub fn seek_fraction(&mut self, frac: f32, duration: Option<Duration>) -> bool {
+        let Some(duration) = duration else {
+            return false;
+        };
+        let frac = f64::from(frac.clamp(0.0, 1.0));
+        self.position.offset_secs = frac * duration.as_secs_f64();
+        true
+    }
+}
+
+/// Loop between two positions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Loop {
+    pub start: Position,
+
+    /// `None` until the loop has been closed
+    pub end: Option<Position>,
+
+    /// Playback is looping
+    pub active: bool,
+}
+
+impl Loop {
+    /// Open a new, inactive loop at the given position
+    #[must_use]
+    pub const fn new(start: Position) -> Self {
+        Self {
+            start,
+            end: None,
+            active: false,
+        }
+    }
+
+    /// Duration of a closed loop in seconds
+    #[must_use]
+    pub fn length_secs(&self) -> Option<f64> {
+        let end = self.end?;
+        Some(end.offset_secs - self.start.offset_secs)
+    }
+
+    /// Check if a position is within the closed loop
+    ///
+    /// The start is included and the end is excluded.
+    #[must_use]
+    pub fn contains(&self, position: Position) -> bool {
+        let Some(end) = self.end else {
+            return false;
+        };
+        (self.start.offset_secs..end.offset_secs).contains(&position.offset_secs)
+    }
+
+    /// Wrap a position that exceeded the end of an active loop
+    ///
+    /// Positions before the start are not affected, i.e. seeking
+    /// backwards out of the loop is possible.
+    #[must_use]
+    pub fn wrap_position(&self, position: Position) -> Position {
+        if !self.active {
+            return position;
+        }
+        let Some(length_secs) = self.length_secs() else {
+            return position;
+        };
+        if length_secs <= 0.0 || position.offset_secs < self.start.offset_secs + length_secs {
+            return position;
+        }
+        let offset_secs =
+            self.start.offset_secs + (position.offset_secs - self.start.offset_secs) % length_secs;
+        Position { offset_secs }
+    }
+
+    /// LED state of the reloop/exit button on Pioneer controllers
+    #[must_use]
+    pub const fn pioneer_reloop_led_state(&self) -> LedState {
+        match (self.end, self.active) {
+            (None, _) => LedState::Off,
+            (Some(_), true) => LedState::BlinkFast,
+            (Some(_), false) => LedState::On,
+        }
+    }
+}
+
+/// Playhead and loop of a deck
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DeckState {
+    pub playhead: Playhead,
+    pub current_loop: Option<Loop>,
+}
+
+impl DeckState {
+    /// Open a new loop at the playhead position
+    ///
+    /// Replaces the current loop.
+    pub fn loop_in(&mut self) {
+        self.current_loop = Some(Loop::new(self.playhead.position));
+    }
+
+    /// Close and activate the current loop at the playhead position
+    ///
+    /// Returns `false` if there is no loop or if the playhead is not
+    /// after the start of the loop.
+    pub fn loop_out(&mut self) -> bool {
+        let Self {
+            playhead,
+            current_loop,
+        } = self;
+        let Some(current_loop) = current_loop else {
+            return false;
+        };
+        if playhead.position.offset_secs <= current_loop.start.offset_secs {
+            return false;
+        }
+        current_loop.end = Some(playhead.position);
+        current_loop.active = true;
+        true
+    }
+
+    /// Toggle the closed loop
+    ///
+    /// Exits an active loop. Otherwise re-activates the loop and moves
+    /// the playhead to its start. Returns `false` if there is no closed loop.
+    pub fn reloop(&mut self) -> bool {
+        let Self {
+            playhead,
+            current_loop,
+        } = self;
+        let Some(current_loop) = current_loop.as_mut().filter(|l| l.end.is_some()) else {
+            return false;
+        };
+        if current_loop.active {
+            current_loop.active = false;
+        } else {
+            current_loop.active = true;
+            playhead.position = current_loop.start;
+        }
+        true
+    }
+
+    /// Check if the playhead is within an active loop
+    #[must_use]
+    pub fn is_playhead_in_loop(&self) -> bool {
+        self.current_loop
+            .is_some_and(|l| l.active && l.contains(self.playhead.position))
+    }
+
+    /// Move the playhead, wrapping it around an active loop
+    ///
+    /// Returns the resulting position.
+    pub fn update_playhead_position(&mut self, position: Position) -> Position {
+        let position = self
+            .current_loop
+            .map_or(position, |l| l.wrap_position(position));
+        self.playhead.position = position;
+        position
+    }
+
+    /// LED state of the reloop/exit button on Pioneer controllers
+    #[must_use]
+    pub fn pioneer_reloop_led_state(&self) -> LedState {
+        self.current_loop
+            .as_ref()
+            .map_or(LedState::Off, Loop::pioneer_reloop_led_state)
+    }
+}
+
+/// Estimates the tempo from the time stamps of taps
+///
+/// The tempo is averaged over the last few intervals between taps.
+/// Intervals that deviate too much from the median are rejected as
+/// outliers. Tapping starts over after a long gap.
+#[derive(Debug, Clone)]
+pub struct TapTempo {
+    max_gap: Duration,
+    taps: VecDeque<TimeStamp>,
+}
+
+impl TapTempo {
+    /// Number of taps that are considered, i.e. one more than the number of intervals
+    pub const MAX_TAPS: usize = 9;
+
+    pub const DEFAULT_MAX_GAP: Duration = Duration::from_secs(2);
+
+    /// Maximum relative deviation of an interval from the median
+    pub const OUTLIER_TOLERANCE: f64 = 0.25;
+
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_gap: Self::DEFAULT_MAX_GAP,
+            taps: VecDeque::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn with_max_gap(mut self, max_gap: Duration) -> Self {
+        self.max_gap = max_gap;
+        self
+    }
+
+    /// Record a tap
+    ///
+    /// Starts over if the time stamp is after the maximum gap or
+    /// before the previous tap.
+    pub fn tap(&mut self, ts: TimeStamp) {
+        if let Some(&last_ts) = self.taps.back() {
+            if ts
+                .duration_since(last_ts)
+                .is_none_or(|gap| gap > self.max_gap)
+            {
+                self.taps.clear();
+            }
+        }
+        if self.taps.len() >= Self::MAX_TAPS {
+            self.taps.pop_front();
+        }
+        self.taps.push_back(ts);
+    }
+
+    pub fn reset(&mut self) {
+        self.taps.clear();
+    }
+
+    /// The estimated tempo in beats per minute
+    ///
+    /// Returns `None` until at least two taps have been recorded.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn bpm(&self) -> Option<f32> {
+        let mut intervals = self
+            .taps
+            .iter()
+            .zip(self.taps.iter().skip(1))
+            .filter_map(|(&earlier, &later)| later.duration_since(earlier))
+            .map(|interval| interval.as_secs_f64())
+            .filter(|&secs| secs > 0.0)
+            .collect::<Vec<_>>();
+        if intervals.is_empty() {
+            return None;
+        }
+        intervals.sort_unstable_by(f64::total_cmp);
+        let median = intervals[intervals.len() / 2];
+        let (sum, count) = intervals
+            .iter()
+            .filter(|&&secs| (secs - median).abs() <= median * Self::OUTLIER_TOLERANCE)
+            .fold((0.0, 0), |(sum, count), secs| (sum + secs, count + 1));
+        debug_assert!(count > 0);
+        let mean = sum / f64::from(count);
+        Some((60.0 / mean) as f32)
+    }
+}
+
+impl Default for TapTempo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Playable {
     pub play_state: PlayState,
@@ -100,6 +591,46 @@ impl TempoInput {
                 .center_slider
                 .map_position_linear(self.range_min, range_center, self.range_max)
     }
+
+    /// Adjust the tempo for matching the tempo of a master
+    ///
+    /// Moves the slider to the position that results in the [`Self::playback_rate()`]
+    /// needed for playing a track with `track_bpm` in sync with `master_bpm`.
+    /// The resulting playback rate is limited by the tempo range.
+    ///
+    /// Returns the adjusted input and `true` if the tempo could be matched
+    /// within the tempo range. Invalid tempos leave the input unmodified.
+    #[must_use]
+    pub fn with_sync_bpm(self, master_bpm: f32, track_bpm: f32) -> (Self, bool) {
+        if !(master_bpm.is_finite() && master_bpm > 0.0 && track_bpm.is_finite() && track_bpm > 0.0)
+        {
+            return (self, false);
+        }
+        let Self {
+            range_min,
+            range_max,
+            center_slider: _,
+        } = self;
+        debug_assert!(range_min < range_max);
+        let range_center = (range_min + range_max) / 2.0;
+        let tempo = master_bpm / track_bpm - PLAYBACK_RATE_DEFAULT;
+        let clamped_tempo = tempo.clamp(range_min, range_max);
+        let position = if clamped_tempo < range_center {
+            (clamped_tempo - range_center) / (range_center - range_min)
+        } else {
+            (clamped_tempo - range_center) / (range_max - range_center)
+        };
+        let center_slider = CenterSliderInput {
+            position: CenterSliderInput::clamp_position(position),
+        };
+        let synced = Self {
+            range_min,
+            range_max,
+            center_slider,
+        };
+        let in_range = (range_min..=range_max).contains(&tempo);
+        (synced, in_range)
+    }
 }
 
 impl Default for TempoInput {
@@ -144,6 +675,260 @@ impl Default for PlaybackParams {
     }
 }
 
+/// Transient offset of the playback rate, e.g. for nudging with a jog wheel
+///
+/// Each nudge adds to the offset, which is limited by a maximum. The
+/// offset decays linearly back to zero, i.e. from the maximum to zero
+/// within the decay time. The offset should be added to
+/// [`PlaybackParams::rate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PitchBend {
+    sensitivity: f32,
+    max_offset: f32,
+    decay: Duration,
+    offset: f32,
+}
+
+impl PitchBend {
+    /// Rate offset per revolution
+    pub const DEFAULT_SENSITIVITY: f32 = 0.1;
+
+    pub const DEFAULT_MAX_OFFSET: f32 = 0.2;
+
+    pub const DEFAULT_DECAY: Duration = Duration::from_millis(500);
+
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            sensitivity: Self::DEFAULT_SENSITIVITY,
+            max_offset: Self::DEFAULT_MAX_OFFSET,
+            decay: Self::DEFAULT_DECAY,
+            offset: 0.0,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_sensitivity(mut self, sensitivity: f32) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_max_offset(mut self, max_offset: f32) -> Self {
+        self.max_offset = max_offset;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_decay(mut self, decay: Duration) -> Self {
+        self.decay = decay;
+        self
+    }
+
+    /// Nudge by a delta in revolutions
+    ///
+    /// Accepts the delta of a [`SliderEncoderInput`](crate::SliderEncoderInput)
+    /// directly. The ticks of a [`StepEncoderInput`](crate::StepEncoderInput)
+    /// need to be divided by the number of ticks per revolution.
+    pub fn nudge(&mut self, delta: f32) {
+        debug_assert!(self.max_offset >= 0.0);
+        self.offset = delta
+            .mul_add(self.sensitivity, self.offset)
+            .clamp(-self.max_offset, self.max_offset);
+    }
+
+    /// Decay the offset by the elapsed time `dt` and return it
+    pub fn current_rate_offset(&mut self, dt: Duration) -> f32 {
+        let decay = if self.decay.is_zero() {
+            self.max_offset
+        } else {
+            self.max_offset * (dt.as_secs_f32() / self.decay.as_secs_f32())
+        };
+        self.offset = if self.offset > 0.0 {
+            (self.offset - decay).max(0.0)
+        } else {
+            (self.offset + decay).min(0.0)
+        };
+        self.offset
+    }
+
+    pub fn reset(&mut self) {
+        self.offset = 0.0;
+    }
+}
+
+impl Default for PitchBend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScratchState {
+    Released,
+    Touched { since_input: Duration },
+    Easing { from_rate: f32, elapsed: Duration },
+}
+
+/// Vinyl mode, i.e. controlling the playhead by touching and moving a jog wheel
+///
+/// Touching the jog wheel brakes playback immediately. While touched
+/// the playhead follows the velocity of the jog wheel. When the jog
+/// wheel is released the velocity eases back to the tempo of the deck,
+/// or to a halt if the deck is not playing.
+///
+/// The engine moves the playhead on its own while active, independent of
+/// [`Playhead::is_playing`]. Neither the tempo in [`PlaybackParams::rate`]
+/// nor the play state are modified, i.e. a stopped deck stays stopped
+/// after scratching.
+#[derive(Debug, Clone)]
+pub struct ScratchEngine {
+    velocity_tracker: ScratchVelocityTracker,
+    revs_per_sec: f32,
+    ease: Duration,
+    state: ScratchState,
+}
+
+impl ScratchEngine {
+    /// Velocity of a vinyl record at 33 ⅓ RPM
+    pub const DEFAULT_REVS_PER_SEC: f32 = 100.0 / 3.0 / 60.0;
+
+    pub const DEFAULT_EASE: Duration = Duration::from_millis(200);
+
+    #[must_use]
+    pub const fn new(velocity_tracker: ScratchVelocityTracker) -> Self {
+        Self {
+            velocity_tracker,
+            revs_per_sec: Self::DEFAULT_REVS_PER_SEC,
+            ease: Self::DEFAULT_EASE,
+            state: ScratchState::Released,
+        }
+    }
+
+    /// Velocity of the jog wheel at the normal playback rate
+    #[must_use]
+    pub const fn with_revs_per_sec(mut self, revs_per_sec: f32) -> Self {
+        self.revs_per_sec = revs_per_sec;
+        self
+    }
+
+    /// Time for easing back to the tempo after releasing
+    #[must_use]
+    pub const fn with_ease(mut self, ease: Duration) -> Self {
+        self.ease = ease;
+        self
+    }
+
+    #[must_use]
+    pub const fn is_touched(&self) -> bool {
+        matches!(self.state, ScratchState::Touched { .. })
+    }
+
+    /// Controls the playhead, either touched or easing
+    #[must_use]
+    pub const fn is_active(&self) -> bool {
+        !matches!(self.state, ScratchState::Released)
+    }
+
+    /// Start scratching
+    pub fn touch(&mut self) {
+        if self.is_touched() {
+            return;
+        }
+        self.velocity_tracker.reset();
+        self.state = ScratchState::Touched {
+            since_input: Duration::ZERO,
+        };
+    }
+
+    /// Move the jog wheel while touched
+    pub fn update(&mut self, ts: TimeStamp, input: SliderEncoderInput) {
+        let ScratchState::Touched { since_input } = &mut self.state else {
+            return;
+        };
+        *since_input = Duration::ZERO;
+        self.velocity_tracker.update(ts, input);
+    }
+
+    /// Stop scratching and ease back to the tempo
+    pub fn release(&mut self) {
+        if !self.is_touched() {
+            return;
+        }
+        self.state = ScratchState::Easing {
+            from_rate: self.scratch_rate(),
+            elapsed: Duration::ZERO,
+        };
+    }
+
+    fn scratch_rate(&self) -> f32 {
+        debug_assert!(self.revs_per_sec > 0.0);
+        self.velocity_tracker.velocity() / self.revs_per_sec
+    }
+
+    /// The effective playback rate after the elapsed time `dt`
+    ///
+    /// Returns `None` if not active.
+    fn next_rate(&mut self, target_rate: f32, dt: Duration) -> Option<f32> {
+        match &mut self.state {
+            ScratchState::Released => None,
+            ScratchState::Touched { since_input } => {
+                *since_input += dt;
+                if *since_input > self.velocity_tracker.max_elapsed() {
+                    // The jog wheel is held still
+                    self.velocity_tracker.reset();
+                }
+                Some(self.scratch_rate())
+            }
+            ScratchState::Easing { from_rate, elapsed } => {
+                *elapsed += dt;
+                if *elapsed >= self.ease {
+                    self.state = ScratchState::Released;
+                    return None;
+                }
+                let progress = elapsed.as_secs_f32() / self.ease.as_secs_f32();
+                Some((target_rate - *from_rate).mul_add(progress, *from_rate))
+            }
+        }
+    }
+
+    /// Move the playhead after the elapsed time `dt`
+    ///
+    /// Returns `true` if the playhead has been moved by the engine. Otherwise
+    /// the playhead is left untouched and should be advanced as usual, see
+    /// [`Playhead::advance()`].
+    ///
+    /// The position is kept within the bounds of the media while scratching.
+    pub fn advance(
+        &mut self,
+        playhead: &mut Playhead,
+        playable: &Playable,
+        params: &PlaybackParams,
+        dt: Duration,
+    ) -> bool {
+        let target_rate = if playhead.is_playing {
+            params.rate
+        } else {
+            PLAYBACK_RATE_PAUSED
+        };
+        let Some(rate) = self.next_rate(target_rate, dt) else {
+            return false;
+        };
+        let offset_secs = f64::from(rate).mul_add(dt.as_secs_f64(), playhead.position.offset_secs);
+        let offset_secs = offset_secs.max(0.0);
+        playhead.position.offset_secs = playable.duration.map_or(offset_secs, |duration| {
+            offset_secs.min(duration.as_secs_f64())
+        });
+        true
+    }
+}
+
+impl Default for ScratchEngine {
+    fn default() -> Self {
+        Self::new(ScratchVelocityTracker::default())
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct Player {
     /// Cue
@@ -227,3 +1012,285 @@ pub trait Adapter {
     /// of reading the current value.
     fn update_player(&mut self, playhead: Option<Playhead>, update_player: UpdatePlayer);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tempo_input_with_sync_bpm() {
+        let (tempo_input, synced) = TempoInput::default().with_sync_bpm(126.0, 120.0);
+        assert!(synced);
+        assert!((tempo_input.playback_rate() - 1.05).abs() < 1e-6);
+        let (tempo_input, synced) = TempoInput::default().with_sync_bpm(120.0, 126.0);
+        assert!(synced);
+        assert!((tempo_input.playback_rate() - 120.0 / 126.0).abs() < 1e-6);
+        // Out of range
+        let (tempo_input, synced) = TempoInput::default().with_sync_bpm(140.0, 120.0);
+        assert!(!synced);
+        assert!((tempo_input.playback_rate() - 1.08).abs() < 1e-6);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn playhead_seek_fraction() {
+        let mut playhead = Playhead::default();
+        let duration = Some(Duration::from_secs(200));
+        assert!(playhead.seek_fraction(0.25, duration));
+        assert_eq!(50.0, playhead.position.offset_secs);
+        assert!(playhead.seek_fraction(1.5, duration));
+        assert_eq!(200.0, playhead.position.offset_secs);
+        assert!(!playhead.seek_fraction(0.5, None));
+        assert_eq!(200.0, playhead.position.offset_secs);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn pitch_bend() {
+        let mut pitch_bend = PitchBend::new();
+        pitch_bend.nudge(0.5);
+        assert!((pitch_bend.current_rate_offset(Duration::ZERO) - 0.05).abs() < 1e-6);
+        // Limited by the maximum offset
+        pitch_bend.nudge(5.0);
+        assert_eq!(0.2, pitch_bend.current_rate_offset(Duration::ZERO));
+        // Decays linearly from the maximum offset to zero
+        let half_decay = PitchBend::DEFAULT_DECAY / 2;
+        assert!((pitch_bend.current_rate_offset(half_decay) - 0.1).abs() < 1e-6);
+        assert_eq!(0.0, pitch_bend.current_rate_offset(half_decay));
+        pitch_bend.nudge(-1.0);
+        assert!((pitch_bend.current_rate_offset(Duration::ZERO) + 0.1).abs() < 1e-6);
+        assert_eq!(
+            0.0,
+            pitch_bend.current_rate_offset(PitchBend::DEFAULT_DECAY)
+        );
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn scratch_engine() {
+        const DT: Duration = Duration::from_millis(500);
+        let params = PlaybackParams::default();
+        let mut playable = Playable {
+            play_state: PlayState::Playing,
+            duration: Some(Duration::from_secs(60)),
+        };
+        let mut playhead = Playhead::default();
+        let mut engine =
+            ScratchEngine::new(ScratchVelocityTracker::new(0.0, Duration::from_secs(1)))
+                .with_ease(DT * 2);
+        assert!(!engine.advance(&mut playhead, &playable, &params, DT));
+        // Brake when touched
+        playhead.is_playing = true;
+        engine.touch();
+        assert!(engine.advance(&mut playhead, &playable, &params, DT));
+        assert_eq!(0.0, playhead.position.offset_secs);
+        // Follow the jog wheel
+        engine.update(
+            TimeStamp::from_micros(0),
+            SliderEncoderInput {
+                delta: ScratchEngine::DEFAULT_REVS_PER_SEC * 2.0,
+            },
+        );
+        assert!(engine.advance(&mut playhead, &playable, &params, DT));
+        assert!((playhead.position.offset_secs - 1.0).abs() < 1e-6);
+        // Ease back to the tempo
+        engine.release();
+        assert!(engine.advance(&mut playhead, &playable, &params, DT));
+        assert!((playhead.position.offset_secs - 1.75).abs() < 1e-6);
+        assert!(!engine.advance(&mut playhead, &playable, &params, DT));
+        assert!(!engine.is_active());
+        playhead.advance(&mut playable, &params, DT);
+        assert!((playhead.position.offset_secs - 2.25).abs() < 1e-6);
+        // Scratch a stopped deck without modifying the tempo
+        playhead.is_playing = false;
+        engine.touch();
+        engine.update(
+            TimeStamp::from_micros(0),
+            SliderEncoderInput {
+                delta: -ScratchEngine::DEFAULT_REVS_PER_SEC,
+            },
+        );
+        assert!(engine.advance(&mut playhead, &playable, &params, DT));
+        assert!((playhead.position.offset_secs - 1.75).abs() < 1e-6);
+        engine.release();
+        assert!(engine.advance(&mut playhead, &playable, &params, DT));
+        assert!(!engine.advance(&mut playhead, &playable, &params, DT));
+        assert!(!playhead.is_playing);
+        assert_eq!(PLAYBACK_RATE_DEFAULT, params.rate);
+    }
+
+    #[test]
+    fn tap_tempo() {
+        let mut tap_tempo = TapTempo::new();
+        assert!(tap_tempo.bpm().is_none());
+        // 120 BPM with a single misplaced tap
+        for millis in [0, 500, 1000, 1500, 1700, 2500] {
+            tap_tempo.tap(TimeStamp::from_micros(millis * 1000));
+        }
+        assert!((tap_tempo.bpm().unwrap() - 120.0).abs() < 1e-3);
+        // Start over after a long gap
+        tap_tempo.tap(TimeStamp::from_micros(10_000_000));
+        assert!(tap_tempo.bpm().is_none());
+        tap_tempo.tap(TimeStamp::from_micros(10_400_000));
+        assert!((tap_tempo.bpm().unwrap() - 150.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn beat_grid() {
+        let beat_grid = BeatGrid {
+            first_beat_secs: 0.25,
+            bpm: 120.0,
+        };
+        let position = Position { offset_secs: 1.25 };
+        assert!((beat_grid.position_to_beats(position) - 2.0).abs() < 1e-9);
+        assert!((beat_grid.beats_to_position(2.0).offset_secs - 1.25).abs() < 1e-9);
+        let snapped = beat_grid.snap_to_beat(Position { offset_secs: 1.4 });
+        assert!((snapped.offset_secs - 1.25).abs() < 1e-9);
+        let snapped = beat_grid.snap_to_beat(Position { offset_secs: 0.1 });
+        assert!((snapped.offset_secs - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn deck_state_loop() {
+        let mut deck_state = DeckState::default();
+        assert!(!deck_state.reloop());
+        deck_state.update_playhead_position(Position { offset_secs: 1.0 });
+        deck_state.loop_in();
+        assert!(!deck_state.loop_out());
+        deck_state.update_playhead_position(Position { offset_secs: 3.0 });
+        assert!(deck_state.loop_out());
+        assert_eq!(LedState::BlinkFast, deck_state.pioneer_reloop_led_state());
+        let wrapped = deck_state.update_playhead_position(Position { offset_secs: 3.5 });
+        assert!((wrapped.offset_secs - 1.5).abs() < 1e-9);
+        assert!(deck_state.is_playhead_in_loop());
+        // Exit
+        assert!(deck_state.reloop());
+        assert!(!deck_state.is_playhead_in_loop());
+        assert_eq!(LedState::On, deck_state.pioneer_reloop_led_state());
+        let position = deck_state.update_playhead_position(Position { offset_secs: 3.5 });
+        assert!((position.offset_secs - 3.5).abs() < 1e-9);
+        // Reloop
+        assert!(deck_state.reloop());
+        assert_eq!(Position { offset_secs: 1.0 }, deck_state.playhead.position);
+    }
+
+    #[test]
+    fn play_state_transitions() {
+        let cue = Cue::default();
+        let on_cue = PlayState::Paused {
+            playhead_on_cue: true,
+        };
+        let state = on_cue.clone().on_cue_press(cue);
+        assert_eq!(PlayState::Previewing { cue }, state);
+        assert_eq!(on_cue, state.clone().on_cue_release());
+        // Continue playing while previewing
+        let state = state.on_play_pause();
+        assert_eq!(PlayState::Playing, state);
+        assert_eq!(PlayState::Playing, state.clone().on_cue_release());
+        assert_eq!(
+            PlayState::Paused {
+                playhead_on_cue: false
+            },
+            state.clone().on_play_pause()
+        );
+        assert_eq!(on_cue, state.on_cue_press(cue));
+        // Ended
+        assert_eq!(PlayState::Ended, PlayState::Ended.on_play_pause());
+        assert_eq!(on_cue, PlayState::Ended.on_cue_press(cue));
+    }
+
+    #[test]
+    fn play_state_led_states() {
+        use LedState::{BlinkFast, BlinkSlow, Off, On};
+        let cue = Cue {
+            position: Position { offset_secs: 1.0 },
+        };
+        // (state, Pioneer cue/play, Denon cue/play)
+        let table = [
+            (
+                PlayState::Paused {
+                    playhead_on_cue: true,
+                },
+                (On, BlinkSlow),
+                (On, Off),
+            ),
+            (
+                PlayState::Paused {
+                    playhead_on_cue: false,
+                },
+                (BlinkFast, BlinkSlow),
+                (BlinkSlow, Off),
+            ),
+            (PlayState::Previewing { cue }, (On, BlinkSlow), (On, On)),
+            (PlayState::Playing, (On, On), (Off, On)),
+            (PlayState::Ended, (Off, Off), (Off, Off)),
+        ];
+        for (state, pioneer, denon) in table {
+            assert_eq!(
+                pioneer,
+                (
+                    state.pioneer_cue_led_state(),
+                    state.pioneer_playpause_led_state()
+                ),
+                "{state:?}"
+            );
+            assert_eq!(
+                denon,
+                (
+                    state.denon_cue_led_state(),
+                    state.denon_playpause_led_state()
+                ),
+                "{state:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn playhead_advance() {
+        let mut playable = Playable {
+            play_state: PlayState::Playing,
+            duration: Some(Duration::from_secs(10)),
+        };
+        let mut playhead = Playhead {
+            position: Position { offset_secs: 1.0 },
+            is_playing: true,
+        };
+        let params = PlaybackParams {
+            rate: 2.0,
+            ..Default::default()
+        };
+        playhead.advance(&mut playable, &params, Duration::from_secs(2));
+        assert!((playhead.position.offset_secs - 5.0).abs() < 1e-9);
+        // Reverse until the start
+        let reverse = PlaybackParams {
+            rate: -1.0,
+            ..Default::default()
+        };
+        playhead.advance(&mut playable, &reverse, Duration::from_secs(8));
+        assert!(playhead.position.offset_secs.abs() < 1e-9);
+        assert_eq!(PlayState::Playing, playable.play_state);
+        // Clamped at the end
+        playhead.advance(&mut playable, &params, Duration::from_secs(6));
+        assert!((playhead.position.offset_secs - 10.0).abs() < 1e-9);
+        assert!(!playhead.is_playing);
+        assert_eq!(PlayState::Ended, playable.play_state);
+    }
+
+    #[test]
+    fn hot_cues() {
+        let mut hot_cues = HotCues::<4>::new();
+        let position = Position { offset_secs: 2.0 };
+        assert_eq!(None, hot_cues.set(1, position));
+        assert_eq!(Some(position), hot_cues.jump(1));
+        assert_eq!(None, hot_cues.jump(2));
+        let leds = hot_cues.virtual_leds();
+        assert_eq!(LedState::Off, leds[0].state);
+        assert_eq!(LedState::On, leds[1].state);
+        assert_eq!(Some(Cue { position }), hot_cues.clear(1));
+        assert_eq!(None, hot_cues.jump(1));
+        // Out of range
+        assert_eq!(None, hot_cues.set(4, position));
+        assert_eq!(None, hot_cues.get(4));
+        assert_eq!(None, hot_cues.clear(4));
+    }
+}
@@ -37,14 +37,6 @@ fn midi_status_to_deck_cmd(status: u8) -> (Deck, u8) {
 //     cmd | channel
 // }
 
-fn midi_value_to_button(data2: u8) -> ButtonInput {
-    match data2 {
-        0x00 => ButtonInput::Released,
-        0x40 => ButtonInput::Pressed,
-        _ => unreachable!(),
-    }
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq, FromRepr, EnumIter, EnumCount)]
 pub enum MainSensor {
     CrossfaderCenterSlider,
@@ -92,12 +84,15 @@ pub fn try_decode_midi_input(
     input: &[u8],
 ) -> Result<Option<(Sensor, ControlValue)>, MidiInputDecodeError> {
     let [status, data1, data2] = *input else {
-        return Err(MidiInputDecodeError);
+        return Err(MidiInputDecodeError::UnexpectedLength {
+            got: input.len(),
+            expected: 3,
+        });
     };
     let (deck, cmd) = midi_status_to_deck_cmd(status);
     let (sensor, value) = match cmd {
         MIDI_CMD_NOTE_OFF | MIDI_CMD_NOTE_ON => {
-            let input = midi_value_to_button(data2);
+            let input = ButtonInput::from_velocity(data2);
             debug_assert_eq!(cmd == MIDI_CMD_NOTE_ON, input == ButtonInput::Pressed);
             debug_assert_eq!(cmd == MIDI_CMD_NOTE_OFF, input == ButtonInput::Released);
             let sensor = match data1 {
@@ -106,7 +101,7 @@ pub fn try_decode_midi_input(
                 MIDI_DECK_PLAYPAUSE_BUTTON => Sensor::Deck(deck, DeckSensor::PlayPauseButton),
                 MIDI_DECK_SYNC_BUTTON => Sensor::Deck(deck, DeckSensor::SyncButton),
                 _ => {
-                    return Err(MidiInputDecodeError);
+                    return Err(MidiInputDecodeError::Unsupported);
                 }
             };
             (sensor, input.into())
@@ -161,7 +156,7 @@ pub fn try_decode_midi_input(
                 SliderInput::from_u7(data2).into(),
             ),
             _ => {
-                return Err(MidiInputDecodeError);
+                return Err(MidiInputDecodeError::Unsupported);
             }
         },
         0xe0 => (
@@ -171,7 +166,7 @@ pub fn try_decode_midi_input(
                 .into(),
         ),
         _ => {
-            return Err(MidiInputDecodeError);
+            return Err(MidiInputDecodeError::UnknownStatus(status));
         }
     };
     Ok(Some((sensor, value)))
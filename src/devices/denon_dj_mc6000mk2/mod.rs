@@ -6,7 +6,8 @@ use std::borrow::Cow;
 use strum::{EnumCount, EnumIter};
 
 use crate::{
-    AudioInterfaceDescriptor, ControllerDescriptor, DeviceDescriptor, MidiDeviceDescriptor,
+    AudioChannelDescriptor, AudioChannelRole, AudioInterfaceDescriptor, ControllerCapabilities,
+    ControllerDescriptor, DeviceDescriptor, MidiDeviceDescriptor, MidiPortNameMatching,
 };
 
 mod input;
@@ -15,9 +16,15 @@ pub use self::input::{try_decode_midi_input, DeckSensor, MainSensor, Sensor, Sid
 mod output;
 pub use self::output::OutputGateway;
 
+const AUDIO_CHANNELS: &[AudioChannelDescriptor] = &[
+    AudioChannelDescriptor::output(0, 2, AudioChannelRole::Main),
+    AudioChannelDescriptor::output(2, 2, AudioChannelRole::Headphones),
+];
+
 pub const AUDIO_INTERFACE_DESCRIPTOR: AudioInterfaceDescriptor = AudioInterfaceDescriptor {
     num_input_channels: 0, // TODO
     num_output_channels: 4,
+    channels: Cow::Borrowed(AUDIO_CHANNELS),
 };
 
 pub const MIDI_DEVICE_DESCRIPTOR: &MidiDeviceDescriptor = &MidiDeviceDescriptor {
@@ -27,6 +34,8 @@ pub const MIDI_DEVICE_DESCRIPTOR: &MidiDeviceDescriptor = &MidiDeviceDescriptor
         audio_interface: Some(AUDIO_INTERFACE_DESCRIPTOR),
     },
     port_name_prefix: "MC6000MK2",
+    port_name_matching: MidiPortNameMatching::StartsWith,
+    port_name_lenient: false,
 };
 
 pub const DEVICE_DESCRIPTOR: &DeviceDescriptor = &MIDI_DEVICE_DESCRIPTOR.device;
@@ -38,6 +47,7 @@ pub const CONTROLLER_DESCRIPTOR: &ControllerDescriptor = &ControllerDescriptor {
     num_mixer_channels: Deck::COUNT as u8,
     num_pads_per_deck: 4, // hot cues
     num_effect_units: 2,
+    capabilities: ControllerCapabilities::empty(),
 };
 
 #[derive(Debug, Clone, Copy, EnumIter, EnumCount)]
@@ -9,7 +9,10 @@ use crate::{
 #[allow(clippy::too_many_lines)]
 pub fn try_decode_midi_input(input: &[u8]) -> Result<Option<Control>, MidiInputDecodeError> {
     let [status, command, value] = *input else {
-        return Err(MidiInputDecodeError);
+        return Err(MidiInputDecodeError::UnexpectedLength {
+            got: input.len(),
+            expected: 3,
+        });
     };
     let index = ControlIndex::new(u32::from(status) << 7 | u32::from(command));
     let value = ControlValue::from_bits(u32::from(value));
@@ -1,7 +1,9 @@
 // SPDX-FileCopyrightText: The djio authors
 // SPDX-License-Identifier: MPL-2.0
 
-use strum::{EnumCount, EnumIter, FromRepr};
+use std::sync::LazyLock;
+
+use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator as _};
 
 use super::{
     Deck, CONTROL_INDEX_DECK_A, CONTROL_INDEX_DECK_B, CONTROL_INDEX_DECK_BIT_MASK,
@@ -19,18 +21,10 @@ use super::{
 };
 use crate::{
     ButtonInput, CenterSliderInput, Control, ControlIndex, ControlInputEvent, ControlValue,
-    MidiInputConnector, MidiInputDecodeError, SliderEncoderInput, SliderInput, StepEncoderInput,
-    TimeStamp,
+    InputControlDescriptor, InputControlKind, MidiInputConnector, MidiInputDecodeError,
+    SliderEncoderInput, SliderInput, StepEncoderInput, TimeStamp,
 };
 
-fn u7_to_button(input: u8) -> ButtonInput {
-    match input {
-        0x00 => ButtonInput::Released,
-        0x7f => ButtonInput::Pressed,
-        _ => unreachable!(),
-    }
-}
-
 #[derive(Debug, Clone, Copy, FromRepr, EnumIter, EnumCount)]
 #[repr(u8)]
 pub enum MainSensor {
@@ -52,6 +46,29 @@ pub enum MainSensor {
     ProgramKnobStepEncoder,
 }
 
+impl MainSensor {
+    #[must_use]
+    pub const fn kind(self) -> InputControlKind {
+        match self {
+            Self::BrowseKnobShiftButton
+            | Self::TapButton
+            | Self::TapHoldButton
+            | Self::TouchPadLowerLeftButton
+            | Self::TouchPadLowerRightButton
+            | Self::TouchPadModeButton
+            | Self::TouchPadUpperLeftButton
+            | Self::TouchPadUpperRightButton => InputControlKind::Button,
+            Self::CrossfaderCenterSlider
+            | Self::AudiolessMonitorLevelSlider
+            | Self::AudiolessMonitorBalanceSlider
+            | Self::AudiolessMasterLevelSlider
+            | Self::TouchPadXSlider
+            | Self::TouchPadYSlider => InputControlKind::Slider,
+            Self::BrowseKnobStepEncoder | Self::ProgramKnobStepEncoder => InputControlKind::Encoder,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, FromRepr, EnumIter, EnumCount)]
 #[repr(u8)]
 pub enum DeckSensor {
@@ -87,6 +104,25 @@ pub enum DeckSensor {
     TouchWheelSearchSliderEncoder,
 }
 
+impl DeckSensor {
+    #[must_use]
+    pub const fn kind(self) -> InputControlKind {
+        match self {
+            Self::VolumeFaderSlider
+            | Self::TouchStripSlider
+            | Self::GainKnobCenterSlider
+            | Self::EqHiKnobCenterSlider
+            | Self::EqLoKnobCenterSlider
+            | Self::EqMidKnobCenterSlider
+            | Self::PitchFaderCenterSlider => InputControlKind::Slider,
+            Self::TouchWheelBendSliderEncoder
+            | Self::TouchWheelScratchSliderEncoder
+            | Self::TouchWheelSearchSliderEncoder => InputControlKind::Encoder,
+            _ => InputControlKind::Button,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Sensor {
     Main(MainSensor),
@@ -117,6 +153,46 @@ impl Sensor {
             }
         }
     }
+
+    #[must_use]
+    pub const fn kind(self) -> InputControlKind {
+        match self {
+            Self::Main(sensor) => sensor.kind(),
+            Self::Deck(_, sensor) => sensor.kind(),
+        }
+    }
+
+    /// Human-readable name
+    #[must_use]
+    pub fn name(self) -> String {
+        match self {
+            Self::Main(sensor) => format!("{sensor:?}"),
+            Self::Deck(deck, sensor) => format!("Deck {deck:?} {sensor:?}"),
+        }
+    }
+
+    /// All sensors
+    pub fn iter() -> impl Iterator<Item = Self> {
+        MainSensor::iter().map(Self::Main).chain(
+            Deck::iter()
+                .flat_map(|deck| DeckSensor::iter().map(move |sensor| Self::Deck(deck, sensor))),
+        )
+    }
+}
+
+/// Descriptors of all sensors
+#[must_use]
+pub fn input_controls() -> &'static [InputControlDescriptor] {
+    static INPUT_CONTROLS: LazyLock<Vec<InputControlDescriptor>> = LazyLock::new(|| {
+        Sensor::iter()
+            .map(|sensor| InputControlDescriptor {
+                index: sensor.to_control_index(),
+                name: sensor.name().into(),
+                kind: sensor.kind(),
+            })
+            .collect()
+    });
+    &INPUT_CONTROLS
 }
 
 impl From<Sensor> for ControlIndex {
@@ -151,11 +227,14 @@ impl TryFrom<ControlIndex> for Sensor {
     }
 }
 
-fn midi_status_to_deck(status: u8) -> Deck {
+fn midi_status_to_deck(status: u8) -> Result<Deck, MidiInputDecodeError> {
     match status & 0xf {
-        MIDI_CHANNEL_DECK_A => Deck::A,
-        MIDI_CHANNEL_DECK_B => Deck::B,
-        _ => unreachable!("Unexpected MIDI status {status}"),
+        MIDI_CHANNEL_DECK_A => Ok(Deck::A),
+        MIDI_CHANNEL_DECK_B => Ok(Deck::B),
+        _ => {
+            log::debug!("Unexpected MIDI status {status}");
+            Err(MidiInputDecodeError::UnknownStatus(status))
+        }
     }
 }
 
@@ -165,7 +244,7 @@ pub fn try_decode_midi_input(
 ) -> Result<Option<(Sensor, ControlValue)>, MidiInputDecodeError> {
     let decoded = match *input {
         [MIDI_STATUS_BUTTON_MAIN, data1, data2] => {
-            let input = u7_to_button(data2);
+            let input = ButtonInput::from_velocity(data2);
             let sensor = match data1 {
                 0x07 => MainSensor::BrowseKnobShiftButton,
                 MIDI_TAP_BUTTON => MainSensor::TapButton,
@@ -176,14 +255,14 @@ pub fn try_decode_midi_input(
                 0x4c => MainSensor::TouchPadLowerLeftButton,
                 0x4d => MainSensor::TouchPadLowerRightButton,
                 _ => {
-                    return Err(MidiInputDecodeError);
+                    return Err(MidiInputDecodeError::Unsupported);
                 }
             };
             (sensor.into(), input.into())
         }
         [status @ (MIDI_STATUS_BUTTON_DECK_A | MIDI_STATUS_BUTTON_DECK_B), data1, data2] => {
-            let input = u7_to_button(data2);
-            let deck = midi_status_to_deck(status);
+            let input = ButtonInput::from_velocity(data2);
+            let deck = midi_status_to_deck(status)?;
             let sensor = match data1 {
                 0x0e => DeckSensor::LoadButton,
                 MIDI_DECK_TOUCHSTRIP_LOOP_LEFT_BUTTON => DeckSensor::TouchStripLoopLeftButton,
@@ -208,7 +287,7 @@ pub fn try_decode_midi_input(
                 0x2f => DeckSensor::SyncShiftButton,
                 0x30 => DeckSensor::CueShiftButton,
                 _ => {
-                    return Err(MidiInputDecodeError);
+                    return Err(MidiInputDecodeError::Unsupported);
                 }
             };
             (Sensor::Deck(deck, sensor), input.into())
@@ -262,11 +341,11 @@ pub fn try_decode_midi_input(
                 StepEncoderInput::from_u7(data2).into(),
             ),
             _ => {
-                return Err(MidiInputDecodeError);
+                return Err(MidiInputDecodeError::Unsupported);
             }
         },
         [status @ (MIDI_STATUS_CC_DECK_A | MIDI_STATUS_CC_DECK_B), data1, data2] => {
-            let deck = midi_status_to_deck(status);
+            let deck = midi_status_to_deck(status)?;
             let (sensor, value) = match data1 {
                 0x0e => (
                     DeckSensor::TouchWheelBendSliderEncoder,
@@ -309,13 +388,13 @@ pub fn try_decode_midi_input(
                     SliderInput::from_u7(data2).into(),
                 ),
                 _ => {
-                    return Err(MidiInputDecodeError);
+                    return Err(MidiInputDecodeError::Unsupported);
                 }
             };
             (Sensor::Deck(deck, sensor), value)
         }
         _ => {
-            return Err(MidiInputDecodeError);
+            return Err(MidiInputDecodeError::UnknownStatus(input[0]));
         }
     };
     Ok(Some(decoded))
@@ -358,3 +437,39 @@ impl MidiInputConnector for MidiInputEventDecoder {
         assert_eq!(device, MIDI_DEVICE_DESCRIPTOR);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        input_controls, try_decode_midi_input_event, MainSensor, Sensor, MIDI_STATUS_BUTTON_MAIN,
+    };
+    use crate::{ButtonInput, Control, ControlInputEvent, TimeStamp};
+
+    #[test]
+    fn input_controls_match_sensors() {
+        let input_controls = input_controls();
+        assert_eq!(Sensor::iter().count(), input_controls.len());
+        for input_control in input_controls {
+            let sensor = Sensor::try_from(input_control.index).unwrap();
+            assert_eq!(sensor.name(), input_control.name);
+            assert_eq!(sensor.kind(), input_control.kind);
+        }
+    }
+
+    #[test]
+    fn decode_button_with_unexpected_velocity() {
+        let ts = TimeStamp::from_micros(0);
+        let event =
+            try_decode_midi_input_event(ts, &[MIDI_STATUS_BUTTON_MAIN, 0x0b, 0x40]).unwrap();
+        assert_eq!(
+            Some(ControlInputEvent {
+                ts,
+                input: Control {
+                    index: Sensor::Main(MainSensor::TapButton).into(),
+                    value: ButtonInput::Pressed.into(),
+                },
+            }),
+            event
+        );
+    }
+}
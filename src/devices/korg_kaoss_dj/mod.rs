@@ -6,23 +6,31 @@ use std::borrow::Cow;
 use strum::{EnumCount, EnumIter, FromRepr};
 
 use crate::{
-    AudioInterfaceDescriptor, ControllerDescriptor, DeviceDescriptor, MidiDeviceDescriptor,
+    AudioChannelDescriptor, AudioChannelRole, AudioInterfaceDescriptor, ControllerCapabilities,
+    ControllerDescriptor, DeviceDescriptor, MidiDeviceDescriptor, MidiPortNameMatching,
 };
 
 mod input;
 pub use self::input::{
-    try_decode_midi_input, try_decode_midi_input_event, DeckSensor, InvalidInputControlIndex,
-    MainSensor, MidiInputEventDecoder, Sensor,
+    input_controls, try_decode_midi_input, try_decode_midi_input_event, DeckSensor,
+    InvalidInputControlIndex, MainSensor, MidiInputEventDecoder, Sensor,
 };
 
 mod output;
 pub use self::output::{
-    led_output_into_midi_message, DeckLed, InvalidOutputControlIndex, Led, MainLed, OutputGateway,
+    control_indices_for, led_output_into_midi_message, midi_message_into_led_output,
+    output_controls, DeckLed, InvalidOutputControlIndex, Led, MainLed, OutputGateway,
 };
 
+const AUDIO_CHANNELS: &[AudioChannelDescriptor] = &[
+    AudioChannelDescriptor::output(0, 2, AudioChannelRole::Main),
+    AudioChannelDescriptor::output(2, 2, AudioChannelRole::Headphones),
+];
+
 pub const AUDIO_INTERFACE_DESCRIPTOR: AudioInterfaceDescriptor = AudioInterfaceDescriptor {
     num_input_channels: 0,
     num_output_channels: 4,
+    channels: Cow::Borrowed(AUDIO_CHANNELS),
 };
 
 pub const MIDI_DEVICE_DESCRIPTOR: &MidiDeviceDescriptor = &MidiDeviceDescriptor {
@@ -32,6 +40,8 @@ pub const MIDI_DEVICE_DESCRIPTOR: &MidiDeviceDescriptor = &MidiDeviceDescriptor
         audio_interface: Some(AUDIO_INTERFACE_DESCRIPTOR),
     },
     port_name_prefix: "KAOSS DJ",
+    port_name_matching: MidiPortNameMatching::StartsWith,
+    port_name_lenient: false,
 };
 
 pub const DEVICE_DESCRIPTOR: &DeviceDescriptor = &MIDI_DEVICE_DESCRIPTOR.device;
@@ -43,6 +53,7 @@ pub const CONTROLLER_DESCRIPTOR: &ControllerDescriptor = &ControllerDescriptor {
     num_mixer_channels: Deck::COUNT as u8,
     num_pads_per_deck: 0,
     num_effect_units: 0,
+    capabilities: ControllerCapabilities::TOUCH_STRIP,
 };
 
 #[derive(Debug, Clone, Copy, FromRepr, EnumIter, EnumCount)]
@@ -1,6 +1,8 @@
 // SPDX-FileCopyrightText: The djio authors
 // SPDX-License-Identifier: MPL-2.0
 
+use std::sync::LazyLock;
+
 use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator as _};
 
 use super::{
@@ -18,7 +20,7 @@ use super::{
 };
 use crate::{
     Control, ControlIndex, ControlOutputGateway, LedOutput, MidiOutputConnection,
-    MidiOutputGateway, OutputError, OutputResult,
+    MidiOutputGateway, OutputControlDescriptor, OutputControlKind, OutputError, OutputResult,
 };
 
 const LED_OFF: u8 = 0x00;
@@ -107,6 +109,52 @@ impl Led {
             Self::Deck(deck, led) => ControlIndex::new(deck.control_index_bit_mask() | led as u32),
         }
     }
+
+    /// All LEDs of a deck
+    pub fn iter_deck(deck: Deck) -> impl Iterator<Item = Self> {
+        DeckLed::iter().map(move |led| Self::Deck(deck, led))
+    }
+
+    /// All LEDs
+    pub fn iter() -> impl Iterator<Item = Self> {
+        MainLed::iter()
+            .map(Self::Main)
+            .chain(Deck::iter().flat_map(Self::iter_deck))
+    }
+
+    /// Human-readable name
+    #[must_use]
+    pub fn name(self) -> String {
+        match self {
+            Self::Main(led) => format!("{led:?}"),
+            Self::Deck(deck, led) => format!("Deck {deck:?} {led:?}"),
+        }
+    }
+}
+
+/// Descriptors of all LEDs
+///
+/// All LEDs could only be switched on or off.
+#[must_use]
+pub fn output_controls() -> &'static [OutputControlDescriptor] {
+    static OUTPUT_CONTROLS: LazyLock<Vec<OutputControlDescriptor>> = LazyLock::new(|| {
+        Led::iter()
+            .map(|led| OutputControlDescriptor {
+                index: led.to_control_index(),
+                name: led.name().into(),
+                kind: OutputControlKind::OnOff,
+            })
+            .collect()
+    });
+    &OUTPUT_CONTROLS
+}
+
+/// Control indices of all LEDs of a deck
+///
+/// Allows to address the LEDs of a deck without knowing how
+/// the deck is encoded into the [`ControlIndex`].
+pub fn control_indices_for(deck: Deck) -> impl Iterator<Item = ControlIndex> {
+    Led::iter_deck(deck).map(Led::to_control_index)
 }
 
 impl From<MainLed> for Led {
@@ -190,6 +238,27 @@ pub const fn led_output_into_midi_message(led: Led, output: LedOutput) -> [u8; 3
     [status, data1, data2]
 }
 
+/// Inverse of [`led_output_into_midi_message()`]
+///
+/// Could be used for interpreting messages that are echoed back by the
+/// controller. Any non-zero value is interpreted as [`LedOutput::On`].
+#[must_use]
+pub fn midi_message_into_led_output(message: &[u8]) -> Option<(Led, LedOutput)> {
+    let [status, data1, data2] = *message else {
+        return None;
+    };
+    let led = Led::iter().find(|&led| {
+        let [led_status, led_data1, _] = led_output_into_midi_message(led, LedOutput::Off);
+        led_status == status && led_data1 == data1
+    })?;
+    let output = if data2 == LED_OFF {
+        LedOutput::Off
+    } else {
+        LedOutput::On
+    };
+    Some((led, output))
+}
+
 fn send_led_output<C: MidiOutputConnection>(
     midi_output_connection: &mut C,
     led: Led,
@@ -198,6 +267,19 @@ fn send_led_output<C: MidiOutputConnection>(
     midi_output_connection.send_midi_output(&led_output_into_midi_message(led, output))
 }
 
+fn send_led_outputs<C: MidiOutputConnection>(
+    midi_output_connection: &mut C,
+    leds: impl IntoIterator<Item = Led>,
+    output: LedOutput,
+) -> OutputResult<()> {
+    let messages = leds
+        .into_iter()
+        .map(|led| led_output_into_midi_message(led, output))
+        .collect::<Vec<_>>();
+    let messages = messages.iter().map(<[u8; 3]>::as_slice).collect::<Vec<_>>();
+    midi_output_connection.send_midi_outputs(&messages)
+}
+
 fn on_attach<C: MidiOutputConnection>(midi_output_connection: &mut C) -> OutputResult<()> {
     // MIDI SysEx message for querying the initial position of all knobs and faders
     const MIDI_STATUS_SYSEX: &[u8] = &[
@@ -233,15 +315,7 @@ fn on_detach<C: MidiOutputConnection>(midi_output_connection: &mut C) -> OutputR
     // First send a MIDI system reset message
     midi_output_connection.send_midi_system_reset()?;
     // Turn off all LEDs
-    for led in MainLed::iter() {
-        send_led_output(midi_output_connection, led.into(), LedOutput::Off)?;
-    }
-    for deck in Deck::iter() {
-        for led in DeckLed::iter() {
-            send_led_output(midi_output_connection, Led::Deck(deck, led), LedOutput::Off)?;
-        }
-    }
-    Ok(())
+    send_led_outputs(midi_output_connection, Led::iter(), LedOutput::Off)
 }
 
 #[derive(Debug)]
@@ -265,14 +339,23 @@ impl<C: MidiOutputConnection> OutputGateway<C> {
         };
         send_led_output(midi_output_connection, led, output)
     }
+
+    /// Reset the hardware into a defined state without detaching it
+    ///
+    /// Turns off all LEDs like when detaching the MIDI output connection.
+    pub fn reset(&mut self) -> OutputResult<()> {
+        let Some(midi_output_connection) = &mut self.midi_output_connection else {
+            return Err(OutputError::Disconnected);
+        };
+        on_detach(midi_output_connection)
+    }
 }
 
 impl<C: MidiOutputConnection> ControlOutputGateway for OutputGateway<C> {
     fn send_output(&mut self, output: &Control) -> OutputResult<()> {
         let Control { index, value } = *output;
-        let led = Led::try_from(index).map_err(|InvalidOutputControlIndex| OutputError::Send {
-            msg: format!("No LED with control index {index}").into(),
-        })?;
+        let led = Led::try_from(index)
+            .map_err(|InvalidOutputControlIndex| OutputError::UnknownControl(index))?;
         self.send_led_output(led, value.into())
     }
 }
@@ -301,3 +384,125 @@ impl<C: MidiOutputConnection> MidiOutputGateway<C> for OutputGateway<C> {
         Some(midi_output_connection)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use strum::{EnumCount as _, IntoEnumIterator as _};
+
+    use super::{
+        control_indices_for, led_output_into_midi_message, midi_message_into_led_output,
+        output_controls, DeckLed, Led, MainLed, OutputGateway,
+    };
+    use crate::{
+        devices::korg_kaoss_dj::Deck, CaptureMidiOutput, Control, ControlIndex,
+        ControlOutputGateway as _, LedOutput, LoopbackMidi, MidiInputHandler,
+        MidiOutputGateway as _, OutputError, TimeStamp,
+    };
+
+    #[test]
+    fn midi_message_round_trip() {
+        for led in Led::iter() {
+            for output in [LedOutput::Off, LedOutput::On] {
+                let message = led_output_into_midi_message(led, output);
+                let (decoded_led, decoded_output) = midi_message_into_led_output(&message).unwrap();
+                assert_eq!(led.to_control_index(), decoded_led.to_control_index());
+                assert_eq!(output, decoded_output);
+            }
+        }
+    }
+
+    #[test]
+    fn output_controls_match_leds() {
+        let output_controls = output_controls();
+        assert_eq!(Led::iter().count(), output_controls.len());
+        for output_control in output_controls {
+            let led = Led::try_from(output_control.index).unwrap();
+            assert_eq!(led.name(), output_control.name);
+        }
+    }
+
+    #[test]
+    fn attach_and_detach() {
+        let num_leds = MainLed::COUNT + Deck::iter().count() * DeckLed::COUNT;
+        let mut gateway = OutputGateway::default();
+        let mut connection = Some(CaptureMidiOutput::new());
+        gateway
+            .attach_midi_output_connection(&mut connection)
+            .unwrap();
+        let mut capture = gateway.detach_midi_output_connection().unwrap();
+        let messages = capture.take_messages();
+        let (attach_messages, detach_messages) = messages.split_at(num_leds + 2);
+        // System reset
+        assert_eq!([0xff], attach_messages[0].as_slice());
+        // Query the initial position of all knobs and faders
+        assert_eq!(Some(&0xf0), attach_messages[num_leds + 1].first());
+        assert_eq!(Some(&0xf7), attach_messages[num_leds + 1].last());
+        assert_eq!([0xff], detach_messages[0].as_slice());
+        // All LEDs are turned off
+        assert_eq!(num_leds + 1, detach_messages.len());
+        assert!(detach_messages[1..].iter().all(|msg| msg[2] == 0x00));
+    }
+
+    #[test]
+    fn loopback_led_outputs() {
+        #[derive(Default)]
+        struct LedEcho(Vec<(Led, LedOutput)>);
+
+        impl MidiInputHandler for LedEcho {
+            fn handle_midi_input(&mut self, _ts: TimeStamp, input: &[u8]) -> bool {
+                let Some(led_output) = midi_message_into_led_output(input) else {
+                    return false;
+                };
+                self.0.push(led_output);
+                true
+            }
+        }
+
+        let loopback = LoopbackMidi::new();
+        let mut gateway = OutputGateway::default();
+        gateway
+            .attach_midi_output_connection(&mut Some(loopback.output()))
+            .unwrap();
+        // Discard all messages that have been sent when attaching
+        loopback.deliver(TimeStamp::from_micros(0), &mut LedEcho::default());
+        assert_eq!(0, loopback.pending());
+        let led = Led::Deck(Deck::B, DeckLed::PlayPauseButton);
+        gateway
+            .send_output(&Control {
+                index: led.to_control_index(),
+                value: LedOutput::On.into(),
+            })
+            .unwrap();
+        loopback.feed(&[0xf8]);
+        let mut echo = LedEcho::default();
+        assert_eq!(1, loopback.deliver(TimeStamp::from_micros(1), &mut echo));
+        let [(echoed_led, LedOutput::On)] = echo.0.as_slice() else {
+            panic!("unexpected echo: {:?}", echo.0);
+        };
+        assert_eq!(led.to_control_index(), echoed_led.to_control_index());
+    }
+
+    #[test]
+    fn unknown_control() {
+        let mut gateway = OutputGateway::default();
+        gateway
+            .attach_midi_output_connection(&mut Some(CaptureMidiOutput::new()))
+            .unwrap();
+        let index = ControlIndex::new(u32::MAX);
+        let result = gateway.send_output(&Control {
+            index,
+            value: LedOutput::On.into(),
+        });
+        assert!(matches!(result, Err(OutputError::UnknownControl(unknown)) if unknown == index));
+    }
+
+    #[test]
+    fn control_indices_for_deck() {
+        for deck in [Deck::A, Deck::B] {
+            for index in control_indices_for(deck) {
+                let led = Led::try_from(index).unwrap();
+                assert_eq!(Some(deck as u8), led.deck().map(|deck| deck as u8));
+            }
+        }
+    }
+}
@@ -0,0 +1,326 @@
+// SPDX-FileCopyrightText: The djio authors
+// SPDX-License-Identifier: MPL-2.0
+
+use std::{borrow::Cow, sync::mpsc};
+
+use hidapi::DeviceInfo;
+
+use crate::{
+    hid::{
+        report::{BufferRecycler, ReportStats},
+        thread::{
+            Command, CommandDisconnected, CommandReceiver, Environment, Event, EventHandler,
+            HidThreadConfig, JoinedThread, ReceiveCommandResult,
+        },
+    },
+    AudioChannelDescriptor, AudioChannelRole, AudioInterfaceDescriptor, ControllerCapabilities,
+    ControllerDescriptor, DeviceDescriptor, HidDevice, HidDeviceError, HidResult, HidThread,
+    MonotonicClock,
+};
+
+const AUDIO_CHANNELS: &[AudioChannelDescriptor] = &[
+    AudioChannelDescriptor::output(0, 2, AudioChannelRole::Main),
+    AudioChannelDescriptor::output(2, 2, AudioChannelRole::Headphones),
+    AudioChannelDescriptor::input(0, 2, AudioChannelRole::Microphone),
+];
+
+pub const AUDIO_INTERFACE_DESCRIPTOR: AudioInterfaceDescriptor = AudioInterfaceDescriptor {
+    num_input_channels: 2, // Microphone
+    num_output_channels: 4,
+    channels: Cow::Borrowed(AUDIO_CHANNELS),
+};
+
+pub const DEVICE_DESCRIPTOR: &DeviceDescriptor = &DeviceDescriptor {
+    vendor_name: Cow::Borrowed("Native Instruments"),
+    product_name: Cow::Borrowed("TRAKTOR KONTROL S2MK3"),
+    audio_interface: Some(AUDIO_INTERFACE_DESCRIPTOR),
+};
+
+pub const CONTROLLER_DESCRIPTOR: ControllerDescriptor = ControllerDescriptor {
+    num_decks: 2,
+    num_virtual_decks: 2,
+    num_mixer_channels: 2,
+    num_pads_per_deck: 4,
+    num_effect_units: 1,
+    capabilities: ControllerCapabilities::VU_METERS,
+};
+
+struct ThreadContext {
+    command_rx: mpsc::Receiver<Command>,
+    recycle_report_buffer_tx: mpsc::Sender<Vec<u8>>,
+    report_stats_by_id: Vec<ReportStats>,
+}
+
+impl ThreadContext {
+    fn recycle_report_buffer(&self, buf: Vec<u8>) {
+        if let Err(err) = self.recycle_report_buffer_tx.send(buf) {
+            // Should never happen
+            log::error!(
+                "Failed to submit buffer for recycling: {buf:?}",
+                buf = err.0
+            );
+        }
+    }
+}
+
+impl CommandReceiver for ThreadContext {
+    fn try_recv_command(&mut self) -> ReceiveCommandResult {
+        match self.command_rx.try_recv() {
+            Ok(command) => Ok(Some(command)),
+            Err(mpsc::TryRecvError::Empty) => Ok(None),
+            Err(mpsc::TryRecvError::Disconnected) => Err(CommandDisconnected),
+        }
+    }
+}
+
+impl EventHandler for ThreadContext {
+    fn handle_event(&mut self, event: Event<'_>) {
+        match event {
+            Event::StateChanged(state) => {
+                log::info!("Thread state changed: {state:?}");
+            }
+            Event::Disconnected => {
+                log::warn!("Command channel disconnected unexpectedly");
+            }
+            Event::CommandPanicked { panic: _ } => {
+                log::error!("Failed to handle command");
+            }
+            Event::FeatureReportRead {
+                ts: _,
+                buf,
+                buf_len,
+            } => {
+                log::info!(
+                    "TODO: Handle feature report: {data:?}",
+                    data = &buf[..buf_len]
+                );
+            }
+            Event::FeatureReportReadError { ts: _, buf: _, err } => {
+                log::warn!("Failed to read feature report: {err}");
+            }
+            Event::ScheduledFeatureReportRead { ts: _, data } => {
+                log::info!("TODO: Handle scheduled feature report: {data:?}");
+            }
+            Event::ScheduledFeatureReportReadError {
+                ts: _,
+                report_id,
+                err,
+            } => {
+                log::warn!("Failed to read feature report (id = {report_id}): {err}");
+            }
+            Event::ReportRead { ts, data } => {
+                let report_id = data[0];
+                let report_stats = self
+                    .report_stats_by_id
+                    .get_mut(usize::from(report_id))
+                    .unwrap();
+                let (_count, duration_since_last_report) = report_stats.update(ts);
+                let stats_suffix = duration_since_last_report
+                    .map(|duration| {
+                        format!(
+                            " (\u{0394} = {millis:0.3} ms)",
+                            millis = duration.as_secs_f64() * 1_000.0
+                        )
+                    })
+                    .unwrap_or_default();
+                log::info!("TODO: Handle report{stats_suffix}: {data:?}");
+            }
+            Event::ReportReadError { ts: _, err } => {
+                log::warn!("Failed to read report: {err}");
+            }
+            Event::DeviceDisconnected { err } => {
+                log::warn!("Device disconnected: {err}");
+            }
+            Event::ReportWritten {
+                buf,
+                buf_len: _,
+                bytes_written: _,
+            } => {
+                self.recycle_report_buffer(buf);
+            }
+            Event::FeatureReportWritten { buf: _, buf_len: _ } => {
+                // Buffers of feature reports are not recycled
+            }
+            Event::ReportWriteError {
+                buf: _,
+                buf_len: _,
+                err,
+            } => {
+                log::error!("Failed to write report: {err}");
+                // Buffers of feature reports are not recycled
+            }
+            Event::ReportWriteExpired {
+                buf,
+                buf_len: _,
+                deadline: _,
+            } => {
+                log::warn!("Deadline for writing report expired");
+                self.recycle_report_buffer(buf);
+            }
+            Event::FeatureReportWriteError {
+                buf,
+                buf_len: _,
+                err,
+            } => {
+                log::error!("Failed to write feature report: {err}");
+                self.recycle_report_buffer(buf);
+            }
+        }
+    }
+}
+
+#[allow(missing_debug_implementations)]
+pub struct DeviceContext {
+    info: DeviceInfo,
+    thread: HidThread<ThreadContext>,
+    command_tx: mpsc::Sender<Command>,
+    recycle_report_buffer_rx: mpsc::Receiver<Vec<u8>>,
+    report_buffer_recycler: BufferRecycler,
+}
+
+impl DeviceContext {
+    #[must_use]
+    pub const fn vendor_id() -> u16 {
+        0x17cc
+    }
+
+    #[must_use]
+    pub const fn product_id() -> u16 {
+        0x1710
+    }
+
+    #[must_use]
+    pub fn is_supported(device_info: &DeviceInfo) -> bool {
+        device_info.vendor_id() == Self::vendor_id()
+            && device_info.product_id() == Self::product_id()
+    }
+
+    pub fn attach(connected_device: HidDevice) -> HidResult<DeviceContext> {
+        if !Self::is_supported(connected_device.info()) {
+            return Err(HidDeviceError::NotSupported.into());
+        }
+        if !connected_device.is_connected() {
+            return Err(HidDeviceError::NotConnected.into());
+        }
+        let (command_tx, command_rx) = mpsc::channel::<Command>();
+        let (recycle_report_buffer_tx, recycle_report_buffer_rx) = mpsc::channel::<Vec<u8>>();
+        let thread_context = ThreadContext {
+            command_rx,
+            recycle_report_buffer_tx,
+            // One slot per report id
+            report_stats_by_id: std::iter::repeat_n(
+                ReportStats::default(),
+                usize::from(u8::MAX) + 1,
+            )
+            .collect(),
+        };
+        let info = connected_device.info().clone();
+        let environment = Environment {
+            connected_device,
+            clock: MonotonicClock::new(),
+            context: thread_context,
+        };
+        log::info!("Spawning HID I/O thread");
+        let thread = HidThread::spawn(environment, HidThreadConfig::default())?;
+        Ok(DeviceContext {
+            info,
+            thread,
+            command_tx,
+            recycle_report_buffer_rx,
+            report_buffer_recycler: BufferRecycler::new(),
+        })
+    }
+
+    #[allow(clippy::missing_panics_doc)] // Never panics
+    pub fn detach(self) -> HidResult<HidDevice> {
+        log::info!("Terminating I/O thread");
+        self.command_tx
+            .send(Command::Terminate)
+            .expect("command channel to I/O thread closed unexpectedly");
+        log::info!("Joining I/O thread");
+        let joined_thread = self.thread.join();
+        match joined_thread {
+            JoinedThread::Terminated(terminated_thread) => {
+                // The device is still connected after the thread terminated.
+                let connected_device = terminated_thread.context.connected_device;
+                debug_assert!(connected_device.is_connected());
+                Ok(connected_device)
+            }
+            JoinedThread::JoinError(err) => {
+                Err(anyhow::anyhow!("Joining the I/O thread failed: {err:?}").into())
+            }
+        }
+    }
+
+    #[must_use]
+    pub const fn info(&self) -> &DeviceInfo {
+        &self.info
+    }
+
+    /// Initialization sequence
+    ///
+    /// Should be invoked once after attaching the device.
+    ///
+    /// Unlike the S4MK3 the jog wheels do not need to be activated
+    /// explicitly. Only resets all LEDs into a defined state.
+    pub fn initialize(&mut self) {
+        self.turn_off_leds();
+    }
+
+    /// Finalization sequence
+    ///
+    /// Should be invoked once before detaching the device.
+    pub fn finalize(&mut self) {
+        self.turn_off_leds();
+    }
+
+    fn turn_off_leds(&mut self) {
+        // Turn off button LEDs.
+        let mut data = [0; 63];
+        data[0] = 128; // report id
+        self.write_report(&data);
+        // Turn off meter LEDs.
+        let mut data = [0; 33];
+        data[0] = 129; // report id
+        self.write_report(&data);
+    }
+
+    /// Recycle queued buffers on demand.
+    ///
+    /// Could be invoked periodically during idle times before actually
+    /// writing the next report. Avoids delaying the write request that
+    /// would otherwise first recycle all queued buffers by invoking this
+    /// function.
+    pub fn recycle_queued_buffers(&mut self) {
+        while let Some(buf) = match self.recycle_report_buffer_rx.try_recv() {
+            Ok(buf) => Some(buf),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                // Should never happen during regular operation
+                log::warn!("Failed to receive recycled buffer from i/o thread");
+                None
+            }
+        } {
+            self.report_buffer_recycler.recycle_buf(buf);
+        }
+    }
+
+    pub fn write_report(&mut self, data: &[u8]) {
+        self.recycle_queued_buffers();
+        let buf = self.report_buffer_recycler.fill_buf(data);
+        let buf_len = buf.len();
+        let cmd = Command::WriteReport {
+            buf,
+            buf_len,
+            deadline: None,
+        };
+        self.submit_command(cmd);
+    }
+
+    pub fn submit_command(&self, cmd: Command) {
+        if let Err(err) = self.command_tx.send(cmd) {
+            // Should never happen during regular operation
+            log::warn!("Failed to submit command: {cmd:?}", cmd = err.0);
+        }
+    }
+}
@@ -0,0 +1,418 @@
+// SPDX-FileCopyrightText: The djio authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Decoding of input reports
+//!
+//! All byte offsets refer to the report data without the leading
+//! report id.
+
+use derive_more::From;
+use strum::{EnumCount, EnumIter, FromRepr};
+
+use super::{
+    Deck, MixerChannel, CONTROL_INDEX_DECK_BIT_MASK, CONTROL_INDEX_DECK_ONE,
+    CONTROL_INDEX_DECK_TWO, CONTROL_INDEX_ENUM_BIT_MASK, CONTROL_INDEX_MIXER_CHANNEL_BIT_MASK,
+    CONTROL_INDEX_MIXER_CHANNEL_SHIFT,
+};
+use crate::{
+    ButtonInput, CenterSliderInput, Control, ControlIndex, ControlInputEvent, ControlValue,
+    SliderEncoderInput, SliderInput, TimeStamp,
+};
+
+/// Report with the state of all buttons
+pub const BUTTON_REPORT_ID: u8 = 1;
+
+/// Report with the positions of all faders and knobs
+pub const ANALOG_REPORT_ID: u8 = 2;
+
+/// Report with the positions of both jog wheels
+pub const JOG_WHEEL_REPORT_ID: u8 = 3;
+
+/// Maximum value of the 12-bit faders and knobs
+const ANALOG_MAX_VALUE: u16 = 4095;
+
+/// Number of absolute jog wheel positions per revolution
+const JOG_WHEEL_POSITIONS_PER_REV: u16 = 2880;
+
+#[derive(Debug, Clone, Copy, From)]
+pub enum Sensor {
+    Main(MainSensor),
+    Deck(Deck, DeckSensor),
+    Mixer(MixerChannel, MixerSensor),
+}
+
+#[derive(Debug, Clone, Copy, FromRepr, EnumIter, EnumCount)]
+#[repr(u8)]
+pub enum MainSensor {
+    CrossfaderCenterSlider,
+}
+
+#[derive(Debug, Clone, Copy, FromRepr, EnumIter, EnumCount)]
+#[repr(u8)]
+pub enum DeckSensor {
+    PlayPauseButton,
+    CueButton,
+    SyncButton,
+    JogWheelEncoder,
+}
+
+#[derive(Debug, Clone, Copy, FromRepr, EnumIter, EnumCount)]
+#[repr(u8)]
+pub enum MixerSensor {
+    LevelFader,
+}
+
+impl Sensor {
+    #[must_use]
+    pub const fn deck(self) -> Option<Deck> {
+        match self {
+            Self::Deck(deck, _) => Some(deck),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn to_control_index(self) -> ControlIndex {
+        match self {
+            Self::Main(sensor) => ControlIndex::new(sensor as u32),
+            Self::Deck(deck, sensor) => {
+                ControlIndex::new(deck.control_index_bit_mask() | sensor as u32)
+            }
+            Self::Mixer(channel, sensor) => {
+                ControlIndex::new(channel.control_index_bit_mask() | sensor as u32)
+            }
+        }
+    }
+}
+
+impl From<Sensor> for ControlIndex {
+    fn from(from: Sensor) -> Self {
+        from.to_control_index()
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidInputControlIndex;
+
+impl TryFrom<ControlIndex> for Sensor {
+    type Error = InvalidInputControlIndex;
+
+    fn try_from(from: ControlIndex) -> Result<Self, Self::Error> {
+        let value = from.value();
+        debug_assert!(CONTROL_INDEX_ENUM_BIT_MASK <= u8::MAX.into());
+        let enum_index = (value & CONTROL_INDEX_ENUM_BIT_MASK) as u8;
+        let mixer_channel_bits = value & CONTROL_INDEX_MIXER_CHANNEL_BIT_MASK;
+        if mixer_channel_bits != 0 {
+            if value & CONTROL_INDEX_DECK_BIT_MASK != 0 {
+                return Err(InvalidInputControlIndex);
+            }
+            #[allow(clippy::cast_possible_truncation)]
+            let channel = MixerChannel::from_repr(
+                (mixer_channel_bits >> CONTROL_INDEX_MIXER_CHANNEL_SHIFT) as u8 - 1,
+            )
+            .ok_or(InvalidInputControlIndex)?;
+            return MixerSensor::from_repr(enum_index)
+                .map(|sensor| Sensor::Mixer(channel, sensor))
+                .ok_or(InvalidInputControlIndex);
+        }
+        let deck = match value & CONTROL_INDEX_DECK_BIT_MASK {
+            CONTROL_INDEX_DECK_ONE => Deck::One,
+            CONTROL_INDEX_DECK_TWO => Deck::Two,
+            CONTROL_INDEX_DECK_BIT_MASK => return Err(InvalidInputControlIndex),
+            _ => {
+                return MainSensor::from_repr(enum_index)
+                    .map(Sensor::Main)
+                    .ok_or(InvalidInputControlIndex);
+            }
+        };
+        DeckSensor::from_repr(enum_index)
+            .map(|sensor| Sensor::Deck(deck, sensor))
+            .ok_or(InvalidInputControlIndex)
+    }
+}
+
+/// Location of a button in [`BUTTON_REPORT_ID`] as (byte offset, bit)
+const BUTTONS: &[(Deck, DeckSensor, usize, u8)] = &[
+    (Deck::One, DeckSensor::PlayPauseButton, 4, 0),
+    (Deck::One, DeckSensor::CueButton, 4, 1),
+    (Deck::One, DeckSensor::SyncButton, 5, 5),
+    (Deck::Two, DeckSensor::PlayPauseButton, 13, 0),
+    (Deck::Two, DeckSensor::CueButton, 13, 1),
+    (Deck::Two, DeckSensor::SyncButton, 14, 5),
+];
+
+/// Byte offsets of the 16-bit little-endian values in [`ANALOG_REPORT_ID`]
+const ANALOG_SENSORS: &[(Sensor, usize)] = &[
+    (Sensor::Main(MainSensor::CrossfaderCenterSlider), 0),
+    (Sensor::Mixer(MixerChannel::One, MixerSensor::LevelFader), 2),
+    (Sensor::Mixer(MixerChannel::Two, MixerSensor::LevelFader), 4),
+    (
+        Sensor::Mixer(MixerChannel::Three, MixerSensor::LevelFader),
+        6,
+    ),
+    (
+        Sensor::Mixer(MixerChannel::Four, MixerSensor::LevelFader),
+        8,
+    ),
+];
+
+/// Byte offsets of the 16-bit little-endian absolute positions
+/// in [`JOG_WHEEL_REPORT_ID`]
+const JOG_WHEELS: &[(Deck, usize)] = &[(Deck::One, 15), (Deck::Two, 43)];
+
+fn read_u16_le(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u12_to_slider(input: u16) -> SliderInput {
+    let position = f32::from(input.min(ANALOG_MAX_VALUE)) / f32::from(ANALOG_MAX_VALUE);
+    debug_assert!(SliderInput::POSITION_RANGE.contains(&position));
+    SliderInput { position }
+}
+
+fn u12_to_center_slider(input: u16) -> CenterSliderInput {
+    let SliderInput { position } = u12_to_slider(input);
+    let position = position * 2.0 - 1.0;
+    debug_assert!(CenterSliderInput::POSITION_RANGE.contains(&position));
+    CenterSliderInput { position }
+}
+
+/// Map the difference between two absolute jog wheel positions
+/// onto the shortest path, i.e. half a revolution in either direction.
+fn jog_wheel_delta(last_position: u16, position: u16) -> SliderEncoderInput {
+    let positions_per_rev = i32::from(JOG_WHEEL_POSITIONS_PER_REV);
+    let mut diff = (i32::from(position) - i32::from(last_position)).rem_euclid(positions_per_rev);
+    if diff > positions_per_rev / 2 {
+        diff -= positions_per_rev;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let delta = diff as f32 / positions_per_rev as f32;
+    SliderEncoderInput { delta }
+}
+
+/// Stateful decoder for input reports
+///
+/// Only emits events for controls that have changed since
+/// the previous report with the same id.
+#[derive(Debug, Clone, Default)]
+pub struct InputReportDecoder {
+    // State from the previous reports
+    button_data: Option<Vec<u8>>,
+    analog_values: [Option<u16>; ANALOG_SENSORS.len()],
+    jog_wheel_positions: [Option<u16>; JOG_WHEELS.len()],
+}
+
+impl InputReportDecoder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the state of all previous reports
+    ///
+    /// The next reports are decoded as if they were the first ones,
+    /// e.g. after reconnecting the device.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Decode an input report into control input events
+    ///
+    /// The `data` must not contain the leading report id. Unknown
+    /// reports are ignored and result in no events.
+    #[must_use]
+    pub fn decode_report(
+        &mut self,
+        ts: TimeStamp,
+        report_id: u8,
+        data: &[u8],
+    ) -> Vec<ControlInputEvent> {
+        let mut events = Vec::new();
+        let mut emit = |sensor: Sensor, value: ControlValue| {
+            log::debug!("{sensor:?} {value}");
+            let input = Control {
+                index: sensor.into(),
+                value,
+            };
+            events.push(ControlInputEvent { ts, input });
+        };
+        match report_id {
+            BUTTON_REPORT_ID => {
+                for &(deck, sensor, offset, bit) in BUTTONS {
+                    let Some(byte) = data.get(offset) else {
+                        continue;
+                    };
+                    let mask = 1 << bit;
+                    let pressed = byte & mask != 0;
+                    if let Some(last_byte) = self
+                        .button_data
+                        .as_ref()
+                        .and_then(|last_data| last_data.get(offset))
+                    {
+                        if (last_byte & mask != 0) == pressed {
+                            continue;
+                        }
+                    }
+                    let input = if pressed {
+                        ButtonInput::Pressed
+                    } else {
+                        ButtonInput::Released
+                    };
+                    emit(Sensor::Deck(deck, sensor), input.into());
+                }
+                self.button_data = Some(data.to_vec());
+            }
+            ANALOG_REPORT_ID => {
+                for (&(sensor, offset), last_value) in
+                    ANALOG_SENSORS.iter().zip(&mut self.analog_values)
+                {
+                    let Some(value) = read_u16_le(data, offset) else {
+                        continue;
+                    };
+                    if *last_value == Some(value) {
+                        continue;
+                    }
+                    *last_value = Some(value);
+                    let value = match sensor {
+                        Sensor::Main(MainSensor::CrossfaderCenterSlider) => {
+                            u12_to_center_slider(value).into()
+                        }
+                        _ => u12_to_slider(value).into(),
+                    };
+                    emit(sensor, value);
+                }
+            }
+            JOG_WHEEL_REPORT_ID => {
+                for (&(deck, offset), last_position) in
+                    JOG_WHEELS.iter().zip(&mut self.jog_wheel_positions)
+                {
+                    let Some(position) = read_u16_le(data, offset) else {
+                        continue;
+                    };
+                    if position >= JOG_WHEEL_POSITIONS_PER_REV {
+                        log::debug!("Invalid jog wheel position: {position}");
+                        continue;
+                    }
+                    let Some(last) = last_position.replace(position) else {
+                        // The first report only initializes the position.
+                        continue;
+                    };
+                    if last == position {
+                        continue;
+                    }
+                    let input = jog_wheel_delta(last, position);
+                    emit(
+                        Sensor::Deck(deck, DeckSensor::JogWheelEncoder),
+                        input.into(),
+                    );
+                }
+            }
+            _ => (),
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Deck, DeckSensor, InputReportDecoder, MainSensor, MixerChannel, MixerSensor, Sensor,
+        ANALOG_REPORT_ID, BUTTON_REPORT_ID, JOG_WHEEL_REPORT_ID,
+    };
+    use crate::{
+        ButtonInput, CenterSliderInput, ControlIndex, ControlInputEvent, SliderEncoderInput,
+        SliderInput, TimeStamp,
+    };
+
+    const TS: TimeStamp = TimeStamp::from_micros(0);
+
+    fn control_indices(events: &[ControlInputEvent]) -> Vec<ControlIndex> {
+        events.iter().map(|event| event.input.index).collect()
+    }
+
+    #[test]
+    fn decode_transport_buttons() {
+        let mut decoder = InputReportDecoder::new();
+        let mut data = [0; 15];
+        // The initial report emits the state of all buttons
+        let events = decoder.decode_report(TS, BUTTON_REPORT_ID, &data);
+        assert_eq!(6, events.len());
+        assert!(events
+            .iter()
+            .all(|event| ButtonInput::from(event.input.value) == ButtonInput::Released));
+        assert!(decoder
+            .decode_report(TS, BUTTON_REPORT_ID, &data)
+            .is_empty());
+        // Play/pause of the left deck and sync of the right deck
+        data[4] = 0b0000_0001;
+        data[14] = 0b0010_0000;
+        let events = decoder.decode_report(TS, BUTTON_REPORT_ID, &data);
+        assert_eq!(
+            vec![
+                Sensor::Deck(Deck::One, DeckSensor::PlayPauseButton).to_control_index(),
+                Sensor::Deck(Deck::Two, DeckSensor::SyncButton).to_control_index(),
+            ],
+            control_indices(&events)
+        );
+        assert!(events
+            .iter()
+            .all(|event| ButtonInput::from(event.input.value) == ButtonInput::Pressed));
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn decode_faders() {
+        let mut decoder = InputReportDecoder::new();
+        // Crossfader left, all level faders closed except for channel 1
+        let mut data = [0x00, 0x00, 0xff, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let events = decoder.decode_report(TS, ANALOG_REPORT_ID, &data);
+        assert_eq!(5, events.len());
+        assert_eq!(
+            Sensor::Main(MainSensor::CrossfaderCenterSlider).to_control_index(),
+            events[0].input.index
+        );
+        assert_eq!(
+            -1.0,
+            CenterSliderInput::from(events[0].input.value).position
+        );
+        assert_eq!(1.0, SliderInput::from(events[1].input.value).position);
+        assert_eq!(0.0, SliderInput::from(events[2].input.value).position);
+        // Only changed faders are reported
+        data[4..6].copy_from_slice(&2048u16.to_le_bytes());
+        let events = decoder.decode_report(TS, ANALOG_REPORT_ID, &data);
+        assert_eq!(
+            vec![Sensor::Mixer(MixerChannel::Two, MixerSensor::LevelFader).to_control_index()],
+            control_indices(&events)
+        );
+        let position = SliderInput::from(events[0].input.value).position;
+        assert!((position - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn decode_jog_wheels() {
+        let mut decoder = InputReportDecoder::new();
+        let mut data = [0; 45];
+        data[43..45].copy_from_slice(&10u16.to_le_bytes());
+        // The initial report only initializes the positions
+        assert!(decoder
+            .decode_report(TS, JOG_WHEEL_REPORT_ID, &data)
+            .is_empty());
+        // A quarter revolution forward on the left deck and
+        // backward across the zero position on the right deck
+        data[15..17].copy_from_slice(&720u16.to_le_bytes());
+        data[43..45].copy_from_slice(&2870u16.to_le_bytes());
+        let events = decoder.decode_report(TS, JOG_WHEEL_REPORT_ID, &data);
+        assert_eq!(
+            vec![
+                Sensor::Deck(Deck::One, DeckSensor::JogWheelEncoder).to_control_index(),
+                Sensor::Deck(Deck::Two, DeckSensor::JogWheelEncoder).to_control_index(),
+            ],
+            control_indices(&events)
+        );
+        let delta = SliderEncoderInput::from(events[0].input.value).delta;
+        assert!((delta - 0.25).abs() < 1e-6);
+        let delta = SliderEncoderInput::from(events[1].input.value).delta;
+        assert!((delta + 20.0 / 2880.0).abs() < 1e-6);
+    }
+}
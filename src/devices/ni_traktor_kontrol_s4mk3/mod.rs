@@ -3,27 +3,44 @@
 
 use std::{
     borrow::Cow,
-    sync::mpsc,
-    time::{Duration, Instant},
+    sync::{mpsc, Arc, Mutex, PoisonError},
+    time::Instant,
 };
 
 use hidapi::DeviceInfo;
+use strum::{EnumCount, EnumIter, FromRepr};
 
 use crate::{
     hid::{
-        report::BufferRecycler,
+        report::{BufferRecycler, ReportStats},
         thread::{
             Command, CommandDisconnected, CommandReceiver, Environment, Event, EventHandler,
-            JoinedThread, ReceiveCommandResult,
+            HidThreadConfig, JoinedThread, ReceiveCommandResult,
         },
+        DeviceId,
     },
-    AudioInterfaceDescriptor, ControllerDescriptor, DeviceDescriptor, HidDevice, HidDeviceError,
-    HidResult, HidThread,
+    AudioChannelDescriptor, AudioChannelRole, AudioInterfaceDescriptor, ControlInputEventSink,
+    ControllerCapabilities, ControllerDescriptor, DeviceDescriptor, HidApi, HidDevice,
+    HidDeviceError, HidResult, HidThread, LedOutput, MonotonicClock, RgbLedOutput,
 };
 
+pub mod input;
+pub use self::input::{
+    DeckSensor, InputReportDecoder, InvalidInputControlIndex, MainSensor, MixerSensor, Sensor,
+};
+
+pub mod output;
+pub use self::output::{rgb_led_to_palette_index, DeckLed, LedReports};
+
+const AUDIO_CHANNELS: &[AudioChannelDescriptor] = &[
+    AudioChannelDescriptor::output(0, 2, AudioChannelRole::Main),
+    AudioChannelDescriptor::output(2, 2, AudioChannelRole::Headphones),
+];
+
 pub const AUDIO_INTERFACE_DESCRIPTOR: AudioInterfaceDescriptor = AudioInterfaceDescriptor {
     num_input_channels: 0, // TODO
     num_output_channels: 4,
+    channels: Cow::Borrowed(AUDIO_CHANNELS),
 };
 
 pub const DEVICE_DESCRIPTOR: &DeviceDescriptor = &DeviceDescriptor {
@@ -38,43 +55,73 @@ pub const CONTROLLER_DESCRIPTOR: ControllerDescriptor = ControllerDescriptor {
     num_mixer_channels: 4,
     num_pads_per_deck: 8,
     num_effect_units: 2,
+    capabilities: ControllerCapabilities::MOTORIZED_JOG
+        .union(ControllerCapabilities::TOUCH_STRIP)
+        .union(ControllerCapabilities::VU_METERS),
 };
 
-#[derive(Debug, Clone, Default)]
-struct ReportStats {
-    count: usize,
-    last_instant: Option<Instant>,
-    max_duration_since_last_instant: Option<Duration>,
+#[derive(Debug, Clone, Copy, FromRepr, EnumIter, EnumCount)]
+#[repr(u8)]
+pub enum Deck {
+    /// Left
+    One,
+    /// Right
+    Two,
 }
 
-impl ReportStats {
-    #[must_use]
-    fn update(&mut self, instant: Instant) -> (usize, Option<Duration>) {
-        self.count = self.count.checked_add(1).unwrap();
-        let duration_since_last_instant = self
-            .last_instant
-            .map(|last_instant| instant.duration_since(last_instant));
-        self.last_instant = Some(instant);
-        self.max_duration_since_last_instant =
-            duration_since_last_instant.map(|duration_since_last_instant| {
-                if let Some(max_duration_since_last_instant) = self.max_duration_since_last_instant
-                {
-                    max_duration_since_last_instant.max(duration_since_last_instant)
-                } else {
-                    duration_since_last_instant
-                }
-            });
-        (self.count, duration_since_last_instant)
+impl Deck {
+    const fn control_index_bit_mask(self) -> u32 {
+        match self {
+            Deck::One => CONTROL_INDEX_DECK_ONE,
+            Deck::Two => CONTROL_INDEX_DECK_TWO,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, FromRepr, EnumIter, EnumCount)]
+#[repr(u8)]
+pub enum MixerChannel {
+    One,
+    Two,
+    Three,
+    Four,
+}
+
+impl MixerChannel {
+    const fn control_index_bit_mask(self) -> u32 {
+        (self as u32 + 1) << CONTROL_INDEX_MIXER_CHANNEL_SHIFT
     }
 }
 
+const CONTROL_INDEX_DECK_ONE: u32 = 0x0100;
+const CONTROL_INDEX_DECK_TWO: u32 = 0x0200;
+
+const CONTROL_INDEX_DECK_BIT_MASK: u32 = CONTROL_INDEX_DECK_ONE | CONTROL_INDEX_DECK_TWO;
+const CONTROL_INDEX_ENUM_BIT_MASK: u32 = (1 << CONTROL_INDEX_DECK_BIT_MASK.trailing_zeros()) - 1;
+
+const CONTROL_INDEX_MIXER_CHANNEL_SHIFT: u32 = 12;
+const CONTROL_INDEX_MIXER_CHANNEL_BIT_MASK: u32 = 0x7 << CONTROL_INDEX_MIXER_CHANNEL_SHIFT;
+
+/// Receives decoded input events on the I/O thread
+///
+/// Shared with the [`DeviceContext`] for respawning the I/O thread.
+type SharedInputEventSink = Arc<Mutex<dyn ControlInputEventSink + Send>>;
+
 struct ThreadContext {
     command_rx: mpsc::Receiver<Command>,
     recycle_report_buffer_tx: mpsc::Sender<Vec<u8>>,
     report_stats_by_id: Vec<ReportStats>,
+    input_report_decoder: InputReportDecoder,
+    input_event_sink: SharedInputEventSink,
 }
 
 impl ThreadContext {
+    /// Forget all state of the previously connected device
+    fn reset_device_state(&mut self) {
+        self.report_stats_by_id.fill(ReportStats::default());
+        self.input_report_decoder.reset();
+    }
+
     fn recycle_report_buffer(&self, buf: Vec<u8>) {
         if let Err(err) = self.recycle_report_buffer_tx.send(buf) {
             // Should never happen
@@ -102,22 +149,42 @@ impl EventHandler for ThreadContext {
             Event::StateChanged(state) => {
                 log::info!("Thread state changed: {state:?}");
             }
-            Event::FeatureReportRead { buf, buf_len } => {
+            Event::Disconnected => {
+                log::warn!("Command channel disconnected unexpectedly");
+            }
+            Event::CommandPanicked { panic: _ } => {
+                log::error!("Failed to handle command");
+            }
+            Event::FeatureReportRead {
+                ts: _,
+                buf,
+                buf_len,
+            } => {
                 log::info!(
                     "TODO: Handle feature report: {data:?}",
                     data = &buf[..buf_len]
                 );
             }
-            Event::FeatureReportReadError { buf: _, err } => {
+            Event::FeatureReportReadError { ts: _, buf: _, err } => {
                 log::warn!("Failed to read feature report: {err}");
             }
-            Event::ReportRead { data } => {
+            Event::ScheduledFeatureReportRead { ts: _, data } => {
+                log::info!("TODO: Handle scheduled feature report: {data:?}");
+            }
+            Event::ScheduledFeatureReportReadError {
+                ts: _,
+                report_id,
+                err,
+            } => {
+                log::warn!("Failed to read feature report (id = {report_id}): {err}");
+            }
+            Event::ReportRead { ts, data } => {
                 let report_id = data[0];
                 let report_stats = self
                     .report_stats_by_id
                     .get_mut(usize::from(report_id))
                     .unwrap();
-                let (_count, duration_since_last_report) = report_stats.update(Instant::now());
+                let (_count, duration_since_last_report) = report_stats.update(ts);
                 let stats_suffix = duration_since_last_report
                     .map(|duration| {
                         format!(
@@ -126,11 +193,23 @@ impl EventHandler for ThreadContext {
                         )
                     })
                     .unwrap_or_default();
-                log::info!("TODO: Handle report{stats_suffix}: {data:?}");
+                log::debug!("Received report{stats_suffix}: {data:?}");
+                let events = self
+                    .input_report_decoder
+                    .decode_report(ts, report_id, &data[1..]);
+                if !events.is_empty() {
+                    self.input_event_sink
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .sink_control_input_events(&events);
+                }
             }
-            Event::ReportReadError(err) => {
+            Event::ReportReadError { ts: _, err } => {
                 log::warn!("Failed to read report: {err}");
             }
+            Event::DeviceDisconnected { err } => {
+                log::warn!("Device disconnected: {err}");
+            }
             Event::ReportWritten {
                 buf,
                 buf_len: _,
@@ -169,13 +248,50 @@ impl EventHandler for ThreadContext {
     }
 }
 
+fn new_thread_context(
+    input_event_sink: SharedInputEventSink,
+) -> (
+    ThreadContext,
+    mpsc::Sender<Command>,
+    mpsc::Receiver<Vec<u8>>,
+) {
+    let (command_tx, command_rx) = mpsc::channel::<Command>();
+    let (recycle_report_buffer_tx, recycle_report_buffer_rx) = mpsc::channel::<Vec<u8>>();
+    let thread_context = ThreadContext {
+        command_rx,
+        recycle_report_buffer_tx,
+        // One slot per report id
+        report_stats_by_id: std::iter::repeat_n(ReportStats::default(), usize::from(u8::MAX) + 1)
+            .collect(),
+        input_report_decoder: InputReportDecoder::new(),
+        input_event_sink,
+    };
+    (thread_context, command_tx, recycle_report_buffer_rx)
+}
+
+fn spawn_thread(
+    connected_device: HidDevice,
+    thread_context: ThreadContext,
+) -> HidResult<HidThread<ThreadContext>> {
+    let environment = Environment {
+        connected_device,
+        clock: MonotonicClock::new(),
+        context: thread_context,
+    };
+    log::info!("Spawning HID I/O thread");
+    HidThread::spawn(environment, HidThreadConfig::default())
+}
+
 #[allow(missing_debug_implementations)]
 pub struct DeviceContext {
     info: DeviceInfo,
-    thread: HidThread<ThreadContext>,
+    /// `None` if reconnecting failed
+    thread: Option<HidThread<ThreadContext>>,
     command_tx: mpsc::Sender<Command>,
     recycle_report_buffer_rx: mpsc::Receiver<Vec<u8>>,
     report_buffer_recycler: BufferRecycler,
+    led_reports: LedReports,
+    input_event_sink: SharedInputEventSink,
 }
 
 impl DeviceContext {
@@ -195,47 +311,47 @@ impl DeviceContext {
             && device_info.product_id() == Self::product_id()
     }
 
-    pub fn attach(connected_device: HidDevice) -> HidResult<DeviceContext> {
+    /// Attach a connected device and spawn the I/O thread
+    ///
+    /// Decoded input events are forwarded to `input_event_sink` from
+    /// the I/O thread.
+    pub fn attach(
+        connected_device: HidDevice,
+        input_event_sink: impl ControlInputEventSink + Send + 'static,
+    ) -> HidResult<DeviceContext> {
         if !Self::is_supported(connected_device.info()) {
             return Err(HidDeviceError::NotSupported.into());
         }
         if !connected_device.is_connected() {
             return Err(HidDeviceError::NotConnected.into());
         }
-        let (command_tx, command_rx) = mpsc::channel::<Command>();
-        let (recycle_report_buffer_tx, recycle_report_buffer_rx) = mpsc::channel::<Vec<u8>>();
-        let thread_context = ThreadContext {
-            command_rx,
-            recycle_report_buffer_tx,
-            // One slot per report id
-            report_stats_by_id: std::iter::repeat(ReportStats::default())
-                .take(usize::from(u8::MAX) + 1)
-                .collect(),
-        };
+        let input_event_sink: SharedInputEventSink = Arc::new(Mutex::new(input_event_sink));
+        let (thread_context, command_tx, recycle_report_buffer_rx) =
+            new_thread_context(Arc::clone(&input_event_sink));
         let info = connected_device.info().clone();
-        let environment = Environment {
-            connected_device,
-            context: thread_context,
-        };
-        log::info!("Spawning HID I/O thread");
-        let thread = HidThread::spawn(environment)?;
+        let thread = spawn_thread(connected_device, thread_context)?;
         Ok(DeviceContext {
             info,
-            thread,
+            thread: Some(thread),
             command_tx,
             recycle_report_buffer_rx,
             report_buffer_recycler: BufferRecycler::new(),
+            led_reports: LedReports::new(),
+            input_event_sink,
         })
     }
 
     #[allow(clippy::missing_panics_doc)] // Never panics
     pub fn detach(self) -> HidResult<HidDevice> {
+        let Some(thread) = self.thread else {
+            return Err(HidDeviceError::NotConnected.into());
+        };
         log::info!("Terminating I/O thread");
         self.command_tx
             .send(Command::Terminate)
             .expect("command channel to I/O thread closed unexpectedly");
         log::info!("Joining I/O thread");
-        let joined_thread = self.thread.join();
+        let joined_thread = thread.join();
         match joined_thread {
             JoinedThread::Terminated(terminated_thread) => {
                 // The device is still connected after the thread terminated.
@@ -249,6 +365,91 @@ impl DeviceContext {
         }
     }
 
+    /// Re-open the device and respawn the I/O thread
+    ///
+    /// Intended for recovering after the I/O thread has terminated
+    /// with [`Event::DeviceDisconnected`] and the device has become
+    /// available again. The device is looked up by its [`DeviceId`],
+    /// which requires a serial number.
+    ///
+    /// The command and recycling channels are preserved unless the
+    /// previous I/O thread panicked. Pending commands are discarded.
+    /// The initialization sequence and the current state of all LEDs
+    /// are sent again. Input reports of the new device are decoded
+    /// from scratch, i.e. the first reports emit the state of all
+    /// controls.
+    pub fn reconnect(&mut self, api: &mut HidApi) -> HidResult<()> {
+        let Ok(device_id) = DeviceId::try_from(&self.info) else {
+            return Err(anyhow::anyhow!("Cannot identify device without serial number").into());
+        };
+        let device_id = device_id.into_owned();
+        let thread_context = self.terminate_thread();
+        let Some(mut device) = api.query_device_by_id(&device_id)? else {
+            return Err(HidDeviceError::NotConnected.into());
+        };
+        device.connect(api)?;
+        let thread_context = if let Some(mut thread_context) = thread_context {
+            // The reports of the new device must not be compared
+            // with the last reports of the previous device.
+            thread_context.reset_device_state();
+            thread_context
+        } else {
+            let (thread_context, command_tx, recycle_report_buffer_rx) =
+                new_thread_context(Arc::clone(&self.input_event_sink));
+            self.command_tx = command_tx;
+            self.recycle_report_buffer_rx = recycle_report_buffer_rx;
+            thread_context
+        };
+        self.info = device.info().clone();
+        self.thread = Some(spawn_thread(device, thread_context)?);
+        self.initialize();
+        // The new device doesn't know about the current state of the LEDs.
+        self.led_reports.mark_all_modified();
+        self.flush_leds();
+        Ok(())
+    }
+
+    /// Terminate and join the I/O thread
+    ///
+    /// Returns the thread context for reuse, i.e. `None` if there was
+    /// no thread or if it panicked.
+    fn terminate_thread(&mut self) -> Option<ThreadContext> {
+        let thread = self.thread.take()?;
+        log::info!("Terminating I/O thread");
+        if self.command_tx.send(Command::Terminate).is_err() {
+            log::debug!("I/O thread has already terminated");
+        }
+        log::info!("Joining I/O thread");
+        match thread.join() {
+            JoinedThread::Terminated(terminated_thread) => {
+                let Environment {
+                    connected_device,
+                    clock: _,
+                    context,
+                } = terminated_thread.context;
+                // Close the device before re-opening it.
+                drop(connected_device);
+                // Commands that have not been received by the terminated
+                // thread, including the terminate command, must not be
+                // received by the next thread.
+                while let Ok(command) = context.command_rx.try_recv() {
+                    match command {
+                        Command::WriteReport { buf, .. }
+                        | Command::WriteFeatureReport { buf, .. } => {
+                            context.recycle_report_buffer(buf);
+                        }
+                        _ => (),
+                    }
+                }
+                Some(context)
+            }
+            JoinedThread::JoinError(err) => {
+                log::warn!("Joining the I/O thread failed: {err:?}");
+                None
+            }
+        }
+    }
+
     #[must_use]
     pub const fn info(&self) -> &DeviceInfo {
         &self.info
@@ -280,21 +481,49 @@ impl DeviceContext {
     ///
     /// Reverse-engineered from Traktor Pro.
     pub fn finalize(&mut self) {
-        // Turn off button LEDs.
-        let mut data = [0; 95];
-        data[0] = 128; // report id
-        self.write_report(&data);
+        // Turn off button, pad, and jog wheel LEDs.
+        self.led_reports.turn_off_all();
+        self.flush_leds();
         // Turn off meter LEDs.
         let mut data = [0; 79];
         data[0] = 129; // report id
         self.write_report(&data);
-        // Send the finalizing reports for both wheels 0/1.
-        let mut data = [0; 41];
-        data[0] = 50; // report id
-        debug_assert_eq!(data[1], 0); // wheel 0
-        self.write_report(&data);
-        data[1] = 1; // wheel 1
-        self.write_report(&data);
+    }
+
+    /// Set the color of a performance pad LED.
+    ///
+    /// The change will not be visible until [`Self::flush_leds()`]
+    /// is invoked. Pads beyond [`output::NUM_PADS_PER_DECK`] are ignored.
+    pub fn set_pad_led(&mut self, deck: Deck, pad: u8, output: RgbLedOutput) {
+        self.led_reports.set_pad_led(deck, pad, output);
+    }
+
+    /// Set the state of a button LED.
+    ///
+    /// The change will not be visible until [`Self::flush_leds()`]
+    /// is invoked.
+    pub fn set_button_led(&mut self, deck: Deck, led: DeckLed, output: LedOutput) {
+        self.led_reports.set_button_led(deck, led, output);
+    }
+
+    /// Set the color of a jog wheel ring.
+    ///
+    /// The change will not be visible until [`Self::flush_leds()`]
+    /// is invoked.
+    pub fn set_jog_ring_led(&mut self, deck: Deck, output: RgbLedOutput) {
+        self.led_reports.set_jog_ring_led(deck, output);
+    }
+
+    /// Write all LED reports that have been modified.
+    ///
+    /// Reports without modifications since the last invocation
+    /// are not written again.
+    pub fn flush_leds(&mut self) {
+        let mut led_reports = std::mem::take(&mut self.led_reports);
+        for report in led_reports.take_modified_reports() {
+            self.write_report(report);
+        }
+        self.led_reports = led_reports;
     }
 
     /// Recycle queued buffers on demand.
@@ -318,13 +547,25 @@ impl DeviceContext {
     }
 
     pub fn write_report(&mut self, data: &[u8]) {
+        self.submit_write_report(data, None);
+    }
+
+    /// Write a report that is discarded if it could not be written in time
+    ///
+    /// Prevents flushing stale reports late, e.g. LED updates that have
+    /// already been superseded.
+    pub fn write_report_with_deadline(&mut self, data: &[u8], deadline: Instant) {
+        self.submit_write_report(data, Some(deadline));
+    }
+
+    fn submit_write_report(&mut self, data: &[u8], deadline: Option<Instant>) {
         self.recycle_queued_buffers();
         let buf = self.report_buffer_recycler.fill_buf(data);
         let buf_len = buf.len();
         let cmd = Command::WriteReport {
             buf,
             buf_len,
-            deadline: None,
+            deadline,
         };
         self.submit_command(cmd);
     }
@@ -336,3 +577,53 @@ impl DeviceContext {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{input::BUTTON_REPORT_ID, new_thread_context, SharedInputEventSink, ThreadContext};
+    use crate::{
+        hid::thread::{Event, EventHandler as _},
+        ButtonInput, ControlInputEvent, ControlInputEventSink, TimeStamp,
+    };
+
+    #[derive(Default)]
+    struct CollectedEvents(Vec<ControlInputEvent>);
+
+    impl ControlInputEventSink for CollectedEvents {
+        fn sink_control_input_events(&mut self, events: &[ControlInputEvent]) {
+            self.0.extend_from_slice(events);
+        }
+    }
+
+    #[test]
+    fn reconnect_with_different_initial_report() {
+        let collected_events = Arc::new(Mutex::new(CollectedEvents::default()));
+        let input_event_sink: SharedInputEventSink = Arc::clone(&collected_events) as _;
+        let (mut thread_context, _command_tx, _recycle_report_buffer_rx) =
+            new_thread_context(input_event_sink);
+        let count_released_buttons = |thread_context: &mut ThreadContext, report: &[u8]| {
+            thread_context.handle_event(Event::ReportRead {
+                ts: TimeStamp::default(),
+                data: report,
+            });
+            let events = std::mem::take(&mut collected_events.lock().unwrap().0);
+            events
+                .iter()
+                .filter(|event| ButtonInput::from(event.input.value) == ButtonInput::Released)
+                .count()
+        };
+
+        // Play/pause of the left deck is pressed on the previous device
+        let mut report = [0; 16];
+        report[0] = BUTTON_REPORT_ID;
+        report[5] = 0b0000_0001;
+        assert_eq!(5, count_released_buttons(&mut thread_context, &report));
+
+        // All buttons of the new device are released initially
+        thread_context.reset_device_state();
+        report[5] = 0;
+        assert_eq!(6, count_released_buttons(&mut thread_context, &report));
+    }
+}
@@ -0,0 +1,327 @@
+// SPDX-FileCopyrightText: The djio authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Encoding of LED output reports
+//!
+//! All LEDs are controlled by color palette indexes. The upper 6 bits
+//! select the color and the lower 2 bits select the brightness.
+
+use strum::{EnumCount, EnumIter, FromRepr};
+
+use super::Deck;
+use crate::{LedOutput, RgbLedOutput};
+
+/// Report with the state of all button and pad LEDs
+pub const LED_REPORT_ID: u8 = 128;
+
+/// Length of [`LED_REPORT_ID`] including the report id
+pub const LED_REPORT_LEN: usize = 95;
+
+/// Report with the state of the LEDs of a single jog wheel ring
+pub const JOG_RING_LED_REPORT_ID: u8 = 50;
+
+/// Length of [`JOG_RING_LED_REPORT_ID`] including the report id
+pub const JOG_RING_LED_REPORT_LEN: usize = 41;
+
+/// Number of performance pads per deck
+pub const NUM_PADS_PER_DECK: u8 = 8;
+
+/// First byte of the individual ring LEDs in [`JOG_RING_LED_REPORT_ID`]
+const JOG_RING_LED_OFFSET: usize = 2;
+
+const BUTTON_LED_OFF: u8 = 0x00;
+const BUTTON_LED_ON: u8 = 0x7f;
+
+const PALETTE_WHITE: u8 = 17;
+const PALETTE_NUM_HUES: u8 = 16;
+
+/// Button LED of a deck
+#[derive(Debug, Clone, Copy, FromRepr, EnumIter, EnumCount)]
+#[repr(u8)]
+pub enum DeckLed {
+    PlayPauseButton,
+    CueButton,
+    SyncButton,
+}
+
+const fn deck_led_offset(deck: Deck, led: DeckLed) -> usize {
+    let base = match deck {
+        Deck::One => 11,
+        Deck::Two => 58,
+    };
+    base + led as usize
+}
+
+const fn pad_led_offset(deck: Deck, pad: u8) -> Option<usize> {
+    if pad >= NUM_PADS_PER_DECK {
+        return None;
+    }
+    let base = match deck {
+        Deck::One => 20,
+        Deck::Two => 67,
+    };
+    Some(base + pad as usize)
+}
+
+const fn led_to_byte(output: LedOutput) -> u8 {
+    match output {
+        LedOutput::Off => BUTTON_LED_OFF,
+        LedOutput::On => BUTTON_LED_ON,
+    }
+}
+
+/// Map an RGB color onto the nearest entry of the color palette
+///
+/// The palette consists of 16 evenly distributed hues starting
+/// at red plus white.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn rgb_led_to_palette_index(output: RgbLedOutput) -> u8 {
+    let (hue, saturation, value) = output.to_hsv();
+    if value <= 0.0 {
+        return 0;
+    }
+    let color = if saturation < 0.25 {
+        PALETTE_WHITE
+    } else {
+        let hue_step = 360.0 / f32::from(PALETTE_NUM_HUES);
+        1 + ((hue / hue_step).round() as u8) % PALETTE_NUM_HUES
+    };
+    // Never turn off a colored LED completely
+    let brightness = ((value * 3.0).round() as u8).max(1);
+    (color << 2) | brightness
+}
+
+/// Accumulated state of all LED output reports
+///
+/// Tracks which reports have been modified since they have been
+/// written the last time. Unmodified reports don't need to be written
+/// again.
+#[derive(Debug, Clone)]
+pub struct LedReports {
+    led_report: [u8; LED_REPORT_LEN],
+    led_report_dirty: bool,
+    jog_ring_reports: [[u8; JOG_RING_LED_REPORT_LEN]; 2],
+    jog_ring_reports_dirty: [bool; 2],
+}
+
+impl LedReports {
+    #[must_use]
+    pub fn new() -> Self {
+        let mut led_report = [0; LED_REPORT_LEN];
+        led_report[0] = LED_REPORT_ID;
+        let mut jog_ring_report = [0; JOG_RING_LED_REPORT_LEN];
+        jog_ring_report[0] = JOG_RING_LED_REPORT_ID;
+        let mut jog_ring_reports = [jog_ring_report; 2];
+        for (wheel, report) in (0..).zip(&mut jog_ring_reports) {
+            report[1] = wheel;
+        }
+        Self {
+            led_report,
+            led_report_dirty: true,
+            jog_ring_reports,
+            jog_ring_reports_dirty: [true; 2],
+        }
+    }
+
+    fn set_led_report_byte(&mut self, offset: usize, value: u8) {
+        debug_assert!(offset > 0);
+        if self.led_report[offset] == value {
+            return;
+        }
+        self.led_report[offset] = value;
+        self.led_report_dirty = true;
+    }
+
+    pub fn set_button_led(&mut self, deck: Deck, led: DeckLed, output: LedOutput) {
+        self.set_led_report_byte(deck_led_offset(deck, led), led_to_byte(output));
+    }
+
+    /// Set the color of a performance pad LED
+    ///
+    /// Pads beyond [`NUM_PADS_PER_DECK`] are ignored.
+    pub fn set_pad_led(&mut self, deck: Deck, pad: u8, output: RgbLedOutput) {
+        let Some(offset) = pad_led_offset(deck, pad) else {
+            log::warn!("Ignoring LED of invalid pad {pad} on deck {deck:?}");
+            return;
+        };
+        self.set_led_report_byte(offset, rgb_led_to_palette_index(output));
+    }
+
+    /// Set all LEDs of a jog wheel ring to the same color
+    pub fn set_jog_ring_led(&mut self, deck: Deck, output: RgbLedOutput) {
+        self.fill_jog_ring_report(deck as usize, rgb_led_to_palette_index(output));
+    }
+
+    fn fill_jog_ring_report(&mut self, index: usize, value: u8) {
+        let report = &mut self.jog_ring_reports[index];
+        let leds = &mut report[JOG_RING_LED_OFFSET..];
+        if leds.iter().all(|led| *led == value) {
+            return;
+        }
+        leds.fill(value);
+        self.jog_ring_reports_dirty[index] = true;
+    }
+
+    /// Turn off all LEDs
+    pub fn turn_off_all(&mut self) {
+        for offset in 1..LED_REPORT_LEN {
+            self.set_led_report_byte(offset, BUTTON_LED_OFF);
+        }
+        for index in 0..self.jog_ring_reports.len() {
+            self.fill_jog_ring_report(index, 0);
+        }
+    }
+
+    /// Mark all reports as modified without changing their contents
+    ///
+    /// Forces writing all reports again, e.g. after reconnecting
+    /// the device.
+    pub fn mark_all_modified(&mut self) {
+        self.led_report_dirty = true;
+        self.jog_ring_reports_dirty = [true; 2];
+    }
+
+    /// Collect all modified reports and reset their modification state
+    pub fn take_modified_reports(&mut self) -> impl Iterator<Item = &[u8]> {
+        let Self {
+            led_report,
+            led_report_dirty,
+            jog_ring_reports,
+            jog_ring_reports_dirty,
+        } = self;
+        let led_report = std::mem::take(led_report_dirty).then_some(led_report.as_slice());
+        let jog_ring_reports = jog_ring_reports
+            .iter()
+            .zip(jog_ring_reports_dirty.iter_mut())
+            .filter_map(|(report, dirty)| std::mem::take(dirty).then_some(report.as_slice()));
+        led_report.into_iter().chain(jog_ring_reports)
+    }
+}
+
+impl Default for LedReports {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_index() {
+        assert_eq!(0, rgb_led_to_palette_index(RgbLedOutput::BLACK));
+        assert_eq!(
+            (PALETTE_WHITE << 2) | 3,
+            rgb_led_to_palette_index(RgbLedOutput::WHITE)
+        );
+        // Gray is mapped onto white with reduced brightness
+        assert_eq!(
+            (PALETTE_WHITE << 2) | 2,
+            rgb_led_to_palette_index(RgbLedOutput::from_hsv(0.0, 0.0, 0.5))
+        );
+        // Red, green, and blue
+        assert_eq!(
+            (1 << 2) | 3,
+            rgb_led_to_palette_index(RgbLedOutput::from_hsv(0.0, 1.0, 1.0))
+        );
+        assert_eq!(
+            (6 << 2) | 3,
+            rgb_led_to_palette_index(RgbLedOutput::from_hsv(120.0, 1.0, 1.0))
+        );
+        assert_eq!(
+            (12 << 2) | 3,
+            rgb_led_to_palette_index(RgbLedOutput::from_hsv(240.0, 1.0, 1.0))
+        );
+        // Hues wrap around to red
+        assert_eq!(
+            (1 << 2) | 3,
+            rgb_led_to_palette_index(RgbLedOutput::from_hsv(350.0, 1.0, 1.0))
+        );
+        // Dark colors are not turned off
+        assert_eq!(
+            (1 << 2) | 1,
+            rgb_led_to_palette_index(RgbLedOutput {
+                red: 10,
+                green: 0,
+                blue: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn led_reports() {
+        let mut led_reports = LedReports::new();
+        // All reports are written initially
+        let reports = led_reports
+            .take_modified_reports()
+            .map(<[u8]>::to_vec)
+            .collect::<Vec<_>>();
+        assert_eq!(3, reports.len());
+        assert_eq!(LED_REPORT_LEN, reports[0].len());
+        assert_eq!(LED_REPORT_ID, reports[0][0]);
+        for (wheel, report) in (0..).zip(&reports[1..]) {
+            assert_eq!(JOG_RING_LED_REPORT_LEN, report.len());
+            assert_eq!([JOG_RING_LED_REPORT_ID, wheel], report[..2]);
+        }
+        assert_eq!(0, led_reports.take_modified_reports().count());
+
+        led_reports.set_button_led(Deck::Two, DeckLed::CueButton, LedOutput::On);
+        led_reports.set_pad_led(Deck::One, 3, RgbLedOutput::from_hsv(0.0, 1.0, 1.0));
+        let reports = led_reports
+            .take_modified_reports()
+            .map(<[u8]>::to_vec)
+            .collect::<Vec<_>>();
+        assert_eq!(1, reports.len());
+        let report = &reports[0];
+        assert_eq!(BUTTON_LED_ON, report[59]);
+        assert_eq!((1 << 2) | 3, report[23]);
+        assert_eq!(2, report.iter().skip(1).filter(|&&byte| byte != 0).count());
+
+        // Invalid pads must not modify other LEDs
+        for deck in [Deck::One, Deck::Two] {
+            led_reports.set_pad_led(deck, NUM_PADS_PER_DECK, RgbLedOutput::WHITE);
+            led_reports.set_pad_led(deck, u8::MAX, RgbLedOutput::WHITE);
+        }
+        assert_eq!(0, led_reports.take_modified_reports().count());
+
+        led_reports.set_jog_ring_led(Deck::Two, RgbLedOutput::from_hsv(240.0, 1.0, 1.0));
+        let reports = led_reports
+            .take_modified_reports()
+            .map(<[u8]>::to_vec)
+            .collect::<Vec<_>>();
+        assert_eq!(1, reports.len());
+        let report = &reports[0];
+        assert_eq!([JOG_RING_LED_REPORT_ID, 1], report[..2]);
+        assert!(report[JOG_RING_LED_OFFSET..]
+            .iter()
+            .all(|&byte| byte == (12 << 2) | 3));
+
+        // Unchanged LEDs are not written again
+        led_reports.set_button_led(Deck::Two, DeckLed::CueButton, LedOutput::On);
+        led_reports.set_jog_ring_led(Deck::Two, RgbLedOutput::from_hsv(240.0, 1.0, 1.0));
+        assert_eq!(0, led_reports.take_modified_reports().count());
+
+        // All reports are written again with their current contents
+        led_reports.mark_all_modified();
+        let reports = led_reports
+            .take_modified_reports()
+            .map(<[u8]>::to_vec)
+            .collect::<Vec<_>>();
+        assert_eq!(3, reports.len());
+        assert_eq!(BUTTON_LED_ON, reports[0][59]);
+
+        led_reports.turn_off_all();
+        let reports = led_reports
+            .take_modified_reports()
+            .map(<[u8]>::to_vec)
+            .collect::<Vec<_>>();
+        assert_eq!(2, reports.len());
+        assert!(reports[0][1..].iter().all(|&byte| byte == 0));
+        assert_eq!([JOG_RING_LED_REPORT_ID, 1], reports[1][..2]);
+        assert!(reports[1][JOG_RING_LED_OFFSET..]
+            .iter()
+            .all(|&byte| byte == 0));
+    }
+}
@@ -9,23 +9,26 @@
 //! <https://support.pioneerdj.com/hc/en-us/sections/4416577146009-ddj-400>
 //! and here:
 //! <https://www.pioneerdj.com/-/media/pioneerdj/software-info/controller/ddj-400/ddj-400_midi_message_list_e1.pdf>.
+use std::sync::LazyLock;
+
 use derive_more::From;
-use strum::{EnumCount, EnumIter, FromRepr};
+use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator as _};
 
 use super::{
-    Deck, CONTROL_INDEX_DECK_BIT_MASK, CONTROL_INDEX_DECK_ONE, CONTROL_INDEX_DECK_TWO,
+    Deck, CONTROL_INDEX_DECK_ONE, CONTROL_INDEX_DECK_TWO, CONTROL_INDEX_EFFECT,
     CONTROL_INDEX_ENUM_BIT_MASK, CONTROL_INDEX_PERFORMANCE_DECK_ONE,
     CONTROL_INDEX_PERFORMANCE_DECK_TWO, MIDI_CHANNEL_DECK_ONE, MIDI_CHANNEL_DECK_TWO,
-    MIDI_CHANNEL_PERFORMANCE_DECK_ONE, MIDI_CHANNEL_PERFORMANCE_DECK_TWO, MIDI_DEVICE_DESCRIPTOR,
+    MIDI_CHANNEL_EFFECT, MIDI_CHANNEL_MAIN, MIDI_CHANNEL_PERFORMANCE_DECK_ONE,
+    MIDI_CHANNEL_PERFORMANCE_DECK_TWO, MIDI_COMMAND_CC, MIDI_DEVICE_DESCRIPTOR,
     MIDI_STATUS_BUTTON_DECK_ONE, MIDI_STATUS_BUTTON_DECK_TWO, MIDI_STATUS_BUTTON_EFFECT,
     MIDI_STATUS_BUTTON_MAIN, MIDI_STATUS_BUTTON_PERFORMANCE_DECK_ONE,
     MIDI_STATUS_BUTTON_PERFORMANCE_DECK_TWO, MIDI_STATUS_CC_DECK_ONE, MIDI_STATUS_CC_DECK_TWO,
     MIDI_STATUS_CC_EFFECT, MIDI_STATUS_CC_MAIN,
 };
 use crate::{
-    u7_be_to_u14, ButtonInput, CenterSliderInput, Control, ControlIndex, ControlInputEvent,
-    ControlValue, MidiInputConnector, MidiInputDecodeError, SelectorInput, SliderInput,
-    StepEncoderInput, TimeStamp,
+    ButtonInput, Cc14BitAccumulator, CenterSliderInput, Control, ControlIndex, ControlInputEvent,
+    ControlValue, InputControlDescriptor, InputControlKind, MidiInputConnector,
+    MidiInputDecodeError, SelectorInput, SliderInput, StepEncoderInput, TimeStamp,
 };
 
 #[derive(Debug, Clone, Copy, From)]
@@ -54,6 +57,25 @@ pub enum MainSensor {
     FilterRightCenterSlider,
 }
 
+impl MainSensor {
+    #[must_use]
+    pub const fn kind(self) -> InputControlKind {
+        match self {
+            Self::LoadLeftButton
+            | Self::LoadRightButton
+            | Self::RotarySelectorButton
+            | Self::HeadphoneCueButton => InputControlKind::Button,
+            Self::RotarySelectorStepEncoder => InputControlKind::Encoder,
+            Self::MasterLevelSlider
+            | Self::HeadphonesMixingCenterSlider
+            | Self::HeadphonesLevelSlider
+            | Self::CrossfaderCenterSlider
+            | Self::FilterLeftCenterSlider
+            | Self::FilterRightCenterSlider => InputControlKind::Slider,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, FromRepr, EnumIter, EnumCount)]
 #[repr(u8)]
 pub enum DeckSensor {
@@ -91,7 +113,23 @@ pub enum DeckSensor {
     LevelFader,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl DeckSensor {
+    #[must_use]
+    pub const fn kind(self) -> InputControlKind {
+        match self {
+            Self::JogWheelTopEncoder | Self::JogWheelOuterEncoder => InputControlKind::Encoder,
+            Self::TempoCenterSlider
+            | Self::TrimSlider
+            | Self::EqHighCenterSlider
+            | Self::EqMidCenterSlider
+            | Self::EqLowCenterSlider
+            | Self::LevelFader => InputControlKind::Slider,
+            _ => InputControlKind::Button,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, FromRepr, EnumIter, EnumCount)]
 #[repr(u8)]
 pub enum EffectSensor {
     BeatLeftButton,
@@ -102,6 +140,20 @@ pub enum EffectSensor {
     BeatFxOnOffButton,
 }
 
+impl EffectSensor {
+    #[must_use]
+    pub const fn kind(self) -> InputControlKind {
+        match self {
+            Self::BeatLeftButton
+            | Self::BeatRightButton
+            | Self::BeatFxSelectButton
+            | Self::BeatFxOnOffButton => InputControlKind::Button,
+            Self::BeatFxChannelSelectSwitch => InputControlKind::Selector,
+            Self::BeatFxLevelDepthKnob => InputControlKind::Slider,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum PerformancePadSensor {
     HotCue(u8),
@@ -115,6 +167,25 @@ pub enum PerformancePadSensor {
 }
 
 impl PerformancePadSensor {
+    /// Pad mode and pad number in the range 0..8
+    const fn split(self) -> (&'static str, u8) {
+        match self {
+            Self::HotCue(nr) => ("HotCue", nr),
+            Self::BeatLoop(nr) => ("BeatLoop", nr),
+            Self::BeatJump(nr) => ("BeatJump", nr),
+            Self::Sampler(nr) => ("Sampler", nr),
+            Self::Keyboard(nr) => ("Keyboard", nr),
+            Self::PadFx1(nr) => ("PadFx1", nr),
+            Self::PadFx2(nr) => ("PadFx2", nr),
+            Self::KeyShift(nr) => ("KeyShift", nr),
+        }
+    }
+
+    /// All pads of all pad modes
+    fn iter() -> impl Iterator<Item = Self> {
+        (0..=0x7f).filter_map(Self::try_from_u8)
+    }
+
     const fn as_u8(self) -> u8 {
         match self {
             Self::HotCue(nr) => nr,
@@ -163,7 +234,7 @@ impl Sensor {
                 };
                 ControlIndex::new(deck_bit | sensor as u32)
             }
-            Self::Effect(sensor) => ControlIndex::new(sensor as u32),
+            Self::Effect(sensor) => ControlIndex::new(CONTROL_INDEX_EFFECT | sensor as u32),
             Self::Performance(deck, sensor) => {
                 let deck_bit = match deck {
                     Deck::One => CONTROL_INDEX_PERFORMANCE_DECK_ONE,
@@ -174,6 +245,57 @@ impl Sensor {
             }
         }
     }
+
+    #[must_use]
+    pub const fn kind(self) -> InputControlKind {
+        match self {
+            Self::Main(sensor) => sensor.kind(),
+            Self::Deck(_, sensor) => sensor.kind(),
+            Self::Effect(sensor) => sensor.kind(),
+            Self::Performance(..) => InputControlKind::Pad,
+        }
+    }
+
+    /// Human-readable name
+    #[must_use]
+    pub fn name(self) -> String {
+        match self {
+            Self::Main(sensor) => format!("{sensor:?}"),
+            Self::Deck(deck, sensor) => format!("Deck {deck:?} {sensor:?}"),
+            Self::Effect(sensor) => format!("Effect {sensor:?}"),
+            Self::Performance(deck, sensor) => {
+                let (mode, pad) = sensor.split();
+                format!("Deck {deck:?} {mode} Pad {}", pad + 1)
+            }
+        }
+    }
+}
+
+/// Descriptors of all sensors
+#[must_use]
+pub fn input_controls() -> &'static [InputControlDescriptor] {
+    static INPUT_CONTROLS: LazyLock<Vec<InputControlDescriptor>> = LazyLock::new(|| {
+        let main_sensors = MainSensor::iter().map(Sensor::Main);
+        let effect_sensors = EffectSensor::iter().map(Sensor::Effect);
+        let deck_sensors = Deck::iter().flat_map(|deck| {
+            DeckSensor::iter()
+                .map(move |sensor| Sensor::Deck(deck, sensor))
+                .chain(
+                    PerformancePadSensor::iter()
+                        .map(move |sensor| Sensor::Performance(deck, sensor)),
+                )
+        });
+        main_sensors
+            .chain(effect_sensors)
+            .chain(deck_sensors)
+            .map(|sensor| InputControlDescriptor {
+                index: sensor.to_control_index(),
+                name: sensor.name().into(),
+                kind: sensor.kind(),
+            })
+            .collect()
+    });
+    &INPUT_CONTROLS
 }
 
 impl From<Sensor> for ControlIndex {
@@ -192,27 +314,22 @@ impl TryFrom<ControlIndex> for Sensor {
         let value = from.value();
         debug_assert!(CONTROL_INDEX_ENUM_BIT_MASK <= u8::MAX.into());
         let enum_index = (value & CONTROL_INDEX_ENUM_BIT_MASK) as u8;
-        let deck = match value & CONTROL_INDEX_DECK_BIT_MASK {
-            CONTROL_INDEX_DECK_ONE => Deck::One,
-            CONTROL_INDEX_DECK_TWO => Deck::Two,
-            CONTROL_INDEX_DECK_BIT_MASK => return Err(InvalidInputControlIndex),
-            _ => {
-                return MainSensor::from_repr(enum_index)
-                    .map(Sensor::Main)
-                    .ok_or(InvalidInputControlIndex);
+        let sensor = match value & !CONTROL_INDEX_ENUM_BIT_MASK {
+            0 => MainSensor::from_repr(enum_index).map(Sensor::Main),
+            CONTROL_INDEX_DECK_ONE => {
+                DeckSensor::from_repr(enum_index).map(|sensor| Sensor::Deck(Deck::One, sensor))
+            }
+            CONTROL_INDEX_DECK_TWO => {
+                DeckSensor::from_repr(enum_index).map(|sensor| Sensor::Deck(Deck::Two, sensor))
             }
+            CONTROL_INDEX_PERFORMANCE_DECK_ONE => PerformancePadSensor::try_from_u8(enum_index)
+                .map(|sensor| Sensor::Performance(Deck::One, sensor)),
+            CONTROL_INDEX_PERFORMANCE_DECK_TWO => PerformancePadSensor::try_from_u8(enum_index)
+                .map(|sensor| Sensor::Performance(Deck::Two, sensor)),
+            CONTROL_INDEX_EFFECT => EffectSensor::from_repr(enum_index).map(Sensor::Effect),
+            _ => None,
         };
-        DeckSensor::from_repr(enum_index)
-            .map(|sensor| Sensor::Deck(deck, sensor))
-            .ok_or(InvalidInputControlIndex)
-    }
-}
-
-fn u7_to_button(input: u8) -> ButtonInput {
-    match input {
-        0x00 => ButtonInput::Released,
-        0x7f => ButtonInput::Pressed,
-        _ => unreachable!(),
+        sensor.ok_or(InvalidInputControlIndex)
     }
 }
 
@@ -232,9 +349,53 @@ fn midi_status_to_performance_deck(status: u8) -> Deck {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+/// Control numbers of the MSB and LSB halves of all 14-bit controls
+const CC_14BIT_CONTROLS: &[(u8, u8, u8)] = &[
+    (MIDI_CHANNEL_MAIN, 0x1f, 0x3f),
+    (MIDI_CHANNEL_MAIN, 0x08, 0x28),
+    (MIDI_CHANNEL_MAIN, 0x0d, 0x2d),
+    (MIDI_CHANNEL_MAIN, 0x0c, 0x2c),
+    (MIDI_CHANNEL_MAIN, 0x17, 0x37),
+    (MIDI_CHANNEL_MAIN, 0x18, 0x38),
+    (MIDI_CHANNEL_EFFECT, 0x02, 0x22),
+    (MIDI_CHANNEL_DECK_ONE, 0x00, 0x20),
+    (MIDI_CHANNEL_DECK_ONE, 0x13, 0x33),
+    (MIDI_CHANNEL_DECK_ONE, 0x04, 0x24),
+    (MIDI_CHANNEL_DECK_ONE, 0x07, 0x27),
+    (MIDI_CHANNEL_DECK_ONE, 0x0b, 0x2b),
+    (MIDI_CHANNEL_DECK_ONE, 0x0f, 0x2f),
+    (MIDI_CHANNEL_DECK_TWO, 0x00, 0x20),
+    (MIDI_CHANNEL_DECK_TWO, 0x13, 0x33),
+    (MIDI_CHANNEL_DECK_TWO, 0x04, 0x24),
+    (MIDI_CHANNEL_DECK_TWO, 0x07, 0x27),
+    (MIDI_CHANNEL_DECK_TWO, 0x0b, 0x2b),
+    (MIDI_CHANNEL_DECK_TWO, 0x0f, 0x2f),
+];
+
+#[derive(Debug, Clone)]
 pub struct MidiInputEventDecoder {
-    last_hi: u8,
+    cc_14bit: Cc14BitAccumulator,
+    beat_fx_channel_choice: u8,
+}
+
+impl MidiInputEventDecoder {
+    #[must_use]
+    pub fn new() -> Self {
+        let mut cc_14bit = Cc14BitAccumulator::new(Cc14BitAccumulator::DEFAULT_TIMEOUT);
+        for &(channel, msb_cc, lsb_cc) in CC_14BIT_CONTROLS {
+            cc_14bit.register(channel, msb_cc, lsb_cc);
+        }
+        Self {
+            cc_14bit,
+            beat_fx_channel_choice: 0,
+        }
+    }
+}
+
+impl Default for MidiInputEventDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl crate::MidiInputEventDecoder for MidiInputEventDecoder {
@@ -244,12 +405,14 @@ impl crate::MidiInputEventDecoder for MidiInputEventDecoder {
         input: &[u8],
     ) -> Result<Option<ControlInputEvent>, MidiInputDecodeError> {
         // TODO: make this more readable
-        let (sensor, value) = if let Some(ev) = try_decode_button_event(self, input)? {
-            ev
-        } else if let Some(ev) = try_decode_cc_event(self, input)? {
-            ev
+        let decoded = if let Some(ev) = try_decode_button_event(self, input)? {
+            Some(ev)
         } else {
-            return Err(MidiInputDecodeError);
+            try_decode_cc_event(self, ts, input)?
+        };
+        let Some((sensor, value)) = decoded else {
+            // Incomplete 14-bit value
+            return Ok(None);
         };
         log::debug!("{sensor:?} {input:?}");
         let input = Control {
@@ -274,7 +437,7 @@ fn try_decode_button_event(
                 0x47 => MainSensor::LoadRightButton,
                 0x63 => MainSensor::HeadphoneCueButton,
                 _ => {
-                    return Err(MidiInputDecodeError);
+                    return Err(MidiInputDecodeError::Unsupported);
                 }
             };
             sensor.into()
@@ -283,11 +446,11 @@ fn try_decode_button_event(
             #[allow(clippy::bool_to_int_with_if)]
             let sensor = match data1 {
                 0x10 => {
-                    decoder.last_hi = if data2 == 0x7f { 0 } else { 1 };
+                    decoder.beat_fx_channel_choice = if data2 == 0x7f { 0 } else { 1 };
                     return Ok(None);
                 }
                 0x14 => {
-                    decoder.last_hi = if data2 == 0x7f { 2 } else { 1 };
+                    decoder.beat_fx_channel_choice = if data2 == 0x7f { 2 } else { 1 };
                     return Ok(None);
                 }
                 0x47 => EffectSensor::BeatFxOnOffButton,
@@ -296,7 +459,7 @@ fn try_decode_button_event(
                 0x63 => EffectSensor::BeatFxSelectButton,
                 0x11 => EffectSensor::BeatFxChannelSelectSwitch,
                 _ => {
-                    return Err(MidiInputDecodeError);
+                    return Err(MidiInputDecodeError::Unsupported);
                 }
             };
             Sensor::Effect(sensor)
@@ -327,7 +490,7 @@ fn try_decode_button_event(
                 0x60 => DeckSensor::TempoRangeButton,
                 0x6d => DeckSensor::BeatLoopModeButton,
                 _ => {
-                    return Err(MidiInputDecodeError);
+                    return Err(MidiInputDecodeError::Unsupported);
                 }
             };
             Sensor::Deck(deck, sensor)
@@ -336,130 +499,142 @@ fn try_decode_button_event(
         | MIDI_STATUS_BUTTON_PERFORMANCE_DECK_TWO), data1, _] => {
             let deck = midi_status_to_performance_deck(status);
             let Some(sensor) = PerformancePadSensor::try_from_u8(data1) else {
-                return Err(MidiInputDecodeError);
+                return Err(MidiInputDecodeError::Unsupported);
             };
             Sensor::Performance(deck, sensor)
         }
         _ => return Ok(None),
     };
 
-    let value = if input[1] == 0x11 {
-        let choice = u32::from(decoder.last_hi);
+    let value = if matches!(
+        sensor,
+        Sensor::Effect(EffectSensor::BeatFxChannelSelectSwitch)
+    ) {
+        let choice = u32::from(decoder.beat_fx_channel_choice);
         SelectorInput { choice }.into()
     } else {
-        u7_to_button(input[2]).into()
+        ButtonInput::from_velocity(input[2]).into()
     };
     Ok(Some((sensor, value)))
 }
 
-#[allow(clippy::too_many_lines)]
 fn try_decode_cc_event(
     decoder: &mut MidiInputEventDecoder,
+    ts: TimeStamp,
     input: &[u8],
 ) -> Result<Option<(Sensor, ControlValue)>, MidiInputDecodeError> {
+    let [status, data1, data2] = *input else {
+        return Err(MidiInputDecodeError::UnexpectedLength {
+            got: input.len(),
+            expected: 3,
+        });
+    };
+    let channel = status & 0x0f;
+    if status & 0xf0 == MIDI_COMMAND_CC && decoder.cc_14bit.is_registered(channel, data1) {
+        let Some(value) = decoder.cc_14bit.try_accumulate(ts, channel, data1, data2) else {
+            // Waiting for the other half
+            return Ok(None);
+        };
+        // The control number of the LSB is offset by 32 from the MSB.
+        let msb_cc = data1 & !0x20;
+        return try_decode_cc_14bit_event(status, msb_cc, value).map(Some);
+    }
     let (sensor, value) = match *input {
-        [MIDI_STATUS_CC_MAIN, data1, data2] => match data1 {
-            0x1f | 0x08 | 0x0d | 0x0c | 0x17 | 0x18 => {
-                decoder.last_hi = data2;
-                return Ok(None);
-            }
-            0x3f => (
-                MainSensor::CrossfaderCenterSlider.into(),
-                CenterSliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2)).into(),
-            ),
-            0x28 => (
-                MainSensor::MasterLevelSlider.into(),
-                SliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2)).into(),
-            ),
-            0x2d => (
-                MainSensor::HeadphonesLevelSlider.into(),
-                SliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2)).into(),
-            ),
-            0x2c => (
-                MainSensor::HeadphonesMixingCenterSlider.into(),
-                CenterSliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2)).into(),
-            ),
-            0x40 => (
-                MainSensor::RotarySelectorStepEncoder.into(),
-                StepEncoderInput::from_u7(data2).into(),
-            ),
-            0x37 => (
-                MainSensor::FilterLeftCenterSlider.into(),
-                CenterSliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2)).into(),
-            ),
-            0x38 => (
-                MainSensor::FilterRightCenterSlider.into(),
-                CenterSliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2)).into(),
-            ),
-            _ => {
-                return Err(MidiInputDecodeError);
-            }
-        },
-        [MIDI_STATUS_CC_EFFECT, data1, data2] => match data1 {
-            0x02 => {
-                decoder.last_hi = data2;
-                return Ok(None);
-            }
-            0x22 => (
-                EffectSensor::BeatFxLevelDepthKnob.into(),
-                CenterSliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2)).into(),
-            ),
-            _ => {
-                return Err(MidiInputDecodeError);
-            }
-        },
+        [MIDI_STATUS_CC_MAIN, 0x40, data2] => (
+            MainSensor::RotarySelectorStepEncoder.into(),
+            StepEncoderInput::from_u7(data2).into(),
+        ),
         [status @ (MIDI_STATUS_CC_DECK_ONE | MIDI_STATUS_CC_DECK_TWO), data1, data2] => {
             let deck = midi_status_to_deck(status);
-            let (sensor, value) = match data1 {
-                0x00 | 0x13 | 0x07 | 0x0f | 0x0b | 0x04 => {
-                    decoder.last_hi = data2;
-                    return Ok(None);
+            let sensor = match data1 {
+                0x21 => DeckSensor::JogWheelOuterEncoder,
+                0x22 => DeckSensor::JogWheelTopEncoder,
+                _ => {
+                    return Err(MidiInputDecodeError::Unsupported);
                 }
-                0x20 => (
+            };
+            (
+                Sensor::Deck(deck, sensor),
+                StepEncoderInput::from_u7(data2).into(),
+            )
+        }
+        [MIDI_STATUS_CC_MAIN | MIDI_STATUS_CC_EFFECT, ..] => {
+            return Err(MidiInputDecodeError::Unsupported);
+        }
+        _ => {
+            return Err(MidiInputDecodeError::UnknownStatus(input[0]));
+        }
+    };
+    Ok(Some((sensor, value)))
+}
+
+/// Decode a 14-bit control that is identified by the control number of its MSB
+fn try_decode_cc_14bit_event(
+    status: u8,
+    msb_cc: u8,
+    value: u16,
+) -> Result<(Sensor, ControlValue), MidiInputDecodeError> {
+    let decoded = match (status, msb_cc) {
+        (MIDI_STATUS_CC_MAIN, 0x1f) => (
+            MainSensor::CrossfaderCenterSlider.into(),
+            CenterSliderInput::from_u14(value).into(),
+        ),
+        (MIDI_STATUS_CC_MAIN, 0x08) => (
+            MainSensor::MasterLevelSlider.into(),
+            SliderInput::from_u14(value).into(),
+        ),
+        (MIDI_STATUS_CC_MAIN, 0x0d) => (
+            MainSensor::HeadphonesLevelSlider.into(),
+            SliderInput::from_u14(value).into(),
+        ),
+        (MIDI_STATUS_CC_MAIN, 0x0c) => (
+            MainSensor::HeadphonesMixingCenterSlider.into(),
+            CenterSliderInput::from_u14(value).into(),
+        ),
+        (MIDI_STATUS_CC_MAIN, 0x17) => (
+            MainSensor::FilterLeftCenterSlider.into(),
+            CenterSliderInput::from_u14(value).into(),
+        ),
+        (MIDI_STATUS_CC_MAIN, 0x18) => (
+            MainSensor::FilterRightCenterSlider.into(),
+            CenterSliderInput::from_u14(value).into(),
+        ),
+        (MIDI_STATUS_CC_EFFECT, 0x02) => (
+            EffectSensor::BeatFxLevelDepthKnob.into(),
+            CenterSliderInput::from_u14(value).into(),
+        ),
+        (MIDI_STATUS_CC_DECK_ONE | MIDI_STATUS_CC_DECK_TWO, msb_cc) => {
+            let deck = midi_status_to_deck(status);
+            let (sensor, value) = match msb_cc {
+                0x00 => (
                     DeckSensor::TempoCenterSlider,
-                    CenterSliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2))
-                        .inverse()
-                        .into(),
-                ),
-                0x33 => (
-                    DeckSensor::LevelFader,
-                    SliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2)).into(),
-                ),
-                0x21 => (
-                    DeckSensor::JogWheelOuterEncoder,
-                    StepEncoderInput::from_u7(data2).into(),
+                    CenterSliderInput::from_u14(value).inverse().into(),
                 ),
-                0x22 => (
-                    DeckSensor::JogWheelTopEncoder,
-                    StepEncoderInput::from_u7(data2).into(),
-                ),
-                0x24 => (
-                    DeckSensor::TrimSlider,
-                    SliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2)).into(),
-                ),
-                0x27 => (
+                0x13 => (DeckSensor::LevelFader, SliderInput::from_u14(value).into()),
+                0x04 => (DeckSensor::TrimSlider, SliderInput::from_u14(value).into()),
+                0x07 => (
                     DeckSensor::EqHighCenterSlider,
-                    CenterSliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2)).into(),
+                    CenterSliderInput::from_u14(value).into(),
                 ),
-                0x2b => (
+                0x0b => (
                     DeckSensor::EqMidCenterSlider,
-                    CenterSliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2)).into(),
+                    CenterSliderInput::from_u14(value).into(),
                 ),
-                0x2f => (
+                0x0f => (
                     DeckSensor::EqLowCenterSlider,
-                    CenterSliderInput::from_u14(u7_be_to_u14(decoder.last_hi, data2)).into(),
+                    CenterSliderInput::from_u14(value).into(),
                 ),
                 _ => {
-                    return Err(MidiInputDecodeError);
+                    return Err(MidiInputDecodeError::Unsupported);
                 }
             };
             (Sensor::Deck(deck, sensor), value)
         }
         _ => {
-            return Err(MidiInputDecodeError);
+            return Err(MidiInputDecodeError::Unsupported);
         }
     };
-    Ok(Some((sensor, value)))
+    Ok(decoded)
 }
 
 impl MidiInputConnector for MidiInputEventDecoder {
@@ -471,3 +646,79 @@ impl MidiInputConnector for MidiInputEventDecoder {
         assert_eq!(device, MIDI_DEVICE_DESCRIPTOR);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{
+        input_controls, Deck, DeckSensor, MainSensor, MidiInputEventDecoder, Sensor,
+        MIDI_STATUS_BUTTON_DECK_TWO, MIDI_STATUS_CC_MAIN,
+    };
+    use crate::{
+        ButtonInput, CenterSliderInput, Control, ControlInputEvent, MidiInputDecodeError,
+        MidiInputEventDecoder as _, TimeStamp,
+    };
+
+    #[test]
+    fn input_control_indices_are_unique() {
+        let mut indices = HashSet::new();
+        for control in input_controls() {
+            assert!(indices.insert(control.index), "{}", control.name);
+            let sensor = Sensor::try_from(control.index).unwrap();
+            assert_eq!(control.index, sensor.to_control_index());
+        }
+    }
+
+    #[test]
+    fn decode_14bit_slider() {
+        let mut decoder = MidiInputEventDecoder::new();
+        let ts = TimeStamp::from_micros(0);
+        // MSB
+        assert_eq!(
+            None,
+            decoder
+                .try_decode_midi_input_event(ts, &[MIDI_STATUS_CC_MAIN, 0x1f, 0x40])
+                .unwrap()
+        );
+        // LSB
+        assert_eq!(
+            Some(ControlInputEvent {
+                ts,
+                input: Control {
+                    index: Sensor::Main(MainSensor::CrossfaderCenterSlider).into(),
+                    value: CenterSliderInput::from_u14(0x2000).into(),
+                },
+            }),
+            decoder
+                .try_decode_midi_input_event(ts, &[MIDI_STATUS_CC_MAIN, 0x3f, 0x00])
+                .unwrap()
+        );
+        // Truncated
+        assert_eq!(
+            Err(MidiInputDecodeError::UnexpectedLength {
+                got: 2,
+                expected: 3
+            }),
+            decoder.try_decode_midi_input_event(ts, &[MIDI_STATUS_CC_MAIN, 0x1f])
+        );
+    }
+
+    #[test]
+    fn decode_deck_out_button() {
+        let mut decoder = MidiInputEventDecoder::new();
+        let ts = TimeStamp::from_micros(0);
+        assert_eq!(
+            Some(ControlInputEvent {
+                ts,
+                input: Control {
+                    index: Sensor::Deck(Deck::Two, DeckSensor::OutButton).into(),
+                    value: ButtonInput::Pressed.into(),
+                },
+            }),
+            decoder
+                .try_decode_midi_input_event(ts, &[MIDI_STATUS_BUTTON_DECK_TWO, 0x11, 0x7f])
+                .unwrap()
+        );
+    }
+}
@@ -6,20 +6,31 @@ use std::borrow::Cow;
 use strum::{EnumCount, EnumIter, FromRepr};
 
 use crate::{
-    AudioInterfaceDescriptor, ControllerDescriptor, DeviceDescriptor, MidiDeviceDescriptor,
+    AudioChannelDescriptor, AudioChannelRole, AudioInterfaceDescriptor, ControllerCapabilities,
+    ControllerDescriptor, DeviceDescriptor, MidiDeviceDescriptor, MidiPortNameMatching,
 };
 
 pub mod input;
-pub use self::input::{DeckSensor, EffectSensor, MainSensor, MidiInputEventDecoder, Sensor};
+pub use self::input::{
+    input_controls, DeckSensor, EffectSensor, MainSensor, MidiInputEventDecoder, Sensor,
+};
 
 pub mod output;
 pub use self::output::{
-    led_output_into_midi_message, DeckLed, InvalidOutputControlIndex, Led, MainLed, OutputGateway,
+    control_indices_for, led_output_into_midi_message, midi_message_into_led_output,
+    output_controls, DeckLed, InvalidOutputControlIndex, Led, MainLed, OutputGateway, PadMode,
+    NUM_PERFORMANCE_PADS,
 };
 
+const AUDIO_CHANNELS: &[AudioChannelDescriptor] = &[
+    AudioChannelDescriptor::output(0, 2, AudioChannelRole::Main),
+    AudioChannelDescriptor::output(2, 2, AudioChannelRole::Headphones),
+];
+
 pub const AUDIO_INTERFACE_DESCRIPTOR: AudioInterfaceDescriptor = AudioInterfaceDescriptor {
     num_input_channels: 0,
     num_output_channels: 4,
+    channels: Cow::Borrowed(AUDIO_CHANNELS),
 };
 
 pub const MIDI_DEVICE_DESCRIPTOR: &MidiDeviceDescriptor = &MidiDeviceDescriptor {
@@ -29,6 +40,8 @@ pub const MIDI_DEVICE_DESCRIPTOR: &MidiDeviceDescriptor = &MidiDeviceDescriptor
         audio_interface: Some(AUDIO_INTERFACE_DESCRIPTOR),
     },
     port_name_prefix: "DDJ-400",
+    port_name_matching: MidiPortNameMatching::StartsWith,
+    port_name_lenient: false,
 };
 
 pub const DEVICE_DESCRIPTOR: &DeviceDescriptor = &MIDI_DEVICE_DESCRIPTOR.device;
@@ -39,6 +52,7 @@ pub const CONTROLLER_DESCRIPTOR: &ControllerDescriptor = &ControllerDescriptor {
     num_mixer_channels: 2,
     num_pads_per_deck: 8,
     num_effect_units: 1,
+    capabilities: ControllerCapabilities::empty(),
 };
 
 #[derive(Debug, Clone, Copy, FromRepr, EnumIter, EnumCount)]
@@ -99,6 +113,7 @@ const CONTROL_INDEX_DECK_ONE: u32 = 0x0100;
 const CONTROL_INDEX_DECK_TWO: u32 = 0x0200;
 const CONTROL_INDEX_PERFORMANCE_DECK_ONE: u32 = 0x0300;
 const CONTROL_INDEX_PERFORMANCE_DECK_TWO: u32 = 0x0400;
+const CONTROL_INDEX_EFFECT: u32 = 0x0500;
 
 const CONTROL_INDEX_DECK_BIT_MASK: u32 = CONTROL_INDEX_DECK_ONE | CONTROL_INDEX_DECK_TWO;
 const CONTROL_INDEX_ENUM_BIT_MASK: u32 = (1 << CONTROL_INDEX_DECK_BIT_MASK.trailing_zeros()) - 1;
@@ -1,32 +1,50 @@
 // SPDX-FileCopyrightText: The djio authors
 // SPDX-License-Identifier: MPL-2.0
 
+use std::sync::LazyLock;
+
 use derive_more::From;
 use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator as _};
 
 use super::{
     Deck, CONTROL_INDEX_DECK_BIT_MASK, CONTROL_INDEX_DECK_ONE, CONTROL_INDEX_DECK_TWO,
-    CONTROL_INDEX_ENUM_BIT_MASK, MIDI_BEAT_FX, MIDI_COMMAND_NOTE_ON, MIDI_DECK_PLAYPAUSE_BUTTON,
-    MIDI_MASTER_CUE, MIDI_STATUS_BUTTON_MAIN,
+    CONTROL_INDEX_ENUM_BIT_MASK, CONTROL_INDEX_PERFORMANCE_DECK_ONE,
+    CONTROL_INDEX_PERFORMANCE_DECK_TWO, MIDI_BEAT_FX, MIDI_COMMAND_NOTE_ON,
+    MIDI_DECK_PLAYPAUSE_BUTTON, MIDI_MASTER_CUE, MIDI_STATUS_BUTTON_MAIN,
+    MIDI_STATUS_BUTTON_PERFORMANCE_DECK_ONE, MIDI_STATUS_BUTTON_PERFORMANCE_DECK_TWO,
 };
 use crate::{
     Control, ControlIndex, ControlOutputGateway, LedOutput, MidiOutputConnection,
-    MidiOutputGateway, OutputError, OutputResult,
+    MidiOutputGateway, OutputControlDescriptor, OutputControlKind, OutputError, OutputResult,
 };
 
 #[derive(Debug, Clone, Copy, From)]
 pub enum Led {
     Main(MainLed),
     Deck(Deck, DeckLed),
-    // TODO: Performance LEDs
+    /// Performance pad LED with the pad number in the range 0..8
+    ///
+    /// The pads are monochrome and each pad mode has its own set of LEDs.
+    PerformancePad(Deck, u8, PadMode),
 }
 
 impl Led {
+    /// Performance pad LED with a valid pad number
+    ///
+    /// Returns `None` if `pad` is not less than [`NUM_PERFORMANCE_PADS`].
+    #[must_use]
+    pub const fn try_performance_pad(deck: Deck, pad: u8, mode: PadMode) -> Option<Self> {
+        if pad >= NUM_PERFORMANCE_PADS {
+            return None;
+        }
+        Some(Self::PerformancePad(deck, pad, mode))
+    }
+
     #[must_use]
     pub const fn deck(self) -> Option<Deck> {
         match self {
             Self::Main(_) => None,
-            Self::Deck(deck, _) => Some(deck),
+            Self::Deck(deck, _) | Self::PerformancePad(deck, _, _) => Some(deck),
         }
     }
 
@@ -35,8 +53,129 @@ impl Led {
         match self {
             Self::Main(led) => ControlIndex::new(led as u32),
             Self::Deck(deck, led) => ControlIndex::new(deck.control_index_bit_mask() | led as u32),
+            Self::PerformancePad(deck, pad, mode) => {
+                let deck_bit = match deck {
+                    Deck::One => CONTROL_INDEX_PERFORMANCE_DECK_ONE,
+                    Deck::Two => CONTROL_INDEX_PERFORMANCE_DECK_TWO,
+                };
+                ControlIndex::new(deck_bit | mode.pad_id(pad) as u32)
+            }
         }
     }
+
+    /// All LEDs of a deck, including the performance pads
+    pub fn iter_deck(deck: Deck) -> impl Iterator<Item = Self> {
+        let button_leds = DeckLed::iter().map(move |led| Self::Deck(deck, led));
+        let pad_leds = PadMode::iter().flat_map(move |mode| {
+            (0..NUM_PERFORMANCE_PADS).map(move |pad| Self::PerformancePad(deck, pad, mode))
+        });
+        button_leds.chain(pad_leds)
+    }
+
+    /// All LEDs
+    pub fn iter() -> impl Iterator<Item = Self> {
+        MainLed::iter()
+            .map(Self::Main)
+            .chain(Deck::iter().flat_map(Self::iter_deck))
+    }
+
+    /// Human-readable name
+    #[must_use]
+    pub fn name(self) -> String {
+        match self {
+            Self::Main(led) => format!("{led:?}"),
+            Self::Deck(deck, led) => format!("Deck {deck:?} {led:?}"),
+            Self::PerformancePad(deck, pad, mode) => {
+                format!("Deck {deck:?} {mode:?} Pad {}", pad + 1)
+            }
+        }
+    }
+}
+
+/// Descriptors of all LEDs
+///
+/// All LEDs could only be switched on or off.
+#[must_use]
+pub fn output_controls() -> &'static [OutputControlDescriptor] {
+    static OUTPUT_CONTROLS: LazyLock<Vec<OutputControlDescriptor>> = LazyLock::new(|| {
+        Led::iter()
+            .map(|led| OutputControlDescriptor {
+                index: led.to_control_index(),
+                name: led.name().into(),
+                kind: OutputControlKind::OnOff,
+            })
+            .collect()
+    });
+    &OUTPUT_CONTROLS
+}
+
+/// Control indices of all LEDs of a deck
+///
+/// Allows to address the LEDs of a deck without knowing how
+/// the deck is encoded into the [`ControlIndex`].
+pub fn control_indices_for(deck: Deck) -> impl Iterator<Item = ControlIndex> {
+    Led::iter_deck(deck).map(Led::to_control_index)
+}
+
+/// Number of performance pads per deck
+pub const NUM_PERFORMANCE_PADS: u8 = 8;
+
+/// Pad mode of the performance pads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumCount)]
+pub enum PadMode {
+    HotCue,
+    PadFx1,
+    BeatJump,
+    Sampler,
+    Keyboard,
+    PadFx2,
+    BeatLoop,
+    KeyShift,
+}
+
+impl PadMode {
+    /// MIDI note number of a pad in this mode
+    ///
+    /// Out of range pads wrap around and never address the pads
+    /// of another mode. Use [`Led::try_performance_pad()`] for
+    /// validating the pad number.
+    #[must_use]
+    pub const fn pad_id(self, pad: u8) -> u8 {
+        debug_assert!(pad < NUM_PERFORMANCE_PADS);
+        let pad = pad % NUM_PERFORMANCE_PADS;
+        let offset = match self {
+            Self::HotCue => 0x00,
+            Self::PadFx1 => 0x10,
+            Self::BeatJump => 0x20,
+            Self::Sampler => 0x30,
+            Self::Keyboard => 0x40,
+            Self::PadFx2 => 0x50,
+            Self::BeatLoop => 0x60,
+            Self::KeyShift => 0x70,
+        };
+        offset + pad
+    }
+
+    /// Split a MIDI note number into pad mode and pad
+    #[must_use]
+    pub const fn try_from_pad_id(pad_id: u8) -> Option<(Self, u8)> {
+        let pad = pad_id & 0x0f;
+        if pad >= NUM_PERFORMANCE_PADS {
+            return None;
+        }
+        let mode = match pad_id >> 4 {
+            0x0 => Self::HotCue,
+            0x1 => Self::PadFx1,
+            0x2 => Self::BeatJump,
+            0x3 => Self::Sampler,
+            0x4 => Self::Keyboard,
+            0x5 => Self::PadFx2,
+            0x6 => Self::BeatLoop,
+            0x7 => Self::KeyShift,
+            _ => return None,
+        };
+        Some((mode, pad))
+    }
 }
 
 const LED_OFF: u8 = 0x00;
@@ -87,6 +226,16 @@ impl TryFrom<ControlIndex> for Led {
         let value = from.value();
         debug_assert!(CONTROL_INDEX_ENUM_BIT_MASK <= u8::MAX.into());
         let enum_index = (value & CONTROL_INDEX_ENUM_BIT_MASK) as u8;
+        let performance_deck = match value & !CONTROL_INDEX_ENUM_BIT_MASK {
+            CONTROL_INDEX_PERFORMANCE_DECK_ONE => Some(Deck::One),
+            CONTROL_INDEX_PERFORMANCE_DECK_TWO => Some(Deck::Two),
+            _ => None,
+        };
+        if let Some(deck) = performance_deck {
+            return PadMode::try_from_pad_id(enum_index)
+                .map(|(mode, pad)| Led::PerformancePad(deck, pad, mode))
+                .ok_or(InvalidOutputControlIndex);
+        }
         let deck = match value & CONTROL_INDEX_DECK_BIT_MASK {
             CONTROL_INDEX_DECK_ONE => Deck::One,
             CONTROL_INDEX_DECK_TWO => Deck::Two,
@@ -124,11 +273,39 @@ pub const fn led_output_into_midi_message(led: Led, output: LedOutput) -> [u8; 3
             };
             (status, data1)
         }
+        Led::PerformancePad(deck, pad, mode) => {
+            let status = match deck {
+                Deck::One => MIDI_STATUS_BUTTON_PERFORMANCE_DECK_ONE,
+                Deck::Two => MIDI_STATUS_BUTTON_PERFORMANCE_DECK_TWO,
+            };
+            (status, mode.pad_id(pad))
+        }
     };
     let data2 = led_to_u7(output);
     [status, data1, data2]
 }
 
+/// Inverse of [`led_output_into_midi_message()`]
+///
+/// Could be used for interpreting messages that are echoed back by the
+/// controller. Any non-zero value is interpreted as [`LedOutput::On`].
+#[must_use]
+pub fn midi_message_into_led_output(message: &[u8]) -> Option<(Led, LedOutput)> {
+    let [status, data1, data2] = *message else {
+        return None;
+    };
+    let led = Led::iter().find(|&led| {
+        let [led_status, led_data1, _] = led_output_into_midi_message(led, LedOutput::Off);
+        led_status == status && led_data1 == data1
+    })?;
+    let output = if data2 == LED_OFF {
+        LedOutput::Off
+    } else {
+        LedOutput::On
+    };
+    Some((led, output))
+}
+
 fn send_led_output<C: MidiOutputConnection>(
     midi_output_connection: &mut C,
     led: Led,
@@ -137,6 +314,19 @@ fn send_led_output<C: MidiOutputConnection>(
     midi_output_connection.send_midi_output(&led_output_into_midi_message(led, output))
 }
 
+fn send_led_outputs<C: MidiOutputConnection>(
+    midi_output_connection: &mut C,
+    leds: impl IntoIterator<Item = Led>,
+    output: LedOutput,
+) -> OutputResult<()> {
+    let messages = leds
+        .into_iter()
+        .map(|led| led_output_into_midi_message(led, output))
+        .collect::<Vec<_>>();
+    let messages = messages.iter().map(<[u8; 3]>::as_slice).collect::<Vec<_>>();
+    midi_output_connection.send_midi_outputs(&messages)
+}
+
 fn on_attach<C: MidiOutputConnection>(midi_output_connection: &mut C) -> OutputResult<()> {
     // TODO: How to query the initial position of all knobs and faders?
     turn_off_all_leds(midi_output_connection)?;
@@ -149,15 +339,7 @@ fn on_detach<C: MidiOutputConnection>(midi_output_connection: &mut C) -> OutputR
 }
 
 fn turn_off_all_leds<C: MidiOutputConnection>(midi_output_connection: &mut C) -> OutputResult<()> {
-    for led in MainLed::iter() {
-        send_led_output(midi_output_connection, led.into(), LedOutput::Off)?;
-    }
-    for deck in Deck::iter() {
-        for led in DeckLed::iter() {
-            send_led_output(midi_output_connection, Led::Deck(deck, led), LedOutput::Off)?;
-        }
-    }
-    Ok(())
+    send_led_outputs(midi_output_connection, Led::iter(), LedOutput::Off)
 }
 
 #[derive(Debug)]
@@ -181,14 +363,23 @@ impl<C: MidiOutputConnection> OutputGateway<C> {
         };
         send_led_output(midi_output_connection, led, output)
     }
+
+    /// Reset the hardware into a defined state without detaching it
+    ///
+    /// Turns off all LEDs like when detaching the MIDI output connection.
+    pub fn reset(&mut self) -> OutputResult<()> {
+        let Some(midi_output_connection) = &mut self.midi_output_connection else {
+            return Err(OutputError::Disconnected);
+        };
+        on_detach(midi_output_connection)
+    }
 }
 
 impl<C: MidiOutputConnection> ControlOutputGateway for OutputGateway<C> {
     fn send_output(&mut self, output: &Control) -> OutputResult<()> {
         let Control { index, value } = *output;
-        let led = Led::try_from(index).map_err(|InvalidOutputControlIndex| OutputError::Send {
-            msg: format!("No LED with control index {index}").into(),
-        })?;
+        let led = Led::try_from(index)
+            .map_err(|InvalidOutputControlIndex| OutputError::UnknownControl(index))?;
         self.send_led_output(led, value.into())
     }
 }
@@ -217,3 +408,55 @@ impl<C: MidiOutputConnection> MidiOutputGateway<C> for OutputGateway<C> {
         Some(midi_output_connection)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use strum::IntoEnumIterator as _;
+
+    use super::{
+        led_output_into_midi_message, midi_message_into_led_output, output_controls, Deck, Led,
+        PadMode, NUM_PERFORMANCE_PADS,
+    };
+    use crate::LedOutput;
+
+    #[test]
+    fn output_control_indices_are_unique() {
+        let mut indices = HashSet::new();
+        for control in output_controls() {
+            assert!(indices.insert(control.index), "{}", control.name);
+            let led = Led::try_from(control.index).unwrap();
+            assert_eq!(control.index, led.to_control_index());
+        }
+    }
+
+    #[test]
+    fn midi_message_round_trip() {
+        for led in Led::iter() {
+            for output in [LedOutput::Off, LedOutput::On] {
+                let message = led_output_into_midi_message(led, output);
+                let (decoded_led, decoded_output) = midi_message_into_led_output(&message).unwrap();
+                assert_eq!(led.to_control_index(), decoded_led.to_control_index());
+                assert_eq!(output, decoded_output);
+            }
+        }
+    }
+
+    #[test]
+    fn performance_pad_range() {
+        for mode in PadMode::iter() {
+            for pad in 0..NUM_PERFORMANCE_PADS {
+                let led = Led::try_performance_pad(Deck::One, pad, mode).unwrap();
+                assert_eq!(
+                    led.to_control_index(),
+                    Led::try_from(led.to_control_index())
+                        .unwrap()
+                        .to_control_index()
+                );
+            }
+            assert!(Led::try_performance_pad(Deck::Two, NUM_PERFORMANCE_PADS, mode).is_none());
+            assert!(Led::try_performance_pad(Deck::Two, u8::MAX, mode).is_none());
+        }
+    }
+}
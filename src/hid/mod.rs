@@ -14,7 +14,7 @@ use thiserror::Error;
 pub mod report;
 
 pub mod thread;
-pub use thread::HidThread;
+pub use thread::{HidThread, HidThreadConfig};
 
 #[derive(Debug, Error)]
 pub enum HidDeviceError {
@@ -205,6 +205,83 @@ impl HidApi {
     }
 }
 
+/// Change reported by [`HidDeviceWatcher::poll()`]
+#[allow(missing_debug_implementations)]
+pub enum HidDeviceChange {
+    Added(HidDevice),
+    Removed(DeviceId<'static>),
+}
+
+/// Detects added and removed devices
+///
+/// Devices are identified by their [`DeviceId`], i.e. replugging the
+/// same device is recognized. Devices without a serial number are ignored.
+#[allow(missing_debug_implementations)]
+pub struct HidDeviceWatcher {
+    api: HidApi,
+    known_devices: HashSet<DeviceId<'static>>,
+}
+
+impl HidDeviceWatcher {
+    #[must_use]
+    pub fn new(api: HidApi) -> Self {
+        Self {
+            api,
+            known_devices: HashSet::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn api(&self) -> &HidApi {
+        &self.api
+    }
+
+    #[must_use]
+    pub fn into_api(self) -> HidApi {
+        let Self { api, .. } = self;
+        api
+    }
+
+    /// Devices that have been detected by the last poll
+    pub fn known_devices(&self) -> impl Iterator<Item = &DeviceId<'static>> {
+        self.known_devices.iter()
+    }
+
+    /// Query all devices and report changes since the last poll
+    ///
+    /// Initially all devices are reported as added.
+    pub fn poll(&mut self) -> HidResult<Vec<HidDeviceChange>> {
+        let Self { api, known_devices } = self;
+        let mut changes = Vec::new();
+        let mut detected_devices = HashSet::with_capacity(known_devices.len());
+        for info in api.query_devices()? {
+            let Ok(id) = DeviceId::try_from(info) else {
+                log::debug!(
+                    "Ignoring device without serial number: {path:?}",
+                    path = info.path()
+                );
+                continue;
+            };
+            if detected_devices.contains(&id) {
+                // Multiple interfaces of the same device
+                continue;
+            }
+            let id = id.into_owned();
+            if !known_devices.contains(&id) {
+                log::debug!("Added device {id:?}");
+                changes.push(HidDeviceChange::Added(HidDevice::new(info.clone())));
+            }
+            detected_devices.insert(id);
+        }
+        for id in known_devices.difference(&detected_devices) {
+            log::debug!("Removed device {id:?}");
+            changes.push(HidDeviceChange::Removed(id.clone()));
+        }
+        *known_devices = detected_devices;
+        Ok(changes)
+    }
+}
+
 #[allow(missing_debug_implementations)]
 pub struct HidDevice {
     info: DeviceInfo,
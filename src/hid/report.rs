@@ -1,23 +1,66 @@
 // SPDX-FileCopyrightText: The djio authors
 // SPDX-License-Identifier: MPL-2.0
 
+use std::time::Duration;
+
+use crate::TimeStamp;
+
+/// Counters of [`BufferRecycler`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferRecyclerStats {
+    /// Number of newly allocated buffers
+    pub allocated: usize,
+
+    /// Number of reused buffers
+    pub reused: usize,
+
+    /// Number of buffers that have been dropped instead of recycling them
+    pub dropped: usize,
+}
+
 #[derive(Debug)]
 pub struct BufferRecycler {
     // Recycle report buffers, one slot per report id
     recycled: Vec<Vec<Vec<u8>>>,
+    max_pool_size: usize,
+    stats: BufferRecyclerStats,
 }
 
 impl BufferRecycler {
+    /// Default maximum number of recycled buffers per report id
+    pub const DEFAULT_MAX_POOL_SIZE: usize = 16;
+
     #[must_use]
     pub fn new() -> Self {
+        Self::with_max_pool_size(Self::DEFAULT_MAX_POOL_SIZE)
+    }
+
+    /// Create a new instance with a custom pool size
+    ///
+    /// Recycled buffers that exceed the maximum number of buffers
+    /// per report id are dropped.
+    #[must_use]
+    pub fn with_max_pool_size(max_pool_size: usize) -> Self {
         Self {
             // One slot per report id
             recycled: std::iter::repeat(Vec::new())
                 .take(usize::from(u8::MAX) + 1)
                 .collect(),
+            max_pool_size,
+            stats: BufferRecyclerStats::default(),
         }
     }
 
+    #[must_use]
+    pub const fn max_pool_size(&self) -> usize {
+        self.max_pool_size
+    }
+
+    #[must_use]
+    pub const fn stats(&self) -> &BufferRecyclerStats {
+        &self.stats
+    }
+
     #[must_use]
     pub fn try_fetch_buf(&mut self, report_id: u8) -> Option<Vec<u8>> {
         let index = usize::from(report_id);
@@ -31,6 +74,7 @@ impl BufferRecycler {
         let report_id = data[0];
         if let Some(mut recycled) = self.try_fetch_buf(report_id) {
             debug_assert_eq!(recycled[0], report_id);
+            self.stats.reused += 1;
             // All reports of the same id usually have the same length and
             // resizing won't have any affect. This is also the reason why
             // we have picked an arbitrary buffer from those that have been
@@ -49,6 +93,7 @@ impl BufferRecycler {
             }
             recycled
         } else {
+            self.stats.allocated += 1;
             data.to_vec()
         }
     }
@@ -58,7 +103,13 @@ impl BufferRecycler {
         let index = usize::from(report_id);
         debug_assert!(index < self.recycled.len());
         #[allow(unsafe_code)]
-        unsafe { self.recycled.get_unchecked_mut(index) }.push(buffer);
+        let pool = unsafe { self.recycled.get_unchecked_mut(index) };
+        if pool.len() >= self.max_pool_size {
+            log::debug!("Dropping buffer of report {report_id}: pool is full");
+            self.stats.dropped += 1;
+            return;
+        }
+        pool.push(buffer);
     }
 }
 
@@ -67,3 +118,60 @@ impl Default for BufferRecycler {
         Self::new()
     }
 }
+
+/// Statistics about received reports
+#[derive(Debug, Clone, Default)]
+pub struct ReportStats {
+    count: usize,
+    last_ts: Option<TimeStamp>,
+    max_duration_since_last_instant: Option<Duration>,
+}
+
+impl ReportStats {
+    /// Count a report received at the given time stamp.
+    ///
+    /// Returns the total number of reports and the duration since
+    /// the previous report.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Never panics
+    pub fn update(&mut self, ts: TimeStamp) -> (usize, Option<Duration>) {
+        self.count = self.count.checked_add(1).unwrap();
+        let duration_since_last_instant = self
+            .last_ts
+            .map(|last_ts| ts.duration_since(last_ts).unwrap_or_default());
+        self.last_ts = Some(ts);
+        self.max_duration_since_last_instant =
+            duration_since_last_instant.map(|duration_since_last_instant| {
+                if let Some(max_duration_since_last_instant) = self.max_duration_since_last_instant
+                {
+                    max_duration_since_last_instant.max(duration_since_last_instant)
+                } else {
+                    duration_since_last_instant
+                }
+            });
+        (self.count, duration_since_last_instant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BufferRecycler, BufferRecyclerStats};
+
+    #[test]
+    fn recycle_bounded() {
+        let mut recycler = BufferRecycler::with_max_pool_size(1);
+        let first = recycler.fill_buf(&[1, 2, 3]);
+        let second = recycler.fill_buf(&[1, 4, 5]);
+        recycler.recycle_buf(first);
+        recycler.recycle_buf(second);
+        assert_eq!(vec![1, 6, 7], recycler.fill_buf(&[1, 6, 7]));
+        assert_eq!(
+            &BufferRecyclerStats {
+                allocated: 2,
+                reused: 1,
+                dropped: 1,
+            },
+            recycler.stats()
+        );
+    }
+}
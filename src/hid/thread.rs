@@ -3,12 +3,13 @@
 
 use std::{
     any::Any,
-    mem::MaybeUninit,
+    panic::{self, AssertUnwindSafe},
     thread::JoinHandle,
     time::{Duration, Instant},
 };
 
 use super::{HidDevice, HidDeviceError, HidError, HidResult};
+use crate::{MonotonicClock, TimeStamp};
 
 #[derive(Debug, Clone, Copy)]
 pub enum State {
@@ -24,10 +25,36 @@ pub enum State {
 #[allow(missing_debug_implementations)]
 pub enum Event<'e> {
     StateChanged(State),
+    /// The command channel has been disconnected unexpectedly
+    ///
+    /// Emitted before terminating the thread.
+    Disconnected,
+    /// Handling a command or a scheduled read request panicked
+    ///
+    /// The command is discarded and the thread continues.
+    CommandPanicked {
+        /// Payload of the panic
+        panic: Box<dyn Any + Send + 'static>,
+    },
     ReportRead {
+        /// Time stamp when the report has been read
+        ts: TimeStamp,
         data: &'e [u8],
     },
-    ReportReadError(HidError),
+    ReportReadError {
+        ts: TimeStamp,
+        err: HidError,
+    },
+    /// The device is considered as disconnected after too many
+    /// subsequent read errors
+    ///
+    /// Emitted before terminating the thread. The owner could try to
+    /// reconnect the device when it becomes available again, e.g. as
+    /// reported by [`crate::HidDeviceWatcher`].
+    DeviceDisconnected {
+        /// The last error
+        err: HidError,
+    },
     ReportWritten {
         /// Return buffer for recycling to minimize allocations
         buf: Vec<u8>,
@@ -47,14 +74,28 @@ pub enum Event<'e> {
         deadline: Instant,
     },
     FeatureReportRead {
+        /// Time stamp when the feature report has been read
+        ts: TimeStamp,
         buf: Vec<u8>,
         buf_len: usize,
     },
     FeatureReportReadError {
+        ts: TimeStamp,
         /// Return buffer for recycling to minimize allocations
         buf: Vec<u8>,
         err: HidError,
     },
+    /// Result of [`Command::ScheduleFeatureReportRead`]
+    ScheduledFeatureReportRead {
+        /// Time stamp when the feature report has been read
+        ts: TimeStamp,
+        data: &'e [u8],
+    },
+    ScheduledFeatureReportReadError {
+        ts: TimeStamp,
+        report_id: u8,
+        err: HidError,
+    },
     FeatureReportWritten {
         /// Return buffer for recycling to minimize allocations
         buf: Vec<u8>,
@@ -82,6 +123,26 @@ pub enum Command {
         buf_len: usize,
         deadline: Option<Instant>,
     },
+    /// Replace [`HidThreadConfig::first_read_timeout`]
+    ///
+    /// Takes effect with the next cycle.
+    SetReadTimeout(Duration),
+    /// Read a feature report periodically
+    ///
+    /// The first read request is due immediately. Results are emitted as
+    /// [`Event::ScheduledFeatureReportRead`] or
+    /// [`Event::ScheduledFeatureReportReadError`].
+    ///
+    /// Replaces an existing schedule for the same report id. A zero
+    /// `period` results in a read request during each cycle.
+    ScheduleFeatureReportRead {
+        report_id: u8,
+        period: Duration,
+    },
+    /// Stop reading a feature report periodically
+    CancelFeatureReportRead {
+        report_id: u8,
+    },
     Terminate,
 }
 
@@ -119,107 +180,342 @@ pub struct HidThread<C: CommandReceiver + EventHandler> {
 const READ_BUFFER_SIZE: usize = 1 + 16384;
 
 // hidapi only supports timeouts with millisecond precision.
-const MIN_READ_TIMEOUT: Duration = Duration::from_millis(1); // 1 kHz
+const DEFAULT_MIN_READ_TIMEOUT: Duration = Duration::from_millis(1); // 1 kHz
 
-const FIRST_READ_TIMEOUT: Duration = MIN_READ_TIMEOUT;
+const DEFAULT_FIRST_READ_TIMEOUT: Duration = DEFAULT_MIN_READ_TIMEOUT;
 
 // Prevent burning too much CPU if a device is not acting as expected.
 // This is achieved by limiting the maximum polling frequency as defined
 // by the corresponding minimum cycle time.
-// Could be disabled by setting it to `Duration::ZERO`.
-const MIN_CYCLE_TIME: Duration = Duration::from_micros(250); // 4 kHz
+const DEFAULT_MIN_CYCLE_TIME: Duration = Duration::from_micros(250); // 4 kHz
+
+// Read errors usually occur if the device has been unplugged.
+const DEFAULT_MAX_CONSECUTIVE_READ_ERRORS: u32 = 8;
+
+/// Timing parameters of [`HidThread`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HidThreadConfig {
+    /// Minimum duration of each read cycle
+    ///
+    /// Limits the maximum polling frequency. Could be disabled
+    /// by setting it to `Duration::ZERO`.
+    pub min_cycle_time: Duration,
+
+    /// Timeout of the first read request in each cycle
+    ///
+    /// Subsequent read requests within the same cycle return immediately.
+    pub first_read_timeout: Duration,
 
-struct ReadSlot {
-    buf: MaybeUninit<[u8; READ_BUFFER_SIZE]>,
-    len: usize,
+    /// Lower bound of the first read timeout
+    ///
+    /// Must be a non-zero number of full milliseconds as required by hidapi.
+    pub min_read_timeout: Duration,
+
+    /// Number of consecutive read errors until the device is considered
+    /// as disconnected
+    ///
+    /// See also [`Event::DeviceDisconnected`].
+    pub max_consecutive_read_errors: u32,
 }
 
-impl ReadSlot {
-    const fn new() -> Self {
+impl Default for HidThreadConfig {
+    fn default() -> Self {
         Self {
-            buf: MaybeUninit::uninit(),
-            len: 0,
+            min_cycle_time: DEFAULT_MIN_CYCLE_TIME,
+            first_read_timeout: DEFAULT_FIRST_READ_TIMEOUT,
+            min_read_timeout: DEFAULT_MIN_READ_TIMEOUT,
+            max_consecutive_read_errors: DEFAULT_MAX_CONSECUTIVE_READ_ERRORS,
         }
     }
 }
 
-fn handle_command(device: &mut HidDevice, command: Command) -> Option<Event<'_>> {
+/// Last seen content of each report id
+///
+/// Deduplicates subsequent reports with the same id and content, i.e.
+/// considers them as idempotent. Reports with different ids could be
+/// interleaved arbitrarily.
+///
+/// The buffer for each report id is only allocated when the report id
+/// is seen for the first time.
+struct ReportDedup {
+    last_reports: Box<[Option<Vec<u8>>]>,
+}
+
+impl ReportDedup {
+    fn new() -> Self {
+        Self {
+            last_reports: std::iter::repeat_with(|| None)
+                .take(usize::from(u8::MAX) + 1)
+                .collect(),
+        }
+    }
+
+    /// Check if the report equals the last report with the same id
+    ///
+    /// Remembers the report if it differs.
+    fn is_duplicate(&mut self, report: &[u8]) -> bool {
+        let Some(&id) = report.first() else {
+            return false;
+        };
+        let last_report = &mut self.last_reports[usize::from(id)];
+        match last_report {
+            Some(last_report) => {
+                if last_report.as_slice() == report {
+                    return true;
+                }
+                last_report.clear();
+                last_report.extend_from_slice(report);
+            }
+            None => {
+                *last_report = Some(report.to_vec());
+            }
+        }
+        false
+    }
+}
+
+fn is_deadline_expired(deadline: Instant, now: Instant) -> bool {
+    deadline < now
+}
+
+#[derive(Debug)]
+struct ScheduledFeatureReportRead {
+    report_id: u8,
+    period: Duration,
+    next_due: Instant,
+}
+
+/// Periodic read requests of feature reports
+#[derive(Debug, Default)]
+struct FeatureReportReadSchedule {
+    scheduled: Vec<ScheduledFeatureReportRead>,
+}
+
+impl FeatureReportReadSchedule {
+    fn schedule(&mut self, report_id: u8, period: Duration, now: Instant) {
+        self.cancel(report_id);
+        self.scheduled.push(ScheduledFeatureReportRead {
+            report_id,
+            period,
+            next_due: now,
+        });
+    }
+
+    fn cancel(&mut self, report_id: u8) {
+        self.scheduled
+            .retain(|scheduled| scheduled.report_id != report_id);
+    }
+
+    /// Collect the ids of all reports that are due
+    ///
+    /// Reschedules the next read request of each due report.
+    fn take_due(&mut self, now: Instant) -> impl Iterator<Item = u8> + '_ {
+        self.scheduled.iter_mut().filter_map(move |scheduled| {
+            if scheduled.next_due > now {
+                return None;
+            }
+            let next_due = scheduled.next_due + scheduled.period;
+            scheduled.next_due = if next_due > now {
+                next_due
+            } else {
+                // Skip missed periods instead of catching up
+                now + scheduled.period
+            };
+            Some(scheduled.report_id)
+        })
+    }
+}
+
+/// Commands that access the device
+///
+/// All other commands are handled by the thread loop.
+enum DeviceCommand {
+    GetFeatureReport {
+        buf: Vec<u8>,
+    },
+    SendFeatureReport {
+        buf: Vec<u8>,
+        buf_len: usize,
+    },
+    Write {
+        buf: Vec<u8>,
+        buf_len: usize,
+        deadline: Option<Instant>,
+    },
+}
+
+fn handle_device_command(
+    device: &mut HidDevice,
+    clock: &MonotonicClock,
+    command: DeviceCommand,
+) -> Event<'static> {
     match command {
-        Command::Terminate => None,
-        Command::ReadFeatureReport { mut buf } => {
+        DeviceCommand::GetFeatureReport { mut buf } => {
             debug_assert!(!buf.is_empty());
-            match device.get_feature_report(&mut buf) {
-                Ok(bytes_read) => Some(Event::FeatureReportRead {
+            let result = device.get_feature_report(&mut buf);
+            let ts = clock.now();
+            match result {
+                Ok(bytes_read) => Event::FeatureReportRead {
+                    ts,
                     buf,
                     buf_len: bytes_read,
-                }),
-                Err(err) => Some(Event::FeatureReportReadError { buf, err }),
+                },
+                Err(err) => Event::FeatureReportReadError { ts, buf, err },
             }
         }
-        Command::WriteFeatureReport { buf, buf_len } => {
+        DeviceCommand::SendFeatureReport { buf, buf_len } => {
             debug_assert!(buf_len > 0);
             debug_assert!(buf_len <= buf.len());
             match device.send_feature_report(&buf[0..buf_len]) {
-                Ok(()) => Some(Event::FeatureReportWritten { buf, buf_len }),
-                Err(err) => Some(Event::FeatureReportWriteError { buf, buf_len, err }),
+                Ok(()) => Event::FeatureReportWritten { buf, buf_len },
+                Err(err) => Event::FeatureReportWriteError { buf, buf_len, err },
             }
         }
-        Command::WriteReport {
+        DeviceCommand::Write {
             buf,
             buf_len,
             deadline,
         } => {
             debug_assert!(buf_len > 0);
             debug_assert!(buf_len <= buf.len());
-            let expired = deadline.map_or(false, |deadline| deadline > Instant::now());
-            if expired {
-                debug_assert!(deadline.is_some());
-                Some(Event::ReportWriteExpired {
+            let now = Instant::now();
+            if let Some(deadline) = deadline.filter(|&deadline| is_deadline_expired(deadline, now))
+            {
+                Event::ReportWriteExpired {
                     buf,
                     buf_len,
-                    deadline: deadline.unwrap(),
-                })
+                    deadline,
+                }
             } else {
                 match device.write(&buf[0..buf_len]) {
-                    Ok(bytes_written) => Some(Event::ReportWritten {
+                    Ok(bytes_written) => Event::ReportWritten {
                         buf,
                         buf_len,
                         bytes_written,
-                    }),
-                    Err(err) => Some(Event::ReportWriteError { buf, buf_len, err }),
+                    },
+                    Err(err) => Event::ReportWriteError { buf, buf_len, err },
                 }
             }
         }
     }
 }
 
-#[allow(unsafe_code)]
 #[allow(clippy::too_many_lines)] // TODO: Extract functions
-fn thread_fn<C: CommandReceiver + EventHandler>(environment: &mut Environment<C>) {
+fn thread_fn<C: CommandReceiver + EventHandler>(
+    environment: &mut Environment<C>,
+    config: &HidThreadConfig,
+) {
+    let HidThreadConfig {
+        min_cycle_time,
+        mut first_read_timeout,
+        min_read_timeout,
+        max_consecutive_read_errors,
+    } = *config;
     let Environment {
         connected_device: device,
+        clock,
         context,
     } = environment;
-    // Double-buffering for deduplication of subsequent incoming reports
-    let mut read_slots = [ReadSlot::new(), ReadSlot::new()];
-    let mut last_read_slot_index = 0;
+    let mut read_buf = vec![0; READ_BUFFER_SIZE];
+    let mut report_dedup = ReportDedup::new();
+    let mut feature_report_read_schedule = FeatureReportReadSchedule::default();
     let mut last_read_cycle_started = Instant::now();
-    while let Ok(command) = context.try_recv_command() {
+    let mut consecutive_read_errors = 0;
+    'cycle: loop {
+        let Ok(command) = context.try_recv_command() else {
+            log::warn!("Command channel disconnected");
+            context.handle_event(Event::Disconnected);
+            break;
+        };
         // Handle a single command during each cycle.
-        if let Some(command) = command {
-            if let Some(event) = handle_command(device, command) {
-                context.handle_event(event);
-            } else {
-                // Received a termination command
-                break;
+        let device_command = match command {
+            None => None,
+            Some(Command::Terminate) => break,
+            Some(Command::SetReadTimeout(read_timeout)) => {
+                log::debug!(
+                    "Changing read timeout: {millis:0.3} ms",
+                    millis = read_timeout.as_secs_f64() * 1_000.0
+                );
+                first_read_timeout = read_timeout;
+                None
+            }
+            Some(Command::ScheduleFeatureReportRead { report_id, period }) => {
+                log::debug!(
+                    "Scheduling feature report read (id = {report_id}): {millis:0.3} ms",
+                    millis = period.as_secs_f64() * 1_000.0
+                );
+                feature_report_read_schedule.schedule(report_id, period, Instant::now());
+                None
+            }
+            Some(Command::CancelFeatureReportRead { report_id }) => {
+                log::debug!("Canceling feature report read (id = {report_id})");
+                feature_report_read_schedule.cancel(report_id);
+                None
+            }
+            Some(Command::ReadFeatureReport { buf }) => {
+                Some(DeviceCommand::GetFeatureReport { buf })
+            }
+            Some(Command::WriteFeatureReport { buf, buf_len }) => {
+                Some(DeviceCommand::SendFeatureReport { buf, buf_len })
+            }
+            Some(Command::WriteReport {
+                buf,
+                buf_len,
+                deadline,
+            }) => Some(DeviceCommand::Write {
+                buf,
+                buf_len,
+                deadline,
+            }),
+        };
+        if let Some(device_command) = device_command {
+            match panic::catch_unwind(AssertUnwindSafe(|| {
+                handle_device_command(device, clock, device_command)
+            })) {
+                Ok(event) => {
+                    context.handle_event(event);
+                }
+                Err(panic) => {
+                    log::error!("Handling command panicked");
+                    context.handle_event(Event::CommandPanicked { panic });
+                }
+            }
+        }
+        // Read all feature reports that are due. The buffer for reading
+        // reports is reused, because it is only needed further below.
+        for report_id in feature_report_read_schedule.take_due(Instant::now()) {
+            read_buf[0] = report_id;
+            let result = match panic::catch_unwind(AssertUnwindSafe(|| {
+                device.get_feature_report(&mut read_buf)
+            })) {
+                Ok(result) => result,
+                Err(panic) => {
+                    log::error!("Reading feature report panicked (id = {report_id})");
+                    context.handle_event(Event::CommandPanicked { panic });
+                    continue;
+                }
+            };
+            let ts = clock.now();
+            match result {
+                Ok(bytes_read) => {
+                    let data = &read_buf[..bytes_read];
+                    context.handle_event(Event::ScheduledFeatureReportRead { ts, data });
+                }
+                Err(err) => {
+                    context.handle_event(Event::ScheduledFeatureReportReadError {
+                        ts,
+                        report_id,
+                        err,
+                    });
+                }
             }
         }
         // Each new cycle starts with a read request, even though command processing
         // is placed at the top of the loop body. This improves readability and only
         // affects the execution order of the initial cycle.
         let mut read_cycle_started = Instant::now();
-        if !MIN_CYCLE_TIME.is_zero() {
-            let earliest_next_read_cycle = last_read_cycle_started + MIN_CYCLE_TIME;
+        if !min_cycle_time.is_zero() {
+            let earliest_next_read_cycle = last_read_cycle_started + min_cycle_time;
             while earliest_next_read_cycle > read_cycle_started {
                 let sleep_duration = earliest_next_read_cycle.duration_since(read_cycle_started);
                 log::trace!(
@@ -239,71 +535,59 @@ fn thread_fn<C: CommandReceiver + EventHandler>(environment: &mut Environment<C>
         debug_assert!(read_cycle_started >= last_read_cycle_started);
         let elapsed_since_last_read_cycle =
             read_cycle_started.duration_since(last_read_cycle_started);
-        let mut next_read_timeout = if FIRST_READ_TIMEOUT > elapsed_since_last_read_cycle {
-            let next_read_timeout = FIRST_READ_TIMEOUT - elapsed_since_last_read_cycle;
-            // Truncate to milliseconds as expected by hidapi
-            #[allow(clippy::cast_possible_truncation)]
-            if next_read_timeout < MIN_READ_TIMEOUT {
-                // Ensure that the first timeout is not 0
-                MIN_READ_TIMEOUT
-            } else {
-                Duration::from_millis(next_read_timeout.as_millis() as u64)
-            }
-        } else {
-            Duration::ZERO
-        };
+        let mut next_read_timeout = first_read_timeout
+            .checked_sub(elapsed_since_last_read_cycle)
+            .filter(|next_read_timeout| !next_read_timeout.is_zero())
+            .map_or(Duration::ZERO, |next_read_timeout| {
+                // Truncate to milliseconds as expected by hidapi
+                #[allow(clippy::cast_possible_truncation)]
+                if next_read_timeout < min_read_timeout {
+                    // Ensure that the first timeout is not 0
+                    min_read_timeout
+                } else {
+                    Duration::from_millis(next_read_timeout.as_millis() as u64)
+                }
+            });
         loop {
-            let read_slot_index = (last_read_slot_index + 1) % read_slots.len();
-            {
-                let read_slot = unsafe { read_slots.get_unchecked_mut(read_slot_index) };
-                let read_buf = unsafe { read_slot.buf.assume_init_mut() };
-                let read_timeout = next_read_timeout;
-                // Reset the timeout for all subsequent read requests.
-                next_read_timeout = Duration::ZERO;
-                let bytes_read = match device.read(read_buf, Some(read_timeout)) {
-                    Ok(count) => count,
-                    Err(err) => {
-                        context.handle_event(Event::ReportReadError(err));
-                        continue;
+            let read_timeout = next_read_timeout;
+            // Reset the timeout for all subsequent read requests.
+            next_read_timeout = Duration::ZERO;
+            let result = device.read(&mut read_buf, Some(read_timeout));
+            let ts = clock.now();
+            let bytes_read = match result {
+                Ok(count) => count,
+                Err(err) => {
+                    consecutive_read_errors += 1;
+                    if consecutive_read_errors >= max_consecutive_read_errors {
+                        log::warn!(
+                            "Device disconnected after {consecutive_read_errors} read error(s)"
+                        );
+                        context.handle_event(Event::DeviceDisconnected { err });
+                        break 'cycle;
                     }
-                };
-                debug_assert!(bytes_read < READ_BUFFER_SIZE);
-                if bytes_read > 0 {
-                    read_slot.len = bytes_read;
-                } else {
-                    // No report received -> exit loop
+                    context.handle_event(Event::ReportReadError { ts, err });
+                    // Continue with the next cycle instead of spinning
                     break;
                 }
+            };
+            consecutive_read_errors = 0;
+            debug_assert!(bytes_read < READ_BUFFER_SIZE);
+            if bytes_read == 0 {
+                // No report received -> exit loop
+                break;
             }
-            let read_slot = unsafe { read_slots.get_unchecked(read_slot_index) };
-            let read_buf = unsafe { read_slot.buf.assume_init() };
-            debug_assert!(read_slot.len > 0);
-            // Dedup subsequent reports with the same id and content,
-            // i.e. consider them as idempotent.
-            //
-            // This simple dedup algorithm based on double-buffering is ineffective
-            // for devices that send reports with alternating identifiers. However,
-            // we are not aware of any HID devices  that send reports with alternating
-            // identifiers at a high frequency.
-            let last_read_slot = unsafe { read_slots.get_unchecked(last_read_slot_index) };
-            if read_slot.len == last_read_slot.len {
-                let last_read_buf = unsafe { last_read_slot.buf.assume_init() };
-                if read_buf[..read_slot.len] == last_read_buf[..read_slot.len] {
-                    log::trace!(
-                        "Discarding duplicate report (id = {id}, len = {len})",
-                        id = read_buf[0],
-                        len = read_slot.len
-                    );
-                    continue;
-                }
+            let data = &read_buf[..bytes_read];
+            if report_dedup.is_duplicate(data) {
+                log::trace!(
+                    "Discarding duplicate report (id = {id}, len = {len})",
+                    id = data[0],
+                    len = data.len()
+                );
+                continue;
             }
-            // Mark the read slot as occupied.
-            last_read_slot_index = read_slot_index;
             last_read_cycle_started = read_cycle_started;
             // Consume the report.
-            context.handle_event(Event::ReportRead {
-                data: &read_buf[0..read_slot.len],
-            });
+            context.handle_event(Event::ReportRead { ts, data });
         }
     }
     context.handle_event(Event::StateChanged(State::Terminating));
@@ -313,6 +597,9 @@ fn thread_fn<C: CommandReceiver + EventHandler>(environment: &mut Environment<C>
 pub struct Environment<C> {
     pub connected_device: HidDevice,
 
+    /// Time stamps all reports that are read
+    pub clock: MonotonicClock,
+
     pub context: C,
 }
 
@@ -320,13 +607,15 @@ impl<C> HidThread<C>
 where
     C: CommandReceiver + EventHandler + Send + 'static,
 {
-    pub fn spawn(environment: Environment<C>) -> HidResult<Self> {
+    pub fn spawn(environment: Environment<C>, config: HidThreadConfig) -> HidResult<Self> {
         if !environment.connected_device.is_connected() {
             return Err(HidDeviceError::NotConnected.into());
         }
+        debug_assert!(!config.min_read_timeout.is_zero());
+        debug_assert_eq!(0, config.min_read_timeout.subsec_nanos() % 1_000_000);
         let join_handle = std::thread::spawn(move || {
             let mut environment = environment;
-            thread_fn(&mut environment);
+            thread_fn(&mut environment, &config);
             environment
         });
         log::debug!("Spawned thread: {join_handle:?}");
@@ -354,3 +643,57 @@ pub enum JoinedThread<C> {
     Terminated(TerminatedThread<C>),
     JoinError(Box<dyn Any + Send + 'static>),
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{is_deadline_expired, FeatureReportReadSchedule, ReportDedup};
+
+    #[test]
+    fn deadline_expired() {
+        let deadline = Instant::now();
+        // Past
+        assert!(is_deadline_expired(
+            deadline,
+            deadline + Duration::from_millis(1)
+        ));
+        assert!(!is_deadline_expired(deadline, deadline));
+        // Future
+        assert!(!is_deadline_expired(
+            deadline + Duration::from_millis(1),
+            deadline
+        ));
+    }
+
+    #[test]
+    fn dedup_alternating_report_ids() {
+        let mut report_dedup = ReportDedup::new();
+        assert!(!report_dedup.is_duplicate(&[1, 0x10]));
+        assert!(!report_dedup.is_duplicate(&[2, 0x20]));
+        assert!(report_dedup.is_duplicate(&[1, 0x10]));
+        assert!(report_dedup.is_duplicate(&[2, 0x20]));
+        assert!(!report_dedup.is_duplicate(&[1, 0x11]));
+        assert!(report_dedup.is_duplicate(&[2, 0x20]));
+        assert!(!report_dedup.is_duplicate(&[1, 0x10]));
+        // Different length
+        assert!(!report_dedup.is_duplicate(&[1, 0x10, 0x00]));
+    }
+
+    #[test]
+    fn feature_report_read_schedule() {
+        let mut schedule = FeatureReportReadSchedule::default();
+        let now = Instant::now();
+        schedule.schedule(1, Duration::from_millis(10), now);
+        schedule.schedule(2, Duration::from_millis(20), now);
+        assert_eq!(vec![1, 2], schedule.take_due(now).collect::<Vec<_>>());
+        assert!(schedule.take_due(now).next().is_none());
+        let now = now + Duration::from_millis(10);
+        assert_eq!(vec![1], schedule.take_due(now).collect::<Vec<_>>());
+        schedule.cancel(1);
+        // Missed periods are skipped
+        let now = now + Duration::from_millis(50);
+        assert_eq!(vec![2], schedule.take_due(now).collect::<Vec<_>>());
+        assert!(schedule.take_due(now).next().is_none());
+    }
+}
@@ -7,13 +7,28 @@
 use std::{
     borrow::Borrow,
     cmp::Ordering,
+    collections::{HashMap, HashSet},
     ops::{Add, Mul, RangeInclusive, Sub},
+    time::Duration,
 };
 
 use float_cmp::approx_eq;
 use strum::FromRepr;
 
-use crate::{Control, ControlValue, TimeStamp};
+use crate::{
+    Control, ControlIndex, ControlValue, InputControlDescriptor, InputControlKind, LedOutput,
+    LevelMeter, TimeStamp,
+};
+
+mod recorder;
+pub use self::recorder::{EventReadError, EventReader, EventRecorder};
+
+#[cfg(feature = "input-event-stream")]
+mod stream;
+#[cfg(feature = "input-event-stream")]
+pub use self::stream::{
+    control_input_event_channel, control_input_event_stream, ControlInputEventSender,
+};
 
 /// Time-stamped input event
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -33,12 +48,28 @@ where
 
 /// A simple two-state button.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, FromRepr)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum ButtonInput {
     Released = 0,
     Pressed = 1,
 }
 
+impl ButtonInput {
+    /// Map the velocity of a MIDI note message
+    ///
+    /// Any non-zero velocity is considered as pressed. Some devices
+    /// send note on messages with varying velocities when pressing
+    /// and a velocity of zero when releasing a button.
+    #[must_use]
+    pub const fn from_velocity(velocity: u8) -> Self {
+        match velocity {
+            0x00 => Self::Released,
+            _ => Self::Pressed,
+        }
+    }
+}
+
 impl From<ControlValue> for ButtonInput {
     fn from(from: ControlValue) -> Self {
         match from.to_bits() {
@@ -54,8 +85,132 @@ impl From<ButtonInput> for ControlValue {
     }
 }
 
+/// Gesture of a single button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonGesture {
+    /// Released before the long press threshold
+    Tap,
+    /// Tapped again within the double tap window
+    ///
+    /// Always preceded by a [`Self::Tap`] for the first tap.
+    DoubleTap,
+    /// Released after the long press threshold
+    LongPress,
+    /// Still pressed after the long press threshold
+    ///
+    /// Detected by [`ButtonGestureDetector::poll()`]. Followed by
+    /// [`Self::LongPress`] when released.
+    Hold,
+}
+
+/// Detects gestures from the input events of a single button
+///
+/// Gestures are emitted without delay, i.e. a [`ButtonGesture::Tap`] is
+/// emitted even if it turns out to be the first tap of a
+/// [`ButtonGesture::DoubleTap`] later.
+#[derive(Debug, Clone)]
+pub struct ButtonGestureDetector {
+    long_press_threshold: Duration,
+    double_tap_window: Duration,
+    pressed_ts: Option<TimeStamp>,
+    hold_emitted: bool,
+    last_tap_ts: Option<TimeStamp>,
+}
+
+impl ButtonGestureDetector {
+    pub const DEFAULT_LONG_PRESS_THRESHOLD: Duration = Duration::from_millis(500);
+
+    pub const DEFAULT_DOUBLE_TAP_WINDOW: Duration = Duration::from_millis(300);
+
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            long_press_threshold: Self::DEFAULT_LONG_PRESS_THRESHOLD,
+            double_tap_window: Self::DEFAULT_DOUBLE_TAP_WINDOW,
+            pressed_ts: None,
+            hold_emitted: false,
+            last_tap_ts: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_long_press_threshold(mut self, long_press_threshold: Duration) -> Self {
+        self.long_press_threshold = long_press_threshold;
+        self
+    }
+
+    /// Maximum duration between releasing and pressing the button again
+    #[must_use]
+    pub const fn with_double_tap_window(mut self, double_tap_window: Duration) -> Self {
+        self.double_tap_window = double_tap_window;
+        self
+    }
+
+    #[must_use]
+    pub const fn is_pressed(&self) -> bool {
+        self.pressed_ts.is_some()
+    }
+
+    /// Handle an input event of the button
+    pub fn update(&mut self, event: &InputEvent<ButtonInput>) -> Option<ButtonGesture> {
+        let InputEvent { ts, input } = *event;
+        match input {
+            ButtonInput::Pressed => {
+                if self.pressed_ts.is_none() {
+                    self.pressed_ts = Some(ts);
+                    self.hold_emitted = false;
+                }
+                None
+            }
+            ButtonInput::Released => {
+                let pressed_ts = self.pressed_ts.take()?;
+                let pressed = ts.duration_since(pressed_ts).unwrap_or_default();
+                if pressed >= self.long_press_threshold {
+                    self.last_tap_ts = None;
+                    return Some(ButtonGesture::LongPress);
+                }
+                let is_double_tap = self.last_tap_ts.take().is_some_and(|last_tap_ts| {
+                    pressed_ts
+                        .duration_since(last_tap_ts)
+                        .is_some_and(|elapsed| elapsed <= self.double_tap_window)
+                });
+                if is_double_tap {
+                    return Some(ButtonGesture::DoubleTap);
+                }
+                self.last_tap_ts = Some(ts);
+                Some(ButtonGesture::Tap)
+            }
+        }
+    }
+
+    /// Detect if the button is held
+    ///
+    /// Should be invoked periodically while the button is pressed.
+    /// Emits [`ButtonGesture::Hold`] at most once per press.
+    pub fn poll(&mut self, ts: TimeStamp) -> Option<ButtonGesture> {
+        let pressed_ts = self.pressed_ts?;
+        if self.hold_emitted
+            || ts
+                .duration_since(pressed_ts)
+                .is_none_or(|pressed| pressed < self.long_press_threshold)
+        {
+            return None;
+        }
+        self.hold_emitted = true;
+        Some(ButtonGesture::Hold)
+    }
+}
+
+impl Default for ButtonGestureDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// A pad button with pressure information.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[repr(transparent)]
 pub struct PadButtonInput {
     /// Pressure in the interval [0, 1]
@@ -92,11 +247,65 @@ impl PadButtonInput {
         debug_assert!(Self::PRESSURE_RANGE.contains(&pressure));
         Self { pressure }
     }
+
+    /// Reshape the pressure with a velocity curve
+    #[must_use]
+    pub fn with_curve(self, curve: VelocityCurve) -> Self {
+        let Self { pressure } = self;
+        let pressure = curve.apply(pressure);
+        debug_assert!(Self::PRESSURE_RANGE.contains(&pressure));
+        Self { pressure }
+    }
+
+    /// Ignore light touches
+    ///
+    /// A pressure below the threshold is considered as released.
+    #[must_use]
+    pub fn with_threshold(self, threshold: f32) -> Self {
+        debug_assert!(Self::PRESSURE_RANGE.contains(&threshold));
+        if self.pressure < threshold {
+            return Self {
+                pressure: Self::MIN_PRESSURE,
+            };
+        }
+        self
+    }
+}
+
+/// Response curve of velocity-sensitive pads
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VelocityCurve {
+    Linear,
+    /// Exponent of the pressure
+    ///
+    /// Values greater than 1 require a harder touch, values
+    /// less than 1 make the pads more sensitive.
+    Exponential(f32),
+    /// Less sensitive to both very light and very hard touches
+    SCurve,
+}
+
+impl VelocityCurve {
+    /// Map a pressure in the interval [0, 1]
+    #[must_use]
+    pub fn apply(self, pressure: f32) -> f32 {
+        let x = pressure.clamp(PadButtonInput::MIN_PRESSURE, PadButtonInput::MAX_PRESSURE);
+        match self {
+            Self::Linear => x,
+            Self::Exponential(exponent) => {
+                debug_assert!(exponent > 0.0);
+                x.powf(exponent)
+            }
+            // Smoothstep
+            Self::SCurve => x * x * (3.0 - 2.0 * x),
+        }
+    }
 }
 
 impl From<ControlValue> for PadButtonInput {
     fn from(from: ControlValue) -> Self {
-        let pressure = f32::from_bits(from.to_bits());
+        let pressure = from.to_f32();
         debug_assert!(Self::PRESSURE_RANGE.contains(&pressure));
         Self { pressure }
     }
@@ -105,12 +314,14 @@ impl From<ControlValue> for PadButtonInput {
 impl From<PadButtonInput> for ControlValue {
     fn from(from: PadButtonInput) -> Self {
         let PadButtonInput { pressure } = from;
-        Self::from_bits(pressure.to_bits())
+        Self::from_f32(pressure)
     }
 }
 
 /// A continuous fader or knob.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[repr(transparent)]
 pub struct SliderInput {
     /// Position in the interval [0, 1]
@@ -151,6 +362,37 @@ impl SliderInput {
         }
     }
 
+    /// Apply dead zones at both ends of the range
+    ///
+    /// Positions below `lo` snap to [`Self::MIN_POSITION`] and positions
+    /// above `hi` snap to [`Self::MAX_POSITION`]. The remaining range in
+    /// between is rescaled to the full range.
+    ///
+    /// Passing `lo = 0.0` and `hi = 1.0` results in the identity transform.
+    /// If `lo >= hi` the dead zones cover the whole range and positions
+    /// snap to either boundary.
+    #[must_use]
+    pub fn with_deadzone(self, lo: f32, hi: f32) -> Self {
+        debug_assert!(Self::POSITION_RANGE.contains(&lo));
+        debug_assert!(Self::POSITION_RANGE.contains(&hi));
+        let Self { position } = self;
+        let position = if lo >= hi {
+            // The dead zones cover the whole range
+            if position < lo {
+                Self::MIN_POSITION
+            } else {
+                Self::MAX_POSITION
+            }
+        } else if position <= lo {
+            Self::MIN_POSITION
+        } else if position >= hi {
+            Self::MAX_POSITION
+        } else {
+            Self::clamp_position((position - lo) / (hi - lo))
+        };
+        Self { position }
+    }
+
     #[must_use]
     pub fn map_position_linear<T>(self, min_value: T, max_value: T) -> T
     where
@@ -160,6 +402,26 @@ impl SliderInput {
         min_value + T::from(position) * (max_value - min_value)
     }
 
+    /// Map the position onto a range with a non-linear taper.
+    ///
+    /// The position is raised to the power of `curve` before interpolating
+    /// between `min_value` and `max_value`. A `curve` of 1.0 is equivalent
+    /// to [`Self::map_position_linear()`]. Values greater than 1.0 bend the
+    /// curve towards `min_value` (logarithmic/audio taper), values between
+    /// 0.0 and 1.0 bend it towards `max_value` (exponential taper).
+    ///
+    /// The endpoints are always mapped exactly onto `min_value` and `max_value`.
+    ///
+    /// Use [`Self::map_position_to_gain_ratio()`] for volume controls, which
+    /// maps the position linearly onto a dB scale instead.
+    #[must_use]
+    pub fn map_position_exponential(self, min_value: f32, max_value: f32, curve: f32) -> f32 {
+        debug_assert!(curve > 0.0);
+        let Self { position } = self;
+        let t = position.powf(curve);
+        min_value * (1.0 - t) + max_value * t
+    }
+
     /// Interpret the position as a ratio for adjusting the volume of a signal.
     ///
     /// The position is interpreted as a volume level between the silence level
@@ -181,7 +443,7 @@ impl SliderInput {
 
 impl From<ControlValue> for SliderInput {
     fn from(from: ControlValue) -> Self {
-        let position = f32::from_bits(from.to_bits());
+        let position = from.to_f32();
         debug_assert!(Self::POSITION_RANGE.contains(&position));
         Self { position }
     }
@@ -190,12 +452,109 @@ impl From<ControlValue> for SliderInput {
 impl From<SliderInput> for ControlValue {
     fn from(from: SliderInput) -> Self {
         let SliderInput { position } = from;
-        Self::from_bits(position.to_bits())
+        Self::from_f32(position)
+    }
+}
+
+/// Check if a moving position has caught up with a target value
+///
+/// The value is caught if the position is within the given `tolerance`
+/// or if the value lies between the last and the current position.
+fn soft_takeover_catches(
+    last_position: Option<f32>,
+    position: f32,
+    value: f32,
+    tolerance: f32,
+) -> bool {
+    if (position - value).abs() <= tolerance {
+        return true;
+    }
+    let Some(last_position) = last_position else {
+        return false;
+    };
+    (last_position.min(position)..=last_position.max(position)).contains(&value)
+}
+
+/// Soft takeover (pickup) of a [`SliderInput`]
+///
+/// Prevents sudden jumps if the physical position of a fader differs
+/// from the current value of the controlled parameter, e.g. after
+/// switching layers or decks. Inputs are suppressed until the fader
+/// has caught up with the value by crossing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoftTakeover {
+    value: f32,
+    tolerance: f32,
+    last_position: Option<f32>,
+    caught: bool,
+}
+
+impl SoftTakeover {
+    /// Default tolerance for catching the value immediately
+    ///
+    /// Slightly more than the resolution of 7-bit MIDI values.
+    pub const DEFAULT_TOLERANCE: f32 = 0.01;
+
+    /// Start with the current value of the parameter
+    #[must_use]
+    pub const fn new(value: f32) -> Self {
+        Self {
+            value,
+            tolerance: Self::DEFAULT_TOLERANCE,
+            last_position: None,
+            caught: false,
+        }
+    }
+
+    /// Maximum distance from the value for catching it immediately
+    #[must_use]
+    pub const fn with_tolerance(mut self, tolerance: f32) -> Self {
+        debug_assert!(tolerance >= 0.0);
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// The last known value of the parameter
+    #[must_use]
+    pub const fn value(&self) -> f32 {
+        self.value
+    }
+
+    #[must_use]
+    pub const fn is_caught(&self) -> bool {
+        self.caught
+    }
+
+    /// Update the value after it has been modified by a different source
+    ///
+    /// The value needs to be caught again.
+    pub fn set_value(&mut self, value: f32) {
+        self.value = value;
+        self.caught = false;
+    }
+
+    /// Handle the next input
+    ///
+    /// Returns the new value or `None` while the value has not been caught.
+    pub fn update(&mut self, input: SliderInput) -> Option<f32> {
+        let SliderInput { position } = input;
+        let last_position = self.last_position.replace(position);
+        if !self.caught {
+            self.caught =
+                soft_takeover_catches(last_position, position, self.value, self.tolerance);
+            if !self.caught {
+                return None;
+            }
+        }
+        self.value = position;
+        Some(position)
     }
 }
 
 /// A continuous fader or knob with a symmetric center position.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[repr(transparent)]
 pub struct CenterSliderInput {
     /// Position in the interval [-1, 1]
@@ -252,6 +611,33 @@ impl CenterSliderInput {
         }
     }
 
+    /// Apply a dead zone around the center position
+    ///
+    /// Positions within the band of the given `width` around the center
+    /// snap to [`Self::CENTER_POSITION`]. The remaining ranges on both sides
+    /// are rescaled to the full ranges [-1, 0] and [0, 1] respectively.
+    ///
+    /// A `width` of 0.0 results in the identity transform. If the band
+    /// covers the whole range, i.e. for a `width` of 2.0 or more, all
+    /// positions snap to the center.
+    #[must_use]
+    pub fn with_center_detent(self, width: f32) -> Self {
+        debug_assert!(width >= 0.0);
+        let Self { position } = self;
+        let half_width = width / 2.0;
+        if half_width <= 0.0 {
+            return self;
+        }
+        if half_width >= Self::MAX_POSITION || position.abs() <= half_width {
+            return Self {
+                position: Self::CENTER_POSITION,
+            };
+        }
+        let magnitude = (position.abs() - half_width) / (Self::MAX_POSITION - half_width);
+        let position = Self::clamp_position(magnitude.copysign(position));
+        Self { position }
+    }
+
     #[must_use]
     #[inline]
     pub fn map_position_linear<T>(self, min_value: T, center_value: T, max_value: T) -> T
@@ -273,6 +659,39 @@ impl CenterSliderInput {
         }
     }
 
+    /// Map the position onto a range with a non-linear taper.
+    ///
+    /// The distance from the center is raised to the power of `curve`
+    /// before interpolating between `center_value` and either `min_value`
+    /// or `max_value`, i.e. the taper is applied symmetrically on both
+    /// sides. A `curve` of 1.0 is equivalent to [`Self::map_position_linear()`].
+    ///
+    /// The endpoints and the center are always mapped exactly onto `min_value`,
+    /// `max_value`, and `center_value` respectively.
+    ///
+    /// Use [`Self::map_position_to_gain_ratio()`] for volume controls, which
+    /// maps the position linearly onto a dB scale instead.
+    #[must_use]
+    pub fn map_position_exponential(
+        self,
+        min_value: f32,
+        center_value: f32,
+        max_value: f32,
+        curve: f32,
+    ) -> f32 {
+        debug_assert!(curve > 0.0);
+        let Self { position } = self;
+        let t = position.abs().powf(curve);
+        match position
+            .partial_cmp(&Self::CENTER_POSITION)
+            .unwrap_or(Ordering::Equal)
+        {
+            Ordering::Equal => center_value,
+            Ordering::Less => center_value * (1.0 - t) + min_value * t,
+            Ordering::Greater => center_value * (1.0 - t) + max_value * t,
+        }
+    }
+
     /// Interpret the position as a ratio for tuning the volume of a signal.
     ///
     /// The position is interpreted as a volume level between the `min_db`
@@ -296,11 +715,35 @@ impl CenterSliderInput {
             Ordering::Greater => db_to_ratio(position * max_db),
         }
     }
+
+    /// Like [`Self::map_position_to_gain_ratio()`], but with an optional
+    /// silence level.
+    ///
+    /// If `min_db` is `None` then the lower half is mapped linearly onto
+    /// the ratios between 0.0 (-inf dB, silence) and 1.0 (0 dB).
+    ///
+    /// A `max_db` of 0 dB results in unity gain for the whole upper half.
+    #[must_use]
+    pub fn map_position_to_gain_ratio_with_silence(self, min_db: Option<f32>, max_db: f32) -> f32 {
+        debug_assert!(min_db.is_none_or(|min_db| min_db < 0.0));
+        debug_assert!(max_db >= 0.0);
+        let Self { position } = self;
+        match position
+            .partial_cmp(&Self::CENTER_POSITION)
+            .unwrap_or(Ordering::Equal)
+        {
+            Ordering::Equal => 1.0,
+            Ordering::Less => {
+                min_db.map_or(1.0 + position, |min_db| db_to_ratio(-position * min_db))
+            }
+            Ordering::Greater => db_to_ratio(position * max_db),
+        }
+    }
 }
 
 impl From<ControlValue> for CenterSliderInput {
     fn from(from: ControlValue) -> Self {
-        let position = f32::from_bits(from.to_bits());
+        let position = from.to_f32();
         debug_assert!(Self::POSITION_RANGE.contains(&position));
         Self { position }
     }
@@ -309,7 +752,111 @@ impl From<ControlValue> for CenterSliderInput {
 impl From<CenterSliderInput> for ControlValue {
     fn from(from: CenterSliderInput) -> Self {
         let CenterSliderInput { position } = from;
-        Self::from_bits(position.to_bits())
+        Self::from_f32(position)
+    }
+}
+
+/// Soft takeover (pickup) of a [`CenterSliderInput`]
+///
+/// Like [`SoftTakeover`], e.g. for EQ knobs that are shared between
+/// multiple decks.
+///
+/// Positions within the band of the center detent snap to
+/// [`CenterSliderInput::CENTER_POSITION`]. A value at the center is
+/// caught as soon as the knob enters the detent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CenterSliderSoftTakeover {
+    soft_takeover: SoftTakeover,
+    center_detent: f32,
+}
+
+/// Map a center slider position onto the range of a [`SliderInput`]
+const fn center_slider_to_slider_position(position: f32) -> f32 {
+    (position - CenterSliderInput::MIN_POSITION)
+        / (CenterSliderInput::MAX_POSITION - CenterSliderInput::MIN_POSITION)
+}
+
+/// Inverse of [`center_slider_to_slider_position()`]
+const fn slider_to_center_slider_position(position: f32) -> f32 {
+    position * (CenterSliderInput::MAX_POSITION - CenterSliderInput::MIN_POSITION)
+        + CenterSliderInput::MIN_POSITION
+}
+
+impl CenterSliderSoftTakeover {
+    /// Default tolerance for catching the value immediately
+    ///
+    /// Covers the same distance of travel as [`SoftTakeover::DEFAULT_TOLERANCE`],
+    /// i.e. twice the value for the twice as wide range of positions.
+    pub const DEFAULT_TOLERANCE: f32 = 2.0 * SoftTakeover::DEFAULT_TOLERANCE;
+
+    /// Start with a value in the range of [`CenterSliderInput::POSITION_RANGE`]
+    ///
+    /// The center detent is disabled initially.
+    #[must_use]
+    pub const fn new(value: f32) -> Self {
+        Self {
+            soft_takeover: SoftTakeover::new(center_slider_to_slider_position(value))
+                .with_tolerance(Self::DEFAULT_TOLERANCE / 2.0),
+            center_detent: 0.0,
+        }
+    }
+
+    /// Maximum distance from the value for catching it immediately
+    ///
+    /// Measured in the range of [`CenterSliderInput::POSITION_RANGE`].
+    #[must_use]
+    pub const fn with_tolerance(mut self, tolerance: f32) -> Self {
+        debug_assert!(tolerance >= 0.0);
+        // The slider range is only half as wide
+        self.soft_takeover = self.soft_takeover.with_tolerance(tolerance / 2.0);
+        self
+    }
+
+    /// Width of the band around the center
+    ///
+    /// See also [`CenterSliderInput::with_center_detent()`].
+    #[must_use]
+    pub const fn with_center_detent(mut self, width: f32) -> Self {
+        debug_assert!(width >= 0.0);
+        self.center_detent = width;
+        self
+    }
+
+    /// The last known value of the parameter
+    #[must_use]
+    pub const fn value(&self) -> f32 {
+        slider_to_center_slider_position(self.soft_takeover.value())
+    }
+
+    #[must_use]
+    pub const fn is_caught(&self) -> bool {
+        self.soft_takeover.is_caught()
+    }
+
+    /// Update the value after it has been modified by a different source
+    ///
+    /// The value needs to be caught again.
+    pub fn set_value(&mut self, value: f32) {
+        self.soft_takeover
+            .set_value(center_slider_to_slider_position(value));
+    }
+
+    /// Handle the next input
+    ///
+    /// Returns the new value or `None` while the value has not been caught.
+    pub fn update(&mut self, input: CenterSliderInput) -> Option<f32> {
+        let CenterSliderInput { position } = input;
+        let position =
+            if (position - CenterSliderInput::CENTER_POSITION).abs() <= self.center_detent / 2.0 {
+                CenterSliderInput::CENTER_POSITION
+            } else {
+                position
+            };
+        self.soft_takeover
+            .update(SliderInput {
+                position: center_slider_to_slider_position(position),
+            })
+            .map(|_| position)
     }
 }
 
@@ -321,6 +868,8 @@ impl From<CenterSliderInput> for ControlValue {
 ///
 /// The number of ticks per revolution or twist is device-dependent.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[repr(transparent)]
 pub struct StepEncoderInput {
     pub delta: i32,
@@ -352,8 +901,7 @@ impl StepEncoderInput {
 
 impl From<ControlValue> for StepEncoderInput {
     fn from(from: ControlValue) -> Self {
-        #[allow(clippy::cast_possible_wrap)]
-        let delta = from.to_bits() as i32;
+        let delta = from.to_i32();
         Self { delta }
     }
 }
@@ -361,8 +909,86 @@ impl From<ControlValue> for StepEncoderInput {
 impl From<StepEncoderInput> for ControlValue {
     fn from(from: StepEncoderInput) -> Self {
         let StepEncoderInput { delta } = from;
-        #[allow(clippy::cast_sign_loss)]
-        Self::from_bits(delta as u32)
+        Self::from_i32(delta)
+    }
+}
+
+/// Accumulates the deltas of a [`StepEncoderInput`]
+///
+/// High-resolution encoders could drive a coarse selection by
+/// combining multiple ticks into a single step. The value optionally
+/// wraps around at a modulus, e.g. for navigating through menus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepAccumulator {
+    value: i32,
+    pending_ticks: i32,
+    ticks_per_step: i32,
+    modulus: Option<i32>,
+}
+
+impl StepAccumulator {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            value: 0,
+            pending_ticks: 0,
+            ticks_per_step: 1,
+            modulus: None,
+        }
+    }
+
+    /// Number of encoder ticks per step
+    ///
+    /// Must be positive.
+    #[must_use]
+    pub const fn with_ticks_per_step(mut self, ticks_per_step: i32) -> Self {
+        debug_assert!(ticks_per_step > 0);
+        self.ticks_per_step = ticks_per_step;
+        self
+    }
+
+    /// Wrap the value into the range [0, `modulus`)
+    ///
+    /// Must be positive.
+    #[must_use]
+    pub const fn with_modulus(mut self, modulus: i32) -> Self {
+        debug_assert!(modulus > 0);
+        self.modulus = Some(modulus);
+        self.value = self.value.rem_euclid(modulus);
+        self
+    }
+
+    #[must_use]
+    pub const fn value(&self) -> i32 {
+        self.value
+    }
+
+    /// Reset both the value and pending ticks
+    pub fn reset(&mut self) {
+        self.value = 0;
+        self.pending_ticks = 0;
+    }
+
+    /// Accumulate the delta of the input
+    ///
+    /// Returns the net number of steps. Remaining ticks are carried over
+    /// to the next invocation.
+    pub fn apply(&mut self, input: StepEncoderInput) -> i32 {
+        let StepEncoderInput { delta } = input;
+        let ticks = self.pending_ticks.saturating_add(delta);
+        let steps = ticks / self.ticks_per_step;
+        self.pending_ticks = ticks % self.ticks_per_step;
+        self.value = self.value.saturating_add(steps);
+        if let Some(modulus) = self.modulus {
+            self.value = self.value.rem_euclid(modulus);
+        }
+        steps
+    }
+}
+
+impl Default for StepAccumulator {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -378,6 +1004,8 @@ impl From<StepEncoderInput> for ControlValue {
 ///  1.0: One full CW rotation (360 degrees)
 /// -1.0: One full CCW rotation (360 degrees)
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[repr(transparent)]
 pub struct SliderEncoderInput {
     pub delta: f32,
@@ -394,6 +1022,23 @@ impl SliderEncoderInput {
             Self { delta: -delta }
         }
     }
+
+    /// Velocity in revolutions per second
+    ///
+    /// Interprets the delta as the distance that has been covered
+    /// during the `elapsed` time span.
+    ///
+    /// Returns 0.0 if `elapsed` is zero.
+    #[must_use]
+    pub fn velocity(self, elapsed: Duration) -> f32 {
+        let Self { delta } = self;
+        let elapsed_secs = elapsed.as_secs_f32();
+        if elapsed_secs > 0.0 {
+            delta / elapsed_secs
+        } else {
+            0.0
+        }
+    }
 }
 
 impl SliderEncoderInput {
@@ -427,7 +1072,7 @@ impl SliderEncoderInput {
 
 impl From<ControlValue> for SliderEncoderInput {
     fn from(from: ControlValue) -> Self {
-        let delta = f32::from_bits(from.to_bits());
+        let delta = from.to_f32();
         Self { delta }
     }
 }
@@ -435,43 +1080,715 @@ impl From<ControlValue> for SliderEncoderInput {
 impl From<SliderEncoderInput> for ControlValue {
     fn from(from: SliderEncoderInput) -> Self {
         let SliderEncoderInput { delta } = from;
-        Self::from_bits(delta.to_bits())
+        Self::from_f32(delta)
     }
 }
 
-/// Choose one out of many, discrete possible choices
+/// Smoothed velocity of a [`SliderEncoderInput`], e.g. a jog wheel
 ///
-/// Useful for configuration settings, e.g. selecting a mode.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct SelectorInput {
-    pub choice: u32,
+/// Accumulates time-stamped deltas into a velocity in revolutions per
+/// second by applying exponential smoothing.
+#[derive(Debug, Clone)]
+pub struct ScratchVelocityTracker {
+    smoothing: f32,
+    max_elapsed: Duration,
+    last_ts: Option<TimeStamp>,
+    velocity: f32,
 }
 
-impl From<ControlValue> for SelectorInput {
-    fn from(from: ControlValue) -> Self {
-        let choice = from.to_bits();
-        Self { choice }
-    }
-}
+impl ScratchVelocityTracker {
+    /// Weight of the previous velocity when smoothing
+    pub const DEFAULT_SMOOTHING: f32 = 0.5;
 
-impl From<SelectorInput> for ControlValue {
-    fn from(from: SelectorInput) -> Self {
-        let SelectorInput { choice } = from;
-        Self::from_bits(choice)
-    }
-}
+    /// Upper bound for the time span between two subsequent inputs
+    pub const DEFAULT_MAX_ELAPSED: Duration = Duration::from_millis(100);
 
-pub type ControlInputEvent = InputEvent<Control>;
+    /// Lower bound for the time span between two subsequent inputs
+    ///
+    /// Prevents excessive velocities if multiple inputs arrive
+    /// with (almost) the same time stamp.
+    pub const MIN_ELAPSED: Duration = Duration::from_millis(1);
 
-pub trait ControlInputEventSink {
-    /// Callback for sinking control input events
+    /// Create a new tracker
     ///
-    /// The caller will provide one or more events per invocation.
-    /// Multiple events are ordered chronologically according to
-    /// their time stamps.
+    /// The `smoothing` coefficient in the interval [0, 1) controls the
+    /// weight of the previous velocity. A value of 0.0 disables smoothing.
+    ///
+    /// The time span between two subsequent inputs is clamped to `max_elapsed`,
+    /// e.g. when the jog wheel is moved again after a long pause.
+    #[must_use]
+    pub fn new(smoothing: f32, max_elapsed: Duration) -> Self {
+        debug_assert!((0.0..1.0).contains(&smoothing));
+        debug_assert!(max_elapsed >= Self::MIN_ELAPSED);
+        Self {
+            smoothing,
+            max_elapsed,
+            last_ts: None,
+            velocity: 0.0,
+        }
+    }
+
+    #[must_use]
+    pub const fn smoothing(&self) -> f32 {
+        self.smoothing
+    }
+
+    /// Upper bound for the time span between two subsequent inputs
+    #[must_use]
+    pub const fn max_elapsed(&self) -> Duration {
+        self.max_elapsed
+    }
+
+    pub fn set_smoothing(&mut self, smoothing: f32) {
+        debug_assert!((0.0..1.0).contains(&smoothing));
+        self.smoothing = smoothing;
+    }
+
+    /// Smoothed velocity in revolutions per second
+    #[must_use]
+    pub const fn velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    /// Forget the history
+    pub fn reset(&mut self) {
+        self.last_ts = None;
+        self.velocity = 0.0;
+    }
+
+    /// Accumulate the next input
+    ///
+    /// Returns the updated, smoothed velocity.
+    pub fn update(&mut self, ts: TimeStamp, input: SliderEncoderInput) -> f32 {
+        let elapsed = self.last_ts.map_or(self.max_elapsed, |last_ts| {
+            Duration::from_micros(ts.to_micros().saturating_sub(last_ts.to_micros()))
+        });
+        self.last_ts = Some(ts);
+        let elapsed = elapsed.clamp(Self::MIN_ELAPSED, self.max_elapsed);
+        let velocity = input.velocity(elapsed);
+        self.velocity = self.smoothing * self.velocity + (1.0 - self.smoothing) * velocity;
+        self.velocity
+    }
+}
+
+impl Default for ScratchVelocityTracker {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SMOOTHING, Self::DEFAULT_MAX_ELAPSED)
+    }
+}
+
+/// Measured audio level, e.g. for feeding meters on a device
+///
+/// Both levels are amplitude ratios in the interval [0, 1].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LevelInput {
+    /// Root mean square (RMS) level
+    pub rms: f32,
+
+    /// Peak level
+    ///
+    /// Should not be less than the RMS level.
+    pub peak: f32,
+}
+
+impl LevelInput {
+    pub const MIN_LEVEL: f32 = 0.0;
+    pub const MAX_LEVEL: f32 = 1.0;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn level_to_u16(level: f32) -> u16 {
+        (level.clamp(Self::MIN_LEVEL, Self::MAX_LEVEL) * f32::from(u16::MAX)).round() as u16
+    }
+
+    fn level_from_u16(value: u16) -> f32 {
+        f32::from(value) / f32::from(u16::MAX)
+    }
+
+    /// Map the levels onto a strip of LED segments, from bottom to top
+    ///
+    /// All segments up to the RMS level are lit. The segment of the peak
+    /// level is lit additionally.
+    ///
+    /// Use a [`LevelMeter`] directly for continuous updates with
+    /// peak hold or clip indication.
+    #[must_use]
+    pub fn to_led_segments(self, count: usize) -> Vec<LedOutput> {
+        if count == 0 {
+            return Vec::new();
+        }
+        let Self { rms, peak } = self;
+        // The peak segment is held while updating the meter with the RMS level.
+        let mut meter = LevelMeter::new(count).with_peak_hold(1);
+        meter.update(peak);
+        meter.update(rms).to_vec()
+    }
+}
+
+/// Both levels are encoded with a resolution of 16 bits.
+impl From<LevelInput> for ControlValue {
+    fn from(from: LevelInput) -> Self {
+        let LevelInput { rms, peak } = from;
+        let bits = (u32::from(LevelInput::level_to_u16(peak)) << 16)
+            | u32::from(LevelInput::level_to_u16(rms));
+        Self::from_bits(bits)
+    }
+}
+
+impl From<ControlValue> for LevelInput {
+    #[allow(clippy::cast_possible_truncation)]
+    fn from(from: ControlValue) -> Self {
+        let bits = from.to_bits();
+        Self {
+            rms: Self::level_from_u16(bits as u16),
+            peak: Self::level_from_u16((bits >> 16) as u16),
+        }
+    }
+}
+
+/// Choose one out of many, discrete possible choices
+///
+/// Useful for configuration settings, e.g. selecting a mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct SelectorInput {
+    pub choice: u32,
+}
+
+impl SelectorInput {
+    /// Wrap an arbitrary choice into the range [0, `count`)
+    ///
+    /// The `count` must not be 0.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn wrapped(choice: i64, count: u32) -> Self {
+        debug_assert!(count > 0);
+        let choice = choice.rem_euclid(i64::from(count)) as u32;
+        Self { choice }
+    }
+
+    /// Map the choice onto an enum variant
+    ///
+    /// Intended to be used with the `from_repr()` function generated by
+    /// [`strum::FromRepr`], e.g. `selector.to_enum(Mode::from_repr)`.
+    ///
+    /// Returns `None` if the choice is out of range.
+    pub fn to_enum<R, E>(self, from_repr: impl FnOnce(R) -> Option<E>) -> Option<E>
+    where
+        R: TryFrom<u32>,
+    {
+        let Self { choice } = self;
+        R::try_from(choice).ok().and_then(from_repr)
+    }
+}
+
+impl From<ControlValue> for SelectorInput {
+    fn from(from: ControlValue) -> Self {
+        let choice = from.to_bits();
+        Self { choice }
+    }
+}
+
+impl From<SelectorInput> for ControlValue {
+    fn from(from: SelectorInput) -> Self {
+        let SelectorInput { choice } = from;
+        Self::from_bits(choice)
+    }
+}
+
+pub type ControlInputEvent = InputEvent<Control>;
+
+pub trait ControlInputEventSink {
+    /// Callback for sinking control input events
+    ///
+    /// The caller will provide one or more events per invocation.
+    /// Multiple events are ordered chronologically according to
+    /// their time stamps.
     fn sink_control_input_events(&mut self, events: &[ControlInputEvent]);
 }
 
+/// Rewrites the indices of control input events before forwarding them
+///
+/// Allows to drive application logic that has been written against the
+/// control indices of one device with a different device. Events with
+/// unmapped indices are discarded.
+///
+/// Use [`crate::ControlIndexRemapOutput`] with the [inverse mapping](Self::inverse_mapping)
+/// for remapping outputs in the opposite direction.
+#[derive(Debug)]
+pub struct ControlIndexRemap<S> {
+    mapping: HashMap<ControlIndex, ControlIndex>,
+    sink: S,
+    remapped: Vec<ControlInputEvent>,
+}
+
+impl<S> ControlIndexRemap<S> {
+    #[must_use]
+    pub const fn new(mapping: HashMap<ControlIndex, ControlIndex>, sink: S) -> Self {
+        Self {
+            mapping,
+            sink,
+            remapped: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn mapping(&self) -> &HashMap<ControlIndex, ControlIndex> {
+        &self.mapping
+    }
+
+    /// The mapping in the opposite direction
+    ///
+    /// If multiple indices are mapped onto the same index then only one
+    /// of them is retained.
+    #[must_use]
+    pub fn inverse_mapping(&self) -> HashMap<ControlIndex, ControlIndex> {
+        self.mapping.iter().map(|(&from, &to)| (to, from)).collect()
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        let Self { sink, .. } = self;
+        sink
+    }
+}
+
+impl<S> ControlInputEventSink for ControlIndexRemap<S>
+where
+    S: ControlInputEventSink,
+{
+    fn sink_control_input_events(&mut self, events: &[ControlInputEvent]) {
+        let Self {
+            mapping,
+            sink,
+            remapped,
+        } = self;
+        debug_assert!(remapped.is_empty());
+        remapped.extend(events.iter().filter_map(|event| {
+            let InputEvent {
+                ts,
+                input: Control { index, value },
+            } = *event;
+            let index = *mapping.get(&index)?;
+            Some(InputEvent {
+                ts,
+                input: Control { index, value },
+            })
+        }));
+        if !remapped.is_empty() {
+            sink.sink_control_input_events(remapped);
+            remapped.clear();
+        }
+    }
+}
+
+/// Tracks modifier buttons like _Shift_ that select a layer of controls
+///
+/// The layer index is a bit set of all pressed modifiers, i.e. bit `i`
+/// is set while the `i`-th modifier is pressed. Layer 0 is the default
+/// layer without any modifiers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerState {
+    modifiers: Vec<ControlIndex>,
+    pressed: u8,
+}
+
+impl LayerState {
+    /// Maximum number of modifiers
+    pub const MAX_MODIFIERS: usize = 8;
+
+    /// # Panics
+    ///
+    /// Panics if more than [`Self::MAX_MODIFIERS`] modifiers are given.
+    #[must_use]
+    pub fn new(modifiers: Vec<ControlIndex>) -> Self {
+        assert!(
+            modifiers.len() <= Self::MAX_MODIFIERS,
+            "too many modifiers: {}",
+            modifiers.len()
+        );
+        Self {
+            modifiers,
+            pressed: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn modifiers(&self) -> &[ControlIndex] {
+        &self.modifiers
+    }
+
+    /// The current layer index
+    #[must_use]
+    pub const fn layer(&self) -> u8 {
+        self.pressed
+    }
+
+    /// Handle an input of any control
+    ///
+    /// Returns `true` if the control is a modifier. Inputs of modifiers
+    /// are consumed and should not be mapped onto actions.
+    pub fn update(&mut self, input: Control) -> bool {
+        let Control { index, value } = input;
+        let Some(modifier) = self
+            .modifiers
+            .iter()
+            .position(|&modifier| modifier == index)
+        else {
+            return false;
+        };
+        let bit = 1 << modifier;
+        match ButtonInput::from(value) {
+            ButtonInput::Pressed => self.pressed |= bit,
+            ButtonInput::Released => self.pressed &= !bit,
+        }
+        true
+    }
+
+    /// Release all modifiers, e.g. after the device has been reconnected
+    pub fn reset(&mut self) {
+        self.pressed = 0;
+    }
+}
+
+/// Maps controls onto actions depending on the layer
+///
+/// See also: [`LayerState`]
+#[derive(Debug, Clone)]
+pub struct LayerMapping<A> {
+    actions: HashMap<(ControlIndex, u8), A>,
+}
+
+impl<A> LayerMapping<A> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            actions: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_action(mut self, index: ControlIndex, layer: u8, action: A) -> Self {
+        self.insert(index, layer, action);
+        self
+    }
+
+    /// Map a control onto an action in a layer
+    ///
+    /// Returns the action that has been replaced.
+    pub fn insert(&mut self, index: ControlIndex, layer: u8, action: A) -> Option<A> {
+        self.actions.insert((index, layer), action)
+    }
+
+    /// The action of a control in a layer
+    ///
+    /// Controls that are not mapped in the given layer are
+    /// not mapped in the default layer implicitly.
+    #[must_use]
+    pub fn action(&self, index: ControlIndex, layer: u8) -> Option<&A> {
+        self.actions.get(&(index, layer))
+    }
+
+    /// The action of a control in the current layer
+    #[must_use]
+    pub fn current_action(&self, index: ControlIndex, state: &LayerState) -> Option<&A> {
+        self.action(index, state.layer())
+    }
+}
+
+impl<A> Default for LayerMapping<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Suppresses bouncing of buttons before forwarding events
+///
+/// Only settled state changes of buttons are forwarded, i.e. when
+/// the latest state of a button has not changed for the duration of
+/// `window`, measured by the time stamps of the events. Settled changes
+/// are detected when receiving the next event or by [`Self::poll()`]
+/// and forwarded with the time stamp when the `window` elapsed. This
+/// delays all state changes of buttons by `window`.
+///
+/// Only events of the given button indices are debounced. All other
+/// events are forwarded unmodified.
+#[derive(Debug)]
+pub struct Debouncer<S> {
+    window: Duration,
+    buttons: HashMap<ControlIndex, DebouncedButton>,
+    sink: S,
+    forwarded: Vec<ControlInputEvent>,
+}
+
+#[derive(Debug, Default)]
+struct DebouncedButton {
+    /// The latest state and when it has changed
+    latest: Option<(TimeStamp, ControlValue)>,
+    /// The last state that has been forwarded
+    settled: Option<ControlValue>,
+}
+
+impl DebouncedButton {
+    fn update(&mut self, ts: TimeStamp, value: ControlValue) {
+        if self
+            .latest
+            .is_some_and(|(_, latest_value)| latest_value == value)
+        {
+            return;
+        }
+        self.latest = Some((ts, value));
+    }
+
+    /// Settle the latest state if it has not changed within `window`
+    ///
+    /// Returns the time stamp of the settled change if it needs
+    /// to be forwarded.
+    fn settle(&mut self, window: Duration, now: TimeStamp) -> Option<(TimeStamp, ControlValue)> {
+        let (changed, value) = self.latest?;
+        if self.settled == Some(value) {
+            return None;
+        }
+        let settled_ts = changed.saturating_add(window);
+        if now < settled_ts {
+            return None;
+        }
+        self.settled = Some(value);
+        Some((settled_ts, value))
+    }
+}
+
+impl<S> Debouncer<S> {
+    #[must_use]
+    pub fn new(window: Duration, buttons: impl IntoIterator<Item = ControlIndex>, sink: S) -> Self {
+        Self {
+            window,
+            buttons: buttons
+                .into_iter()
+                .map(|index| (index, DebouncedButton::default()))
+                .collect(),
+            sink,
+            forwarded: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn window(&self) -> Duration {
+        self.window
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        let Self { sink, .. } = self;
+        sink
+    }
+
+    /// Collect the settled state changes of all buttons, ordered by time
+    fn settle_buttons(&mut self, now: TimeStamp) {
+        let Self {
+            window,
+            buttons,
+            forwarded,
+            ..
+        } = self;
+        let first_settled = forwarded.len();
+        forwarded.extend(buttons.iter_mut().filter_map(|(index, button)| {
+            let (ts, value) = button.settle(*window, now)?;
+            Some(ControlInputEvent {
+                ts,
+                input: Control {
+                    index: *index,
+                    value,
+                },
+            })
+        }));
+        forwarded[first_settled..].sort_by_key(|event| (event.ts, event.input.index));
+    }
+}
+
+impl<S> Debouncer<S>
+where
+    S: ControlInputEventSink,
+{
+    /// Forward the state changes of buttons that have settled until `now`
+    ///
+    /// Needs to be invoked periodically to forward the final state
+    /// changes of buttons if no more events are received.
+    pub fn poll(&mut self, now: TimeStamp) {
+        debug_assert!(self.forwarded.is_empty());
+        self.settle_buttons(now);
+        self.flush();
+    }
+
+    fn flush(&mut self) {
+        let Self {
+            sink, forwarded, ..
+        } = self;
+        if !forwarded.is_empty() {
+            sink.sink_control_input_events(forwarded);
+            forwarded.clear();
+        }
+    }
+}
+
+impl<S> ControlInputEventSink for Debouncer<S>
+where
+    S: ControlInputEventSink,
+{
+    fn sink_control_input_events(&mut self, events: &[ControlInputEvent]) {
+        debug_assert!(self.forwarded.is_empty());
+        for event in events {
+            self.settle_buttons(event.ts);
+            let InputEvent {
+                ts,
+                input: Control { index, value },
+            } = *event;
+            let Some(button) = self.buttons.get_mut(&index) else {
+                self.forwarded.push(event.clone());
+                continue;
+            };
+            button.update(ts, value);
+        }
+        self.flush();
+    }
+}
+
+/// Learns the control indices of the next input events
+///
+/// Captures up to `capacity` distinct control indices in the order
+/// they are received. All subsequent events are ignored until the
+/// learned indices are taken.
+#[derive(Debug, Clone)]
+pub struct MidiLearn {
+    capacity: usize,
+    learned: Vec<ControlIndex>,
+    ignored: HashSet<ControlIndex>,
+}
+
+impl MidiLearn {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        debug_assert!(capacity > 0);
+        Self {
+            capacity,
+            learned: Vec::with_capacity(capacity),
+            ignored: HashSet::new(),
+        }
+    }
+
+    /// Ignore sliders and encoders
+    ///
+    /// Continuous controls tend to emit events unintentionally when
+    /// touched. Only the given controls are affected, i.e. events with
+    /// unknown indices are still learned.
+    #[must_use]
+    pub fn with_continuous_controls_ignored(mut self, controls: &[InputControlDescriptor]) -> Self {
+        self.ignored.extend(controls.iter().filter_map(|control| {
+            matches!(
+                control.kind,
+                InputControlKind::Slider | InputControlKind::Encoder
+            )
+            .then_some(control.index)
+        }));
+        self
+    }
+
+    /// All indices have been learned
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.learned.len() >= self.capacity
+    }
+
+    /// The indices that have been learned so far
+    #[must_use]
+    pub fn learned(&self) -> &[ControlIndex] {
+        &self.learned
+    }
+
+    /// Take the learned indices and start learning again
+    pub fn take_learned(&mut self) -> Vec<ControlIndex> {
+        std::mem::replace(&mut self.learned, Vec::with_capacity(self.capacity))
+    }
+}
+
+impl ControlInputEventSink for MidiLearn {
+    fn sink_control_input_events(&mut self, events: &[ControlInputEvent]) {
+        for event in events {
+            if self.is_complete() {
+                return;
+            }
+            let index = event.input.index;
+            if self.ignored.contains(&index) || self.learned.contains(&index) {
+                continue;
+            }
+            log::debug!("Learned control index {index}");
+            self.learned.push(index);
+        }
+    }
+}
+
+/// Source of control input events, e.g. for replaying recorded events
+pub trait ControlInputEventSource {
+    /// Poll for pending control input events
+    ///
+    /// Appends all pending events to `out`, ordered chronologically
+    /// according to their time stamps. Appends nothing if no events
+    /// are pending.
+    fn poll_events(&mut self, out: &mut Vec<ControlInputEvent>);
+}
+
+/// Replays a pre-recorded slice of control input events
+///
+/// The events must be ordered chronologically.
+#[derive(Debug, Clone)]
+pub struct SliceEventSource<'a> {
+    events: &'a [ControlInputEvent],
+    next_index: usize,
+}
+
+impl<'a> SliceEventSource<'a> {
+    #[must_use]
+    pub fn new(events: &'a [ControlInputEvent]) -> Self {
+        debug_assert!(input_events_ordered_chronologically(events));
+        Self {
+            events,
+            next_index: 0,
+        }
+    }
+
+    /// The remaining events that have not been polled yet
+    #[must_use]
+    pub fn remaining_events(&self) -> &'a [ControlInputEvent] {
+        &self.events[self.next_index..]
+    }
+
+    #[must_use]
+    pub const fn is_exhausted(&self) -> bool {
+        self.next_index >= self.events.len()
+    }
+
+    /// Restart the replay from the first event
+    pub fn rewind(&mut self) {
+        self.next_index = 0;
+    }
+
+    /// Poll all pending events up to and including the given time stamp
+    pub fn poll_events_until(&mut self, until: TimeStamp, out: &mut Vec<ControlInputEvent>) {
+        let remaining_events = self.remaining_events();
+        let count = remaining_events.partition_point(|event| event.ts <= until);
+        out.extend_from_slice(&remaining_events[..count]);
+        self.next_index += count;
+    }
+}
+
+impl ControlInputEventSource for SliceEventSource<'_> {
+    fn poll_events(&mut self, out: &mut Vec<ControlInputEvent>) {
+        out.extend_from_slice(self.remaining_events());
+        self.next_index = self.events.len();
+    }
+}
+
 #[must_use]
 pub fn split_crossfader_input_linear(input: CenterSliderInput) -> (SliderInput, SliderInput) {
     const fn f_x(x: f32) -> f32 {
@@ -563,12 +1880,42 @@ pub fn split_crossfader_input_square(input: CenterSliderInput) -> (SliderInput,
     )
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Scratch curve with a fast cut
+///
+/// The `sharpness` in the range [0, 1] interpolates between
+/// [`split_crossfader_input_linear()`] (0) and
+/// [`split_crossfader_input_square()`] (1).
+#[must_use]
+pub fn split_crossfader_input_sharp(
+    input: CenterSliderInput,
+    sharpness: f32,
+) -> (SliderInput, SliderInput) {
+    debug_assert!((0.0..=1.0).contains(&sharpness));
+    if sharpness >= 1.0 {
+        return split_crossfader_input_square(input);
+    }
+    let f_x = |x: f32| (x / (1.0 - sharpness.max(0.0))).min(1.0);
+    let CenterSliderInput { position } = input;
+    let x = position * 0.5 + 0.5; // [0, 1]
+    (
+        SliderInput {
+            position: SliderInput::clamp_position(f_x(1.0 - x)),
+        },
+        SliderInput {
+            position: SliderInput::clamp_position(f_x(x)),
+        },
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CrossfaderCurve {
     Linear,
     AmplitudePreserving,
     EnergyPreserving,
     Square,
+    /// See [`split_crossfader_input_sharp()`]
+    Sharp(f32),
 }
 
 impl CrossfaderCurve {
@@ -579,13 +1926,98 @@ impl CrossfaderCurve {
             Self::AmplitudePreserving => split_crossfader_input_amplitude_preserving_approx(input),
             Self::EnergyPreserving => split_crossfader_input_energy_preserving_approx(input),
             Self::Square => split_crossfader_input_square(input),
+            Self::Sharp(sharpness) => split_crossfader_input_sharp(input, sharpness),
+        }
+    }
+
+    /// Split the input with swapped channels
+    ///
+    /// Also known as hamster mode.
+    #[must_use]
+    pub fn split_input_reversed(self, input: CenterSliderInput) -> (SliderInput, SliderInput) {
+        let (left, right) = self.split_input(input);
+        (right, left)
+    }
+}
+
+/// Crossfader settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CrossfaderConfig {
+    pub curve: CrossfaderCurve,
+
+    /// Swap the left and right channels (hamster mode)
+    pub reverse: bool,
+
+    /// Position at and below which the right channel is fully cut
+    ///
+    /// Must be less than `cut_in_hi`.
+    pub cut_in_lo: f32,
+
+    /// Position at and above which the left channel is fully cut
+    ///
+    /// Must be greater than `cut_in_lo`.
+    pub cut_in_hi: f32,
+}
+
+impl CrossfaderConfig {
+    /// Full range without cut-in, not reversed
+    #[must_use]
+    pub const fn new(curve: CrossfaderCurve) -> Self {
+        Self {
+            curve,
+            reverse: false,
+            cut_in_lo: CenterSliderInput::MIN_POSITION,
+            cut_in_hi: CenterSliderInput::MAX_POSITION,
+        }
+    }
+
+    /// Stretch the range between the cut-in points onto the full range
+    #[must_use]
+    pub fn apply_cut_in(&self, input: CenterSliderInput) -> CenterSliderInput {
+        let Self {
+            cut_in_lo,
+            cut_in_hi,
+            ..
+        } = *self;
+        debug_assert!(cut_in_lo < cut_in_hi);
+        let CenterSliderInput { position } = input;
+        let position = (position - cut_in_lo) / (cut_in_hi - cut_in_lo) * 2.0
+            + CenterSliderInput::MIN_POSITION;
+        CenterSliderInput {
+            position: CenterSliderInput::clamp_position(position),
+        }
+    }
+
+    /// Apply the cut-in points before the curve
+    #[must_use]
+    pub fn split_input(&self, input: CenterSliderInput) -> (SliderInput, SliderInput) {
+        let input = self.apply_cut_in(input);
+        if self.reverse {
+            self.curve.split_input_reversed(input)
+        } else {
+            self.curve.split_input(input)
         }
     }
 }
 
+/// Convert a gain in dB into an amplitude ratio
+#[must_use]
+#[inline]
+pub fn db_to_ratio(db: f32) -> f32 {
+    10.0f32.powf(db / 20.0)
+}
+
+/// Convert an amplitude ratio into a gain in dB
+///
+/// Returns negative infinity for ratios <= 0.
+#[must_use]
 #[inline]
-fn db_to_ratio(gain: f32) -> f32 {
-    10.0f32.powf(gain / 20.0)
+pub fn ratio_to_db(ratio: f32) -> f32 {
+    if ratio <= 0.0 {
+        return f32::NEG_INFINITY;
+    }
+    20.0 * ratio.log10()
 }
 
 #[cfg(test)]
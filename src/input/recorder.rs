@@ -0,0 +1,180 @@
+// SPDX-FileCopyrightText: The djio authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Recording and replaying of control input events
+//!
+//! Events are stored in a simple binary format. Each record is
+//! prefixed by its length in bytes, followed by the time stamp
+//! in microseconds (u64), the control index (u32), and the raw
+//! bits of the control value (u32). All numbers are little-endian.
+//! Additional bytes at the end of a record are ignored.
+
+use std::io::{self, Read, Write};
+
+use thiserror::Error;
+
+use super::{ControlInputEvent, ControlInputEventSink};
+use crate::{Control, ControlIndex, ControlValue, TimeStamp};
+
+/// Length of a record without the length prefix
+const RECORD_LEN: u8 = 16;
+
+/// Writes control input events into a recording.
+///
+/// Writing stops after the first error, which could be inspected
+/// by invoking [`Self::finish()`].
+#[derive(Debug)]
+pub struct EventRecorder<W> {
+    writer: W,
+    recorded: usize,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> EventRecorder<W> {
+    #[must_use]
+    pub const fn new(writer: W) -> Self {
+        Self {
+            writer,
+            recorded: 0,
+            error: None,
+        }
+    }
+
+    /// The number of events that have been recorded successfully
+    #[must_use]
+    pub const fn recorded(&self) -> usize {
+        self.recorded
+    }
+
+    /// Record a single event
+    pub fn record_event(&mut self, event: &ControlInputEvent) -> io::Result<()> {
+        let ControlInputEvent {
+            ts,
+            input: Control { index, value },
+        } = event;
+        let mut record = [0; 1 + RECORD_LEN as usize];
+        record[0] = RECORD_LEN;
+        record[1..9].copy_from_slice(&ts.to_micros().to_le_bytes());
+        record[9..13].copy_from_slice(&index.value().to_le_bytes());
+        record[13..17].copy_from_slice(&value.to_bits().to_le_bytes());
+        self.writer.write_all(&record)?;
+        self.recorded += 1;
+        Ok(())
+    }
+
+    /// Flush the writer and return it
+    ///
+    /// Fails with the first error that occurred while recording.
+    pub fn finish(self) -> io::Result<W> {
+        let Self {
+            mut writer, error, ..
+        } = self;
+        if let Some(err) = error {
+            return Err(err);
+        }
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+
+impl<W: Write> ControlInputEventSink for EventRecorder<W> {
+    fn sink_control_input_events(&mut self, events: &[ControlInputEvent]) {
+        if self.error.is_some() {
+            return;
+        }
+        for event in events {
+            if let Err(err) = self.record_event(event) {
+                log::warn!("Failed to record event: {err}");
+                self.error = Some(err);
+                return;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum EventReadError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("invalid record length: {len}")]
+    InvalidRecordLength { len: u8 },
+    #[error("time stamp {ts} precedes the previous time stamp {previous_ts}")]
+    NonMonotonicTimeStamp {
+        previous_ts: TimeStamp,
+        ts: TimeStamp,
+    },
+}
+
+/// Reads control input events from a recording.
+///
+/// Yields events until the end of the recording or the first error.
+/// Time stamps of subsequent events must not decrease.
+#[derive(Debug)]
+pub struct EventReader<R> {
+    reader: R,
+    previous_ts: Option<TimeStamp>,
+    failed: bool,
+}
+
+impl<R: Read> EventReader<R> {
+    #[must_use]
+    pub const fn new(reader: R) -> Self {
+        Self {
+            reader,
+            previous_ts: None,
+            failed: false,
+        }
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        let Self { reader, .. } = self;
+        reader
+    }
+
+    fn read_event(&mut self) -> Result<Option<ControlInputEvent>, EventReadError> {
+        let mut len = [0; 1];
+        if self.reader.read(&mut len)? == 0 {
+            // End of recording
+            return Ok(None);
+        }
+        let [len] = len;
+        if len < RECORD_LEN {
+            return Err(EventReadError::InvalidRecordLength { len });
+        }
+        let mut record = vec![0; len.into()];
+        self.reader.read_exact(&mut record)?;
+        let ts = TimeStamp::from_micros(u64::from_le_bytes(
+            record[0..8].try_into().expect("8 bytes"),
+        ));
+        let index = ControlIndex::new(u32::from_le_bytes(
+            record[8..12].try_into().expect("4 bytes"),
+        ));
+        let value = ControlValue::from_bits(u32::from_le_bytes(
+            record[12..16].try_into().expect("4 bytes"),
+        ));
+        if let Some(previous_ts) = self.previous_ts {
+            if ts < previous_ts {
+                return Err(EventReadError::NonMonotonicTimeStamp { previous_ts, ts });
+            }
+        }
+        self.previous_ts = Some(ts);
+        Ok(Some(ControlInputEvent {
+            ts,
+            input: Control { index, value },
+        }))
+    }
+}
+
+impl<R: Read> Iterator for EventReader<R> {
+    type Item = Result<ControlInputEvent, EventReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let next = self.read_event().transpose();
+        self.failed = matches!(next, Some(Err(_)));
+        next
+    }
+}
@@ -0,0 +1,103 @@
+// SPDX-FileCopyrightText: The djio authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Forwarding control input events into an asynchronous channel
+
+use futures_core::Stream;
+use futures_util::stream;
+use tokio::sync::mpsc;
+
+use super::{ControlInputEvent, ControlInputEventSink};
+
+/// Forwards control input events into a bounded channel
+///
+/// Never blocks the caller, which is usually a device callback.
+/// Events are dropped if the channel is full.
+#[derive(Debug, Clone)]
+pub struct ControlInputEventSender {
+    tx: mpsc::Sender<ControlInputEvent>,
+    dropped: usize,
+}
+
+impl ControlInputEventSender {
+    /// The number of events that have been dropped
+    #[must_use]
+    pub const fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+impl ControlInputEventSink for ControlInputEventSender {
+    fn sink_control_input_events(&mut self, events: &[ControlInputEvent]) {
+        for event in events {
+            match self.tx.try_send(event.clone()) {
+                Ok(()) => (),
+                Err(mpsc::error::TrySendError::Full(event)) => {
+                    log::warn!("Dropping input event: {event:?}");
+                    self.dropped += 1;
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    log::debug!("Receiver has been dropped");
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Create a channel for receiving control input events asynchronously
+///
+/// The `capacity` must not be 0.
+#[must_use]
+pub fn control_input_event_channel(
+    capacity: usize,
+) -> (ControlInputEventSender, mpsc::Receiver<ControlInputEvent>) {
+    let (tx, rx) = mpsc::channel(capacity);
+    let sender = ControlInputEventSender { tx, dropped: 0 };
+    (sender, rx)
+}
+
+/// Create a [`Stream`] of control input events
+///
+/// Like [`control_input_event_channel()`], but the stream ends after
+/// all senders have been dropped.
+pub fn control_input_event_stream(
+    capacity: usize,
+) -> (
+    ControlInputEventSender,
+    impl Stream<Item = ControlInputEvent> + Send + 'static,
+) {
+    let (sender, mut rx) = control_input_event_channel(capacity);
+    let stream = stream::poll_fn(move |cx| rx.poll_recv(cx));
+    (sender, stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::control_input_event_channel;
+    use crate::{
+        Control, ControlIndex, ControlInputEvent, ControlInputEventSink as _, ControlValue,
+        TimeStamp,
+    };
+
+    #[test]
+    fn drop_events_if_full() {
+        let (mut sender, mut rx) = control_input_event_channel(1);
+        let event = ControlInputEvent {
+            ts: TimeStamp::from_micros(1),
+            input: Control {
+                index: ControlIndex::new(0),
+                value: ControlValue::from_bits(1),
+            },
+        };
+        sender.sink_control_input_events(&[event.clone(), event.clone()]);
+        assert_eq!(1, sender.dropped());
+        assert_eq!(Ok(event), rx.try_recv());
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -138,3 +138,672 @@ fn slider_encoder_from_u14() {
         SliderEncoderInput::from_u14(8192).delta
     );
 }
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn slider_encoder_velocity() {
+    let input = SliderEncoderInput { delta: 0.5 };
+    assert_eq!(2.0, input.velocity(Duration::from_millis(250)));
+    assert_eq!(0.0, input.velocity(Duration::ZERO));
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn scratch_velocity_tracker_clamps_elapsed() {
+    let max_elapsed = Duration::from_millis(100);
+    let mut tracker = ScratchVelocityTracker::new(0.0, max_elapsed);
+    let input = SliderEncoderInput { delta: 0.1 };
+    // The first input has no predecessor.
+    assert_eq!(1.0, tracker.update(TimeStamp::from_micros(0), input));
+    assert_eq!(10.0, tracker.update(TimeStamp::from_micros(10_000), input));
+    // Long gap between subsequent inputs.
+    assert_eq!(
+        1.0,
+        tracker.update(TimeStamp::from_micros(10_000_000), input)
+    );
+    // Identical time stamps.
+    assert_eq!(
+        100.0,
+        tracker.update(TimeStamp::from_micros(10_000_000), input)
+    );
+    tracker.reset();
+    assert_eq!(0.0, tracker.velocity());
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn scratch_velocity_tracker_smoothing() {
+    let mut tracker = ScratchVelocityTracker::new(0.5, Duration::from_millis(100));
+    let input = SliderEncoderInput { delta: 0.1 };
+    assert_eq!(0.5, tracker.update(TimeStamp::from_micros(0), input));
+    assert_eq!(0.75, tracker.update(TimeStamp::from_micros(100_000), input));
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn slider_with_deadzone() {
+    let input = SliderInput { position: 0.3 };
+    assert_eq!(input, input.with_deadzone(0.0, 1.0));
+    assert_eq!(
+        SliderInput::MIN_POSITION,
+        SliderInput { position: 0.05 }
+            .with_deadzone(0.1, 0.9)
+            .position
+    );
+    assert_eq!(
+        SliderInput::MAX_POSITION,
+        SliderInput { position: 0.95 }
+            .with_deadzone(0.1, 0.9)
+            .position
+    );
+    assert!(approx_eq!(
+        f32,
+        0.5,
+        SliderInput { position: 0.5 }
+            .with_deadzone(0.1, 0.9)
+            .position
+    ));
+    // Dead zones cover the whole range
+    assert_eq!(
+        SliderInput::MIN_POSITION,
+        SliderInput { position: 0.4 }
+            .with_deadzone(0.5, 0.5)
+            .position
+    );
+    assert_eq!(
+        SliderInput::MAX_POSITION,
+        SliderInput { position: 0.6 }
+            .with_deadzone(0.5, 0.5)
+            .position
+    );
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn center_slider_with_center_detent() {
+    let input = CenterSliderInput { position: -0.3 };
+    assert_eq!(input, input.with_center_detent(0.0));
+    assert_eq!(
+        CenterSliderInput::CENTER_POSITION,
+        CenterSliderInput { position: 0.05 }
+            .with_center_detent(0.2)
+            .position
+    );
+    assert_eq!(
+        CenterSliderInput::MIN_POSITION,
+        CenterSliderInput::from_u7(0)
+            .with_center_detent(0.2)
+            .position
+    );
+    assert_eq!(
+        CenterSliderInput::MAX_POSITION,
+        CenterSliderInput::from_u7(127)
+            .with_center_detent(0.2)
+            .position
+    );
+    assert!(approx_eq!(
+        f32,
+        -0.5,
+        CenterSliderInput { position: -0.55 }
+            .with_center_detent(0.2)
+            .position
+    ));
+    // Band covers the whole range
+    assert_eq!(
+        CenterSliderInput::CENTER_POSITION,
+        CenterSliderInput::from_u7(127)
+            .with_center_detent(2.0)
+            .position
+    );
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn slider_map_position_exponential() {
+    let (min, max) = (20.0, 20_000.0);
+    for curve in [0.5, 1.0, 2.0, 3.0] {
+        assert_eq!(
+            min,
+            SliderInput::from_u7(0).map_position_exponential(min, max, curve)
+        );
+        assert_eq!(
+            max,
+            SliderInput::from_u7(127).map_position_exponential(min, max, curve)
+        );
+    }
+    for input in 0..=127 {
+        let slider = SliderInput::from_u7(input);
+        assert!(approx_eq!(
+            f32,
+            slider.map_position_linear(min, max),
+            slider.map_position_exponential(min, max, 1.0),
+            epsilon = 0.01
+        ));
+    }
+    // Bending towards the minimum value
+    assert!(
+        SliderInput { position: 0.5 }.map_position_exponential(min, max, 2.0)
+            < SliderInput { position: 0.5 }.map_position_linear(min, max)
+    );
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn center_slider_map_position_exponential() {
+    let (min, center, max) = (-26.0, 0.0, 6.0);
+    for curve in [0.5, 1.0, 2.0, 3.0] {
+        assert_eq!(
+            min,
+            CenterSliderInput::from_u7(0).map_position_exponential(min, center, max, curve)
+        );
+        assert_eq!(
+            center,
+            CenterSliderInput::from_u7(64).map_position_exponential(min, center, max, curve)
+        );
+        assert_eq!(
+            max,
+            CenterSliderInput::from_u7(127).map_position_exponential(min, center, max, curve)
+        );
+    }
+    for input in 0..=127 {
+        let slider = CenterSliderInput::from_u7(input);
+        assert!(approx_eq!(
+            f32,
+            slider.map_position_linear(min, center, max),
+            slider.map_position_exponential(min, center, max, 1.0),
+            epsilon = 0.0001
+        ));
+    }
+}
+
+#[test]
+fn slice_event_source() {
+    let events = (0..3)
+        .map(|i| ControlInputEvent {
+            ts: TimeStamp::from_micros(i * 10),
+            input: Control {
+                index: crate::ControlIndex::new(0),
+                value: ControlValue::from_bits(0),
+            },
+        })
+        .collect::<Vec<_>>();
+    let mut source = SliceEventSource::new(&events);
+    let mut polled = Vec::new();
+    source.poll_events_until(TimeStamp::from_micros(10), &mut polled);
+    assert_eq!(&events[..2], polled.as_slice());
+    assert!(!source.is_exhausted());
+    polled.clear();
+    source.poll_events(&mut polled);
+    assert_eq!(&events[2..], polled.as_slice());
+    assert!(source.is_exhausted());
+    polled.clear();
+    source.poll_events(&mut polled);
+    assert!(polled.is_empty());
+    source.rewind();
+    assert_eq!(events.as_slice(), source.remaining_events());
+}
+
+fn recorded_events() -> Vec<ControlInputEvent> {
+    (0..3)
+        .map(|i| ControlInputEvent {
+            ts: TimeStamp::from_micros(u64::from(i) * 10),
+            input: Control {
+                index: crate::ControlIndex::new(i),
+                value: ControlValue::from_bits(i << 16),
+            },
+        })
+        .collect()
+}
+
+#[test]
+fn record_and_replay_events() {
+    let events = recorded_events();
+    let mut recorder = EventRecorder::new(Vec::new());
+    recorder.sink_control_input_events(&events);
+    assert_eq!(events.len(), recorder.recorded());
+    let recording = recorder.finish().unwrap();
+    let replayed = EventReader::new(recording.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(events, replayed);
+}
+
+#[test]
+fn remap_control_indices() {
+    let events = recorded_events();
+    let mapping = [(1, 10), (2, 20)]
+        .into_iter()
+        .map(|(from, to)| (ControlIndex::new(from), ControlIndex::new(to)))
+        .collect();
+    let mut remap = ControlIndexRemap::new(mapping, EventRecorder::new(Vec::new()));
+    assert_eq!(
+        Some(&ControlIndex::new(1)),
+        remap.inverse_mapping().get(&ControlIndex::new(10))
+    );
+    remap.sink_control_input_events(&events);
+    let recording = remap.into_inner().finish().unwrap();
+    let remapped = EventReader::new(recording.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    // The unmapped event has been discarded
+    assert_eq!(2, remapped.len());
+    assert_eq!(ControlIndex::new(10), remapped[0].input.index);
+    assert_eq!(events[1].input.value, remapped[0].input.value);
+    assert_eq!(ControlIndex::new(20), remapped[1].input.index);
+}
+
+#[test]
+fn replay_events_with_decreasing_time_stamps() {
+    let mut events = recorded_events();
+    events.swap(0, 2);
+    let mut recorder = EventRecorder::new(Vec::new());
+    for event in &events {
+        recorder.record_event(event).unwrap();
+    }
+    let recording = recorder.finish().unwrap();
+    let mut reader = EventReader::new(recording.as_slice());
+    assert!(reader.next().unwrap().is_ok());
+    assert!(matches!(
+        reader.next(),
+        Some(Err(EventReadError::NonMonotonicTimeStamp { .. }))
+    ));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn crossfader_config_cut_in_and_reverse() {
+    let mut config = CrossfaderConfig {
+        cut_in_lo: -0.5,
+        cut_in_hi: 0.5,
+        ..CrossfaderConfig::new(CrossfaderCurve::Linear)
+    };
+    let (left, right) = config.split_input(CenterSliderInput { position: -0.5 });
+    assert_eq!(SliderInput::MAX_POSITION, left.position);
+    assert_eq!(SliderInput::MIN_POSITION, right.position);
+    let (left, right) = config.split_input(CenterSliderInput { position: 0.75 });
+    assert_eq!(SliderInput::MIN_POSITION, left.position);
+    assert_eq!(SliderInput::MAX_POSITION, right.position);
+    config.reverse = true;
+    let (left, right) = config.split_input(CenterSliderInput { position: 0.75 });
+    assert_eq!(SliderInput::MAX_POSITION, left.position);
+    assert_eq!(SliderInput::MIN_POSITION, right.position);
+}
+
+#[test]
+fn crossfader_curve_sharp() {
+    for position in [-1.0, -0.5, 0.0, 0.5, 1.0] {
+        let input = CenterSliderInput { position };
+        assert_eq!(
+            split_crossfader_input_linear(input),
+            split_crossfader_input_sharp(input, 0.0)
+        );
+    }
+    // Extremes
+    for position in [
+        CenterSliderInput::MIN_POSITION,
+        CenterSliderInput::MAX_POSITION,
+    ] {
+        let input = CenterSliderInput { position };
+        assert_eq!(
+            split_crossfader_input_square(input),
+            split_crossfader_input_sharp(input, 1.0)
+        );
+        assert_eq!(
+            split_crossfader_input_square(input),
+            split_crossfader_input_sharp(input, 0.9)
+        );
+    }
+    let (left, right) = split_crossfader_input_sharp(CenterSliderInput { position: 0.5 }, 0.5);
+    assert!((left.position - 0.5).abs() < 1e-6);
+    assert!((right.position - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn step_accumulator() {
+    let mut accumulator = StepAccumulator::new()
+        .with_ticks_per_step(4)
+        .with_modulus(3);
+    assert_eq!(0, accumulator.apply(StepEncoderInput { delta: 3 }));
+    assert_eq!(1, accumulator.apply(StepEncoderInput { delta: 2 }));
+    assert_eq!(1, accumulator.value());
+    assert_eq!(-2, accumulator.apply(StepEncoderInput { delta: -9 }));
+    // Wrapped
+    assert_eq!(2, accumulator.value());
+    accumulator.reset();
+    assert_eq!(0, accumulator.value());
+}
+
+#[test]
+fn selector_input() {
+    #[derive(Debug, PartialEq, Eq, FromRepr)]
+    #[repr(u8)]
+    enum Mode {
+        A,
+        B,
+    }
+    assert_eq!(SelectorInput { choice: 2 }, SelectorInput::wrapped(-1, 3));
+    assert_eq!(SelectorInput { choice: 1 }, SelectorInput::wrapped(4, 3));
+    assert_eq!(
+        Some(Mode::B),
+        SelectorInput { choice: 1 }.to_enum(Mode::from_repr)
+    );
+    assert_eq!(None, SelectorInput { choice: 2 }.to_enum(Mode::from_repr));
+    assert_eq!(None, SelectorInput { choice: 256 }.to_enum(Mode::from_repr));
+}
+
+#[test]
+fn pad_button_with_curve_and_threshold() {
+    let input = PadButtonInput { pressure: 0.5 };
+    assert!((input.with_curve(VelocityCurve::Linear).pressure - 0.5).abs() < 1e-6);
+    assert!((input.with_curve(VelocityCurve::Exponential(2.0)).pressure - 0.25).abs() < 1e-6);
+    assert!((input.with_curve(VelocityCurve::SCurve).pressure - 0.5).abs() < 1e-6);
+    assert_eq!(
+        ButtonInput::Released,
+        PadButtonInput { pressure: 0.05 }
+            .with_threshold(0.1)
+            .as_button()
+    );
+    assert_eq!(ButtonInput::Pressed, input.with_threshold(0.1).as_button());
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn soft_takeover() {
+    let mut soft_takeover = SoftTakeover::new(0.5);
+    assert_eq!(None, soft_takeover.update(SliderInput { position: 0.2 }));
+    assert_eq!(None, soft_takeover.update(SliderInput { position: 0.4 }));
+    assert!(!soft_takeover.is_caught());
+    // Crossing the value
+    assert_eq!(
+        Some(0.6),
+        soft_takeover.update(SliderInput { position: 0.6 })
+    );
+    assert!(soft_takeover.is_caught());
+    assert_eq!(
+        Some(0.1),
+        soft_takeover.update(SliderInput { position: 0.1 })
+    );
+    // Modified by a different source
+    soft_takeover.set_value(0.8);
+    assert_eq!(None, soft_takeover.update(SliderInput { position: 0.2 }));
+    // Within tolerance
+    assert_eq!(
+        Some(0.795),
+        soft_takeover.update(SliderInput { position: 0.795 })
+    );
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn center_slider_soft_takeover() {
+    // From below
+    let mut soft_takeover = CenterSliderSoftTakeover::new(0.25);
+    assert_eq!(
+        None,
+        soft_takeover.update(CenterSliderInput { position: -0.5 })
+    );
+    assert_eq!(
+        Some(0.5),
+        soft_takeover.update(CenterSliderInput { position: 0.5 })
+    );
+    // From above
+    let mut soft_takeover = CenterSliderSoftTakeover::new(-0.25);
+    assert_eq!(
+        None,
+        soft_takeover.update(CenterSliderInput { position: 0.5 })
+    );
+    assert_eq!(
+        None,
+        soft_takeover.update(CenterSliderInput { position: 0.0 })
+    );
+    assert_eq!(
+        Some(-0.5),
+        soft_takeover.update(CenterSliderInput { position: -0.5 })
+    );
+    // Exactly at the target
+    let mut soft_takeover = CenterSliderSoftTakeover::new(0.75).with_tolerance(0.0);
+    assert_eq!(
+        Some(0.75),
+        soft_takeover.update(CenterSliderInput { position: 0.75 })
+    );
+    // Entering the center detent
+    let mut soft_takeover = CenterSliderSoftTakeover::new(0.0).with_center_detent(0.2);
+    assert_eq!(
+        None,
+        soft_takeover.update(CenterSliderInput { position: 0.5 })
+    );
+    assert_eq!(
+        Some(0.0),
+        soft_takeover.update(CenterSliderInput { position: 0.09 })
+    );
+    assert_eq!(
+        Some(0.0),
+        soft_takeover.update(CenterSliderInput { position: -0.05 })
+    );
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn center_slider_gain_ratio_with_silence() {
+    let min = CenterSliderInput {
+        position: CenterSliderInput::MIN_POSITION,
+    };
+    let center = CenterSliderInput {
+        position: CenterSliderInput::CENTER_POSITION,
+    };
+    let max = CenterSliderInput {
+        position: CenterSliderInput::MAX_POSITION,
+    };
+    assert_eq!(0.0, min.map_position_to_gain_ratio_with_silence(None, 6.0));
+    assert_eq!(
+        1.0,
+        center.map_position_to_gain_ratio_with_silence(None, 6.0)
+    );
+    assert_eq!(
+        max.map_position_to_gain_ratio(-26.0, 6.0),
+        max.map_position_to_gain_ratio_with_silence(Some(-26.0), 6.0)
+    );
+    assert_eq!(
+        min.map_position_to_gain_ratio(-26.0, 6.0),
+        min.map_position_to_gain_ratio_with_silence(Some(-26.0), 6.0)
+    );
+    // Unity gain for the upper half
+    assert_eq!(1.0, max.map_position_to_gain_ratio_with_silence(None, 0.0));
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn db_ratio_conversion() {
+    assert_eq!(1.0, db_to_ratio(0.0));
+    assert_eq!(0.0, ratio_to_db(1.0));
+    assert_eq!(f32::NEG_INFINITY, ratio_to_db(0.0));
+    assert_eq!(f32::NEG_INFINITY, ratio_to_db(-1.0));
+    for db in [-26.0, -6.0, 6.0] {
+        assert!((db - ratio_to_db(db_to_ratio(db))).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn level_input() {
+    use LedOutput::{Off, On};
+    let input = LevelInput {
+        rms: 0.5,
+        peak: 0.75,
+    };
+    let decoded = LevelInput::from(ControlValue::from(input));
+    assert!((decoded.rms - input.rms).abs() < 1e-4);
+    assert!((decoded.peak - input.peak).abs() < 1e-4);
+    assert_eq!(
+        vec![On, On, On, On, Off, On, Off, Off],
+        input.to_led_segments(8)
+    );
+}
+
+#[test]
+fn midi_learn() {
+    let event = |index| ControlInputEvent {
+        ts: TimeStamp::from_micros(0),
+        input: Control {
+            index: ControlIndex::new(index),
+            value: ButtonInput::Pressed.into(),
+        },
+    };
+    let slider = InputControlDescriptor {
+        index: ControlIndex::new(1),
+        name: "Slider".into(),
+        kind: InputControlKind::Slider,
+    };
+    let mut learn = MidiLearn::new(2).with_continuous_controls_ignored(&[slider]);
+    learn.sink_control_input_events(&[event(1), event(2), event(2)]);
+    assert_eq!([ControlIndex::new(2)].as_slice(), learn.learned());
+    assert!(!learn.is_complete());
+    learn.sink_control_input_events(&[event(3), event(4)]);
+    assert!(learn.is_complete());
+    assert_eq!(
+        vec![ControlIndex::new(2), ControlIndex::new(3)],
+        learn.take_learned()
+    );
+    assert!(learn.learned().is_empty());
+}
+
+#[test]
+fn debounce_button() {
+    let window = Duration::from_millis(10);
+    let button = ControlIndex::new(1);
+    let slider = ControlIndex::new(2);
+    let event = |millis: u64, index, value| ControlInputEvent {
+        ts: TimeStamp::from_micros(millis * 1000),
+        input: Control { index, value },
+    };
+    let pressed = ControlValue::from(ButtonInput::Pressed);
+    let released = ControlValue::from(ButtonInput::Released);
+    let mut debouncer = Debouncer::new(window, [button], EventRecorder::new(Vec::new()));
+    debouncer.sink_control_input_events(&[
+        event(0, button, pressed),
+        // Bouncing within the window
+        event(2, button, released),
+        event(4, button, pressed),
+        // Not debounced
+        event(5, slider, released),
+        event(6, slider, pressed),
+        // Beyond the window
+        event(20, button, pressed),
+        event(21, button, released),
+    ]);
+    debouncer.poll(TimeStamp::from_micros(40_000));
+    let recording = debouncer.into_inner().finish().unwrap();
+    let forwarded = EventReader::new(recording.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        vec![
+            event(5, slider, released),
+            event(6, slider, pressed),
+            // Settled after the last bounce
+            event(14, button, pressed),
+            event(31, button, released),
+        ],
+        forwarded
+    );
+}
+
+#[test]
+fn debounce_short_button_tap() {
+    let window = Duration::from_millis(10);
+    let button = ControlIndex::new(1);
+    let event = |millis: u64, value| ControlInputEvent {
+        ts: TimeStamp::from_micros(millis * 1000),
+        input: Control {
+            index: button,
+            value,
+        },
+    };
+    let pressed = ControlValue::from(ButtonInput::Pressed);
+    let released = ControlValue::from(ButtonInput::Released);
+    let mut debouncer = Debouncer::new(window, [button], EventRecorder::new(Vec::new()));
+    debouncer.sink_control_input_events(&[
+        // Tap within the window
+        event(0, pressed),
+        event(2, released),
+        // Tap beyond the window
+        event(30, pressed),
+        event(50, released),
+    ]);
+    debouncer.poll(TimeStamp::from_micros(100_000));
+    let recording = debouncer.into_inner().finish().unwrap();
+    let forwarded = EventReader::new(recording.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        vec![event(12, released), event(40, pressed), event(60, released)],
+        forwarded
+    );
+    assert_eq!(
+        Some(ButtonInput::Released),
+        forwarded.last().map(|event| event.input.value.into())
+    );
+}
+
+#[test]
+fn button_gestures() {
+    let event = |millis: u64, input| InputEvent {
+        ts: TimeStamp::from_micros(millis * 1000),
+        input,
+    };
+    let mut detector = ButtonGestureDetector::new();
+    assert_eq!(None, detector.update(&event(0, ButtonInput::Pressed)));
+    assert_eq!(
+        Some(ButtonGesture::Tap),
+        detector.update(&event(100, ButtonInput::Released))
+    );
+    assert_eq!(None, detector.update(&event(200, ButtonInput::Pressed)));
+    assert_eq!(
+        Some(ButtonGesture::DoubleTap),
+        detector.update(&event(300, ButtonInput::Released))
+    );
+    // A third tap starts over
+    assert_eq!(None, detector.update(&event(400, ButtonInput::Pressed)));
+    assert_eq!(
+        Some(ButtonGesture::Tap),
+        detector.update(&event(500, ButtonInput::Released))
+    );
+    assert_eq!(None, detector.update(&event(2000, ButtonInput::Pressed)));
+    assert_eq!(None, detector.poll(TimeStamp::from_micros(2_100_000)));
+    assert_eq!(
+        Some(ButtonGesture::Hold),
+        detector.poll(TimeStamp::from_micros(2_500_000))
+    );
+    assert_eq!(None, detector.poll(TimeStamp::from_micros(2_600_000)));
+    assert_eq!(
+        Some(ButtonGesture::LongPress),
+        detector.update(&event(3000, ButtonInput::Released))
+    );
+}
+
+#[test]
+fn layers() {
+    let shift = ControlIndex::new(1);
+    let alt = ControlIndex::new(2);
+    let play = ControlIndex::new(3);
+    let input = |index, input: ButtonInput| Control {
+        index,
+        value: input.into(),
+    };
+    let mapping = LayerMapping::new()
+        .with_action(play, 0, "play")
+        .with_action(play, 1, "cue")
+        .with_action(play, 3, "stop");
+    let mut state = LayerState::new(vec![shift, alt]);
+    assert_eq!(Some(&"play"), mapping.current_action(play, &state));
+    assert!(state.update(input(shift, ButtonInput::Pressed)));
+    assert_eq!(Some(&"cue"), mapping.current_action(play, &state));
+    assert!(state.update(input(alt, ButtonInput::Pressed)));
+    assert_eq!(3, state.layer());
+    assert_eq!(Some(&"stop"), mapping.current_action(play, &state));
+    assert!(state.update(input(shift, ButtonInput::Released)));
+    assert_eq!(None, mapping.current_action(play, &state));
+    assert!(!state.update(input(play, ButtonInput::Pressed)));
+    state.reset();
+    assert_eq!(0, state.layer());
+}
@@ -8,50 +8,143 @@ use std::{
     borrow::Cow,
     fmt,
     sync::atomic::{AtomicU32, Ordering},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 mod controller;
 #[cfg(all(feature = "midi", feature = "controller-thread"))]
 pub use self::controller::midi::context::SingleMidiControllerContext;
 #[cfg(feature = "midi")]
-pub use self::controller::midi::{BoxedMidiController, MidiController};
+pub use self::controller::midi::{
+    BoxedMidiController, ControllerRegistry, MidiController, NewMidiController,
+};
 #[cfg(feature = "controller-thread")]
 pub use self::controller::thread::ControllerThread;
 pub use self::controller::{
-    BoxedControllerTask, Controller, ControllerDescriptor, ControllerTypes,
+    BoxedControllerTask, Controller, ControllerCapabilities, ControllerDescriptor, ControllerTypes,
+    InputControlDescriptor, InputControlKind, OutputControlDescriptor, OutputControlKind,
 };
 
 pub mod devices;
 
 mod input;
+#[cfg(feature = "input-event-stream")]
+pub use self::input::{
+    control_input_event_channel, control_input_event_stream, ControlInputEventSender,
+};
 pub use self::input::{
-    input_events_ordered_chronologically, split_crossfader_input_amplitude_preserving_approx,
+    db_to_ratio, input_events_ordered_chronologically, ratio_to_db,
+    split_crossfader_input_amplitude_preserving_approx,
     split_crossfader_input_energy_preserving_approx, split_crossfader_input_linear,
-    split_crossfader_input_square, ButtonInput, CenterSliderInput, ControlInputEvent,
-    ControlInputEventSink, CrossfaderCurve, InputEvent, PadButtonInput, SelectorInput,
-    SliderEncoderInput, SliderInput, StepEncoderInput,
+    split_crossfader_input_sharp, split_crossfader_input_square, ButtonGesture,
+    ButtonGestureDetector, ButtonInput, CenterSliderInput, CenterSliderSoftTakeover,
+    ControlIndexRemap, ControlInputEvent, ControlInputEventSink, ControlInputEventSource,
+    CrossfaderConfig, CrossfaderCurve, Debouncer, EventReadError, EventReader, EventRecorder,
+    InputEvent, LayerMapping, LayerState, LevelInput, MidiLearn, PadButtonInput,
+    ScratchVelocityTracker, SelectorInput, SliceEventSource, SliderEncoderInput, SliderInput,
+    SoftTakeover, StepAccumulator, StepEncoderInput, VelocityCurve,
 };
 
 mod output;
+#[cfg(all(feature = "blinking-led-interval", not(target_family = "wasm")))]
+pub use self::output::blinking_led_interval_stream;
 #[cfg(feature = "blinking-led-task")]
 pub use self::output::blinking_led_task;
 #[cfg(feature = "blinking-led-task-tokio-rt")]
 pub use self::output::spawn_blinking_led_task;
+#[cfg(feature = "experimental-param")]
+pub use self::output::DimmableVirtualLed;
+#[cfg(feature = "osc")]
 pub use self::output::{
-    BlinkingLedOutput, BlinkingLedTicker, ControlOutputGateway, DimLedOutput, LedOutput, LedState,
-    OutputError, OutputResult, RgbLedOutput, SendOutputsError, VirtualLed,
-    DEFAULT_BLINKING_LED_PERIOD,
+    default_osc_address_mapper, encode_osc_bundle, encode_osc_message, osc_time_tag_now,
+    OscAddressMapper, OscOutputGateway, OSC_TIME_TAG_IMMEDIATELY,
+};
+pub use self::output::{
+    BlinkingLedOutput, BlinkingLedTicker, BoxedControlOutputGateway, CoalescingOutputGateway,
+    ControlIndexRemapOutput, ControlOutputGateway, DimLedOutput, FanOutGateway, LedOutput,
+    LedState, LevelMeter, NullOutputGateway, OutputError, OutputResult, PulsePattern, RgbLedOutput,
+    SendOutputsError, VirtualLed, DEFAULT_BLINKING_LED_PERIOD, DEFAULT_LED_GAMMA,
 };
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AudioChannelDirection {
+    Input,
+    Output,
+}
+
+/// Purpose of audio channels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AudioChannelRole {
+    /// Main or master output
+    Main,
+    /// Booth monitor output
+    Booth,
+    /// Headphones output for pre-listening (cueing)
+    Headphones,
+    Microphone,
+    /// Line or phono input
+    Line,
+}
+
+/// Adjacent channels of an audio interface with a common purpose
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AudioChannelDescriptor {
+    pub direction: AudioChannelDirection,
+    /// Zero-based index of the first channel
+    pub first_channel: u8,
+    /// Number of adjacent channels, e.g. 2 for stereo
+    pub num_channels: u8,
+    pub role: AudioChannelRole,
+}
+
+impl AudioChannelDescriptor {
+    #[must_use]
+    pub const fn input(first_channel: u8, num_channels: u8, role: AudioChannelRole) -> Self {
+        Self {
+            direction: AudioChannelDirection::Input,
+            first_channel,
+            num_channels,
+            role,
+        }
+    }
+
+    #[must_use]
+    pub const fn output(first_channel: u8, num_channels: u8, role: AudioChannelRole) -> Self {
+        Self {
+            direction: AudioChannelDirection::Output,
+            first_channel,
+            num_channels,
+            role,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AudioInterfaceDescriptor {
     pub num_input_channels: u8,
     pub num_output_channels: u8,
+
+    /// Routing of the channels
+    ///
+    /// Might be incomplete, i.e. not all channels have a known role.
+    pub channels: Cow<'static, [AudioChannelDescriptor]>,
+}
+
+impl AudioInterfaceDescriptor {
+    /// The first channels with the given role
+    #[must_use]
+    pub fn find_channels(&self, role: AudioChannelRole) -> Option<&AudioChannelDescriptor> {
+        self.channels.iter().find(|channels| channels.role == role)
+    }
 }
 
 /// Common, information properties about a device.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceDescriptor {
     pub vendor_name: Cow<'static, str>,
     pub product_name: Cow<'static, str>,
@@ -80,6 +173,8 @@ impl DeviceDescriptor {
 #[derive(
     Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Display,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[repr(transparent)]
 pub struct PortIndex {
     value: u32,
@@ -161,6 +256,8 @@ impl Default for PortIndexGenerator {
 ///
 /// Only valid in the scope of a single device.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[repr(transparent)]
 pub struct ControlIndex {
     value: u32,
@@ -186,6 +283,8 @@ impl ControlIndex {
 
 /// A generic, encoded control value.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[repr(transparent)]
 pub struct ControlValue {
     bits: u32,
@@ -202,10 +301,74 @@ impl ControlValue {
         let Self { bits } = self;
         bits
     }
+
+    /// Encode a floating-point number
+    ///
+    /// Used by [`PadButtonInput`], [`SliderInput`], [`CenterSliderInput`],
+    /// and [`SliderEncoderInput`].
+    #[must_use]
+    pub fn from_f32(value: f32) -> Self {
+        Self::from_bits(value.to_bits())
+    }
+
+    /// Decode a floating-point number
+    ///
+    /// Only meaningful for values that have been encoded by [`Self::from_f32()`].
+    /// The raw bits of other values would be reinterpreted silently!
+    #[must_use]
+    pub fn to_f32(self) -> f32 {
+        f32::from_bits(self.to_bits())
+    }
+
+    /// Encode a signed integer
+    ///
+    /// Used by [`StepEncoderInput`].
+    #[must_use]
+    #[allow(clippy::cast_sign_loss)]
+    pub const fn from_i32(value: i32) -> Self {
+        Self::from_bits(value as u32)
+    }
+
+    /// Decode a signed integer
+    ///
+    /// Only meaningful for values that have been encoded by [`Self::from_i32()`].
+    /// The raw bits of other values would be reinterpreted silently!
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap)]
+    pub const fn to_i32(self) -> i32 {
+        self.to_bits() as i32
+    }
+}
+
+/// (De-)serialize a float-backed [`ControlValue`] by its logical value.
+///
+/// [`ControlValue`] is type-erased and (de-)serialized as raw bits by
+/// default. Use this module with `#[serde(with = "...")]` for values that
+/// are known to contain an `f32`, e.g. slider positions.
+#[cfg(feature = "serde")]
+pub mod serde_control_value_f32 {
+    use serde::{Deserialize as _, Deserializer, Serialize as _, Serializer};
+
+    use super::ControlValue;
+
+    pub fn serialize<S>(value: &ControlValue, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        value.to_f32().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<ControlValue, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        f32::deserialize(deserializer).map(ControlValue::from_f32)
+    }
 }
 
 /// Generic, indexed input/output control value.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Control {
     pub index: ControlIndex,
     pub value: ControlValue,
@@ -234,6 +397,83 @@ impl TimeStamp {
     pub const fn to_duration(self) -> Duration {
         Duration::from_micros(self.to_micros())
     }
+
+    /// The time span that has elapsed since an earlier time stamp
+    ///
+    /// Returns `None` if `earlier` is actually later than `self`.
+    #[must_use]
+    pub const fn duration_since(self, earlier: Self) -> Option<Duration> {
+        let Some(micros) = self.to_micros().checked_sub(earlier.to_micros()) else {
+            return None;
+        };
+        Some(Duration::from_micros(micros))
+    }
+
+    /// Move the time stamp back in time, saturating at 0
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn saturating_sub(self, duration: Duration) -> Self {
+        let micros = if duration.as_micros() > u64::MAX as u128 {
+            u64::MAX
+        } else {
+            duration.as_micros() as u64
+        };
+        Self::from_micros(self.to_micros().saturating_sub(micros))
+    }
+
+    /// Move the time stamp forward in time, saturating at the maximum
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn saturating_add(self, duration: Duration) -> Self {
+        let micros = if duration.as_micros() > u64::MAX as u128 {
+            u64::MAX
+        } else {
+            duration.as_micros() as u64
+        };
+        Self::from_micros(self.to_micros().saturating_add(micros))
+    }
+}
+
+/// Monotonic source of time stamps
+///
+/// Time stamps are measured relative to the creation of the clock.
+/// Allows to stamp events from sources that don't provide time stamps
+/// on their own, e.g. HID reports. All events that should be compared
+/// must be stamped by the same clock.
+#[derive(Debug, Clone, Copy)]
+pub struct MonotonicClock {
+    origin: Instant,
+}
+
+impl MonotonicClock {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+
+    /// Time stamp of an instant
+    ///
+    /// Instants before the origin of the clock are mapped to 0.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn time_stamp(&self, instant: Instant) -> TimeStamp {
+        let micros = instant.saturating_duration_since(self.origin).as_micros();
+        TimeStamp::from_micros(micros.min(u64::MAX.into()) as u64)
+    }
+
+    /// Time stamp of the current instant
+    #[must_use]
+    pub fn now(&self) -> TimeStamp {
+        self.time_stamp(Instant::now())
+    }
+}
+
+impl Default for MonotonicClock {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl fmt::Display for TimeStamp {
@@ -258,7 +498,8 @@ pub mod hid;
 
 #[cfg(all(feature = "hid", not(target_family = "wasm")))]
 pub use self::hid::{
-    HidApi, HidDevice, HidDeviceError, HidError, HidResult, HidThread, HidUsagePage,
+    HidApi, HidDevice, HidDeviceChange, HidDeviceError, HidDeviceWatcher, HidError, HidResult,
+    HidThread, HidThreadConfig, HidUsagePage,
 };
 
 #[cfg(feature = "midi")]
@@ -266,15 +507,33 @@ mod midi;
 
 #[cfg(feature = "midir")]
 pub use self::midi::midir::{
-    MidiPortError, MidirDevice, MidirDeviceManager, MidirInputPort, MidirOutputPort,
+    MidiDeviceChange, MidiPortError, MidirDevice, MidirDeviceManager, MidirInputPort,
+    MidirOutputPort,
+};
+#[cfg(feature = "midir")]
+pub use self::midi::midir_thread::{
+    MidiConnectionState, MidiControllerThread, MidiControllerThreadOutput,
 };
+#[cfg(all(feature = "web-midi", target_family = "wasm"))]
+pub use self::midi::web_midi::{
+    find_web_midi_ports, WebMidiInputConnection, WebMidiInputPort, WebMidiOutputConnection,
+    WebMidiOutputPort,
+};
+#[cfg(all(feature = "midi", not(target_family = "wasm")))]
+pub use self::midi::RateLimitedMidiOutput;
 #[cfg(feature = "midi")]
 pub use self::midi::{
-    consume_midi_input_event, BoxedMidiOutputConnection, MidiControlOutputGateway,
-    MidiDeviceDescriptor, MidiInputConnector, MidiInputDecodeError, MidiInputEventDecoder,
-    MidiInputGateway, MidiInputHandler, MidiOutputConnection, MidiOutputGateway,
-    MidiPortDescriptor, NewMidiInputGateway,
+    consume_midi_input_event, decode_midi_aftertouch, is_midi_aftertouch_status,
+    normalize_note_message, select_device, validate_and_decode_midi_input_event,
+    validate_midi_message, BoxedMidiInputEventDecoder, BoxedMidiOutputConnection,
+    Cc14BitAccumulator, ChainedDecoder, DeviceSelection, LoggingMidiInputHandler, MidiAftertouch,
+    MidiClock, MidiControlOutputGateway, MidiDeviceDescriptor, MidiInputConnector,
+    MidiInputDecodeError, MidiInputEventDecoder, MidiInputGateway, MidiInputHandler, MidiMessage,
+    MidiOutputConnection, MidiOutputGateway, MidiPortDescriptor, MidiPortNameMatching,
+    NewMidiInputGateway, NullMidiOutputConnection, RunningStatusReassembler, SelectDeviceError,
 };
+#[cfg(all(feature = "midi", any(test, feature = "test-util")))]
+pub use self::midi::{CaptureMidiOutput, LoopbackMidi, LoopbackMidiOutput};
 
 pub mod deck;
 #[cfg(feature = "observables")]
@@ -293,6 +552,71 @@ mod tests {
         assert_eq!(PortIndex::INVALID, PortIndex::default());
     }
 
+    #[test]
+    #[cfg(feature = "serde")]
+    #[allow(clippy::float_cmp)]
+    fn serde_round_trip() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Position {
+            #[serde(with = "serde_control_value_f32")]
+            value: ControlValue,
+        }
+
+        const CHANNELS: &[AudioChannelDescriptor] = &[
+            AudioChannelDescriptor::output(0, 2, AudioChannelRole::Main),
+            AudioChannelDescriptor::input(0, 1, AudioChannelRole::Microphone),
+        ];
+
+        let control = Control {
+            index: ControlIndex::new(7),
+            value: ControlValue::from_f32(0.25),
+        };
+        let json = serde_json::to_string(&control).unwrap();
+        assert_eq!(control, serde_json::from_str(&json).unwrap());
+
+        let json = serde_json::to_string(&Position {
+            value: ControlValue::from_f32(0.25),
+        })
+        .unwrap();
+        assert_eq!(r#"{"value":0.25}"#, json);
+        let position: Position = serde_json::from_str(&json).unwrap();
+        assert_eq!(0.25, position.value.to_f32());
+
+        let audio_interface = AudioInterfaceDescriptor {
+            num_input_channels: 1,
+            num_output_channels: 2,
+            channels: Cow::Borrowed(CHANNELS),
+        };
+        let json = serde_json::to_string(&audio_interface).unwrap();
+        let deserialized: AudioInterfaceDescriptor = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized.channels, Cow::Owned(_)));
+        assert_eq!(audio_interface, deserialized);
+    }
+
+    #[test]
+    fn time_stamp_arithmetic() {
+        let earlier = TimeStamp::from_micros(1_000);
+        let later = TimeStamp::from_micros(3_500);
+        assert_eq!(
+            Some(Duration::from_micros(2_500)),
+            later.duration_since(earlier)
+        );
+        assert_eq!(None, earlier.duration_since(later));
+        assert_eq!(earlier, later.saturating_sub(Duration::from_micros(2_500)));
+        assert_eq!(
+            TimeStamp::default(),
+            earlier.saturating_sub(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn monotonic_clock() {
+        let clock = MonotonicClock::new();
+        let first = clock.now();
+        let second = clock.now();
+        assert!(first <= second);
+    }
+
     #[test]
     fn next_port_index() {
         assert_eq!(PortIndex::MIN, PortIndex::INVALID.next());
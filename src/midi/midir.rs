@@ -10,7 +10,10 @@ use midir::{
 use thiserror::Error;
 
 use super::{MidiDeviceDescriptor, MidiInputGateway, MidiPortDescriptor, NewMidiInputGateway};
-use crate::{MidiInputHandler, OutputError, PortIndexGenerator, TimeStamp};
+use crate::{
+    MidiInputHandler, OutputError, PortIndex, PortIndexGenerator, RunningStatusReassembler,
+    TimeStamp,
+};
 
 #[derive(Debug, Error)]
 pub enum MidiPortError {
@@ -171,12 +174,15 @@ where
                 (input, input_gateway)
             };
         input_gateway.connect_midi_input_port(&self.descriptor, &self.input_port.descriptor);
+        let mut running_status_reassembler = RunningStatusReassembler::new();
         input
             .connect(
                 &self.input_port.port,
                 port_name,
-                |micros, input, input_handler| {
-                    handle_input(micros, input, input_handler);
+                move |micros, input, input_handler| {
+                    running_status_reassembler.reassemble(input, |message| {
+                        handle_input(micros, message, input_handler);
+                    });
                 },
                 input_gateway,
             )
@@ -253,25 +259,25 @@ where
         })
     }
 
-    #[must_use]
-    #[allow(clippy::missing_panics_doc)] // Never panics
-    pub fn detect_dj_controllers(
+    /// Find matching pairs of input and output ports
+    #[allow(clippy::type_complexity)]
+    fn detect_dj_controller_ports<'d>(
         &self,
-        device_descriptors: &[&MidiDeviceDescriptor],
-        port_index_generator: &PortIndexGenerator,
-    ) -> Vec<(MidiDeviceDescriptor, MidirDevice<I>)> {
+        device_descriptors: &[&'d MidiDeviceDescriptor],
+    ) -> Vec<(
+        &'d MidiDeviceDescriptor,
+        (String, MidiInputPort),
+        (String, MidiOutputPort),
+    )> {
         let mut input_ports = self
             .input_ports()
             .into_iter()
             .filter_map(|port| {
                 let port_name = self.input.port_name(&port).ok()?;
-                let Some(device_descriptor) =
-                    device_descriptors
-                        .iter()
-                        .copied()
-                        .find(|device_descriptor| {
-                            port_name.starts_with(device_descriptor.port_name_prefix)
-                        })
+                let Some(device_descriptor) = device_descriptors
+                    .iter()
+                    .copied()
+                    .find(|device_descriptor| device_descriptor.matches_port_name(&port_name))
                 else {
                     log::debug!("Input port \"{port_name}\" does not belong to a DJ controller");
                     return None;
@@ -289,9 +295,11 @@ where
             .filter_map(|port| {
                 let port_name = self.output.port_name(&port).ok()?;
                 let Some(port_name_prefix) = input_ports
-                    .keys()
-                    .copied()
-                    .find(|port_name_prefix| port_name.starts_with(port_name_prefix))
+                    .iter()
+                    .find(|(_, (device_descriptor, _, _))| {
+                        device_descriptor.matches_port_name(&port_name)
+                    })
+                    .map(|(port_name_prefix, _)| *port_name_prefix)
                 else {
                     log::debug!("Output port \"{port_name}\" does not belong to a DJ controller");
                     return None;
@@ -306,39 +314,131 @@ where
         debug_assert_eq!(input_ports.len(), output_ports.len());
         input_ports
             .into_iter()
-            .map(
+            .filter_map(
                 |(port_name_prefix, (descriptor, input_port_name, input_port))| {
-                    let (output_port_name, output_port) =
-                        output_ports.remove(port_name_prefix).expect("Some");
-                    log::debug!(
-                        "Found DJ controller device \"{device_name}\" (input port: \
-                         \"{input_port_name}\", output port: \"{output_port_name}\")",
-                        device_name = descriptor.device.name()
-                    );
-                    let input_port = MidirInputPort {
-                        descriptor: MidiPortDescriptor {
-                            index: port_index_generator.next(),
-                            name: input_port_name.into(),
-                        },
-                        port: input_port,
-                    };
-                    let output_port = MidirOutputPort {
-                        descriptor: MidiPortDescriptor {
-                            index: port_index_generator.next(),
-                            name: output_port_name.into(),
-                        },
-                        port: output_port,
-                    };
-                    let device = MidirDevice::new(descriptor.clone(), input_port, output_port);
-                    (descriptor.clone(), device)
+                    let output_port = output_ports.remove(port_name_prefix)?;
+                    Some((descriptor, (input_port_name, input_port), output_port))
                 },
             )
             .collect()
     }
+
+    #[must_use]
+    pub fn detect_dj_controllers(
+        &self,
+        device_descriptors: &[&MidiDeviceDescriptor],
+        port_index_generator: &PortIndexGenerator,
+    ) -> Vec<(MidiDeviceDescriptor, MidirDevice<I>)> {
+        self.detect_dj_controller_ports(device_descriptors)
+            .into_iter()
+            .map(|(descriptor, input_port, output_port)| {
+                let device = new_device(descriptor, input_port, output_port, port_index_generator);
+                (descriptor.clone(), device)
+            })
+            .collect()
+    }
+
+    /// Detect added and removed DJ controllers
+    ///
+    /// The `known` devices are keyed by the [`PortIndex`] of their input port
+    /// and are updated accordingly. Devices are matched by their descriptor,
+    /// i.e. the name prefix of their ports.
+    ///
+    /// Added devices are not connected yet.
+    #[must_use]
+    pub fn poll_changes(
+        &self,
+        device_descriptors: &[&MidiDeviceDescriptor],
+        port_index_generator: &PortIndexGenerator,
+        known: &mut HashMap<PortIndex, MidiDeviceDescriptor>,
+    ) -> Vec<MidiDeviceChange<I>> {
+        let detected = self.detect_dj_controller_ports(device_descriptors);
+        let mut changes = Vec::new();
+        known.retain(|&port_index, descriptor| {
+            if detected
+                .iter()
+                .any(|(detected_descriptor, _, _)| *detected_descriptor == descriptor)
+            {
+                return true;
+            }
+            log::debug!("Removed DJ controller device {descriptor:?}");
+            changes.push(MidiDeviceChange::Removed {
+                port_index,
+                descriptor: descriptor.clone(),
+            });
+            false
+        });
+        for (descriptor, input_port, output_port) in detected {
+            if known
+                .values()
+                .any(|known_descriptor| known_descriptor == descriptor)
+            {
+                continue;
+            }
+            let device = new_device(descriptor, input_port, output_port, port_index_generator);
+            log::debug!("Added DJ controller device {descriptor:?}");
+            known.insert(device.input_port.descriptor.index, descriptor.clone());
+            changes.push(MidiDeviceChange::Added(device));
+        }
+        changes
+    }
+}
+
+/// Change reported by [`MidirDeviceManager::poll_changes()`]
+#[allow(missing_debug_implementations)]
+pub enum MidiDeviceChange<I>
+where
+    I: MidiInputGateway + Send + 'static,
+{
+    Added(MidirDevice<I>),
+    Removed {
+        /// Index of the input port
+        port_index: PortIndex,
+        descriptor: MidiDeviceDescriptor,
+    },
+}
+
+fn new_device<I>(
+    descriptor: &MidiDeviceDescriptor,
+    (input_port_name, input_port): (String, MidiInputPort),
+    (output_port_name, output_port): (String, MidiOutputPort),
+    port_index_generator: &PortIndexGenerator,
+) -> MidirDevice<I>
+where
+    I: MidiInputGateway + Send,
+{
+    log::debug!(
+        "Found DJ controller device \"{device_name}\" (input port: \"{input_port_name}\", output \
+         port: \"{output_port_name}\")",
+        device_name = descriptor.device.name()
+    );
+    let input_port = MidirInputPort {
+        descriptor: MidiPortDescriptor {
+            index: port_index_generator.next(),
+            name: input_port_name.into(),
+        },
+        port: input_port,
+    };
+    let output_port = MidirOutputPort {
+        descriptor: MidiPortDescriptor {
+            index: port_index_generator.next(),
+            name: output_port_name.into(),
+        },
+        port: output_port,
+    };
+    MidirDevice::new(descriptor.clone(), input_port, output_port)
 }
 
 impl super::MidiOutputConnection for MidiOutputConnection {
     fn send_midi_output(&mut self, output: &[u8]) -> crate::OutputResult<()> {
         self.send(output).map_err(Into::into)
     }
+
+    fn send_midi_outputs(&mut self, messages: &[&[u8]]) -> crate::OutputResult<()> {
+        // midir doesn't support sending multiple messages at once
+        for output in messages {
+            self.send(output)?;
+        }
+        Ok(())
+    }
 }
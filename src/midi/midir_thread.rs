@@ -0,0 +1,208 @@
+// SPDX-FileCopyrightText: The djio authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Background thread for (re-)connecting a [`MidirDevice`]
+
+use std::{
+    sync::mpsc,
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use super::{
+    midir::{MidirDevice, MidirDeviceManager},
+    MidiInputGateway, MidiOutputConnection, NewMidiInputGateway,
+};
+use crate::{OutputError, OutputResult};
+
+// Controls the polling frequency while the device is connected.
+const CONNECTED_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Poll more frequently while the device is not connected to reconnect it
+// promptly when it becomes available again.
+const DISCONNECTED_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidiConnectionState {
+    Connected,
+    Disconnected,
+}
+
+#[derive(Debug)]
+enum Command {
+    SendOutput(Vec<u8>),
+    Terminate,
+}
+
+/// Sends MIDI output through a [`MidiControllerThread`]
+///
+/// Outputs are sent asynchronously. Outputs that are sent while the
+/// device is disconnected are discarded.
+#[derive(Debug, Clone)]
+pub struct MidiControllerThreadOutput {
+    command_tx: mpsc::Sender<Command>,
+}
+
+impl MidiOutputConnection for MidiControllerThreadOutput {
+    fn send_midi_output(&mut self, output: &[u8]) -> OutputResult<()> {
+        self.command_tx
+            .send(Command::SendOutput(output.to_vec()))
+            .map_err(|_| OutputError::Disconnected)
+    }
+}
+
+/// Dedicated thread for a MIDI controller
+///
+/// Owns the [`MidirDevice`] and connects or disconnects it when it
+/// becomes available or unavailable.
+#[allow(missing_debug_implementations)]
+pub struct MidiControllerThread<I>
+where
+    I: MidiInputGateway + Send + 'static,
+{
+    command_tx: mpsc::Sender<Command>,
+    connection_state_rx: mpsc::Receiver<MidiConnectionState>,
+    join_handle: JoinHandle<MidirDevice<I>>,
+}
+
+impl<I> MidiControllerThread<I>
+where
+    I: MidiInputGateway + Send + 'static,
+{
+    #[must_use]
+    pub fn spawn<F>(
+        device_manager: MidirDeviceManager<I>,
+        device: MidirDevice<I>,
+        new_input_gateway: F,
+    ) -> Self
+    where
+        F: NewMidiInputGateway<MidiInputGateway = I> + Send + 'static,
+    {
+        let (command_tx, command_rx) = mpsc::channel();
+        let (connection_state_tx, connection_state_rx) = mpsc::channel();
+        let join_handle = std::thread::spawn(move || {
+            thread_fn(
+                &device_manager,
+                device,
+                &new_input_gateway,
+                &command_rx,
+                &connection_state_tx,
+            )
+        });
+        log::debug!("Spawned thread: {join_handle:?}");
+        Self {
+            command_tx,
+            connection_state_rx,
+            join_handle,
+        }
+    }
+
+    /// Changes of the connection state
+    ///
+    /// The current state is emitted initially.
+    #[must_use]
+    pub const fn connection_state_rx(&self) -> &mpsc::Receiver<MidiConnectionState> {
+        &self.connection_state_rx
+    }
+
+    /// Handle for sending outputs
+    #[must_use]
+    pub fn output(&self) -> MidiControllerThreadOutput {
+        MidiControllerThreadOutput {
+            command_tx: self.command_tx.clone(),
+        }
+    }
+
+    /// Disconnect the device and terminate the thread
+    ///
+    /// Returns the disconnected device.
+    pub fn terminate_and_join(self) -> anyhow::Result<MidirDevice<I>> {
+        let Self {
+            command_tx,
+            join_handle,
+            ..
+        } = self;
+        if command_tx.send(Command::Terminate).is_err() {
+            log::debug!("Thread has already terminated");
+        }
+        log::debug!("Joining thread: {join_handle:?}");
+        join_handle
+            .join()
+            .map_err(|err| anyhow::anyhow!("MIDI controller thread panicked: {err:?}"))
+    }
+}
+
+fn thread_fn<I, F>(
+    device_manager: &MidirDeviceManager<I>,
+    mut device: MidirDevice<I>,
+    new_input_gateway: &F,
+    command_rx: &mpsc::Receiver<Command>,
+    connection_state_tx: &mpsc::Sender<MidiConnectionState>,
+) -> MidirDevice<I>
+where
+    I: MidiInputGateway + Send,
+    F: NewMidiInputGateway<MidiInputGateway = I>,
+{
+    let device_name = device.descriptor().device.name();
+    let mut output_connection = None;
+    let mut last_connection_state = None;
+    loop {
+        match (device.is_available(device_manager), device.is_connected()) {
+            (true, false) => {
+                log::info!("{device_name}: Connecting");
+                match device.reconnect(Some(new_input_gateway), None) {
+                    Ok(connection) => {
+                        output_connection = Some(connection);
+                    }
+                    Err(err) => {
+                        log::warn!("{device_name}: Failed to connect: {err}");
+                    }
+                }
+            }
+            (false, true) => {
+                log::info!("{device_name}: Disconnecting");
+                output_connection = None;
+                device.disconnect();
+            }
+            (true, true) | (false, false) => (),
+        }
+        let connection_state = if device.is_connected() {
+            MidiConnectionState::Connected
+        } else {
+            MidiConnectionState::Disconnected
+        };
+        if last_connection_state != Some(connection_state) {
+            last_connection_state = Some(connection_state);
+            if connection_state_tx.send(connection_state).is_err() {
+                log::debug!("{device_name}: Connection state receiver has been dropped");
+            }
+        }
+        let poll_interval = match connection_state {
+            MidiConnectionState::Connected => CONNECTED_POLL_INTERVAL,
+            MidiConnectionState::Disconnected => DISCONNECTED_POLL_INTERVAL,
+        };
+        // Handle commands until the next poll cycle is due.
+        let next_poll = Instant::now() + poll_interval;
+        loop {
+            let timeout = next_poll.saturating_duration_since(Instant::now());
+            match command_rx.recv_timeout(timeout) {
+                Ok(Command::SendOutput(output)) => {
+                    let Some(output_connection) = &mut output_connection else {
+                        log::debug!("{device_name}: Discarding output while disconnected");
+                        continue;
+                    };
+                    if let Err(err) = output_connection.send_midi_output(&output) {
+                        log::warn!("{device_name}: Failed to send output: {err}");
+                    }
+                }
+                Ok(Command::Terminate) | Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    log::info!("{device_name}: Terminating");
+                    drop(output_connection);
+                    device.disconnect();
+                    return device;
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+            }
+        }
+    }
+}
@@ -3,24 +3,108 @@
 
 use std::{
     borrow::Cow,
+    fmt,
     ops::{Deref, DerefMut},
+    time::Duration,
 };
 
+use thiserror::Error;
+
 use crate::{
-    ControlInputEvent, ControlInputEventSink, ControlOutputGateway, DeviceDescriptor, OutputResult,
-    PortIndex, TimeStamp,
+    u7_be_to_u14, ControlInputEvent, ControlInputEventSink, ControlOutputGateway, DeviceDescriptor,
+    OutputResult, PadButtonInput, PortIndex, TimeStamp,
 };
 
 #[cfg(feature = "midir")]
 pub(crate) mod midir;
 
+#[cfg(feature = "midir")]
+pub(crate) mod midir_thread;
+
+#[cfg(all(feature = "web-midi", target_family = "wasm"))]
+pub(crate) mod web_midi;
+
+#[cfg(not(target_family = "wasm"))]
+mod rate_limited;
+#[cfg(not(target_family = "wasm"))]
+pub use self::rate_limited::RateLimitedMidiOutput;
+
 const MIDI_OUTPUT_SYSTEM_RESET: &[u8] = &[0xff];
 
+/// How MIDI port names are matched against [`MidiDeviceDescriptor::port_name_prefix`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MidiPortNameMatching {
+    /// The port name starts with the prefix
+    #[default]
+    StartsWith,
+    /// The port name contains the prefix
+    ///
+    /// Tolerates port names that are prefixed with the client name
+    /// or number by the OS, e.g. "2- DDJ-400".
+    Contains,
+}
+
 /// MIDI-related, extended [`DeviceDescriptor`]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MidiDeviceDescriptor {
     pub device: DeviceDescriptor,
     pub port_name_prefix: &'static str,
+    pub port_name_matching: MidiPortNameMatching,
+
+    /// Ignore surrounding whitespace and ASCII case when matching port names
+    ///
+    /// Disabled by default to avoid false positives.
+    pub port_name_lenient: bool,
+}
+
+impl MidiDeviceDescriptor {
+    #[must_use]
+    pub const fn new(device: DeviceDescriptor, port_name_prefix: &'static str) -> Self {
+        Self {
+            device,
+            port_name_prefix,
+            port_name_matching: MidiPortNameMatching::StartsWith,
+            port_name_lenient: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_port_name_matching(
+        mut self,
+        port_name_matching: MidiPortNameMatching,
+    ) -> Self {
+        self.port_name_matching = port_name_matching;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_port_name_lenient(mut self, port_name_lenient: bool) -> Self {
+        self.port_name_lenient = port_name_lenient;
+        self
+    }
+
+    /// Check if a MIDI port belongs to this device
+    #[must_use]
+    pub fn matches_port_name(&self, port_name: &str) -> bool {
+        let Self {
+            port_name_prefix,
+            port_name_matching,
+            port_name_lenient,
+            ..
+        } = self;
+        let (port_name, port_name_prefix) = if *port_name_lenient {
+            (
+                Cow::Owned(port_name.trim().to_ascii_lowercase()),
+                Cow::Owned(port_name_prefix.trim().to_ascii_lowercase()),
+            )
+        } else {
+            (Cow::Borrowed(port_name), Cow::Borrowed(*port_name_prefix))
+        };
+        match port_name_matching {
+            MidiPortNameMatching::StartsWith => port_name.starts_with(port_name_prefix.as_ref()),
+            MidiPortNameMatching::Contains => port_name.contains(port_name_prefix.as_ref()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -29,6 +113,74 @@ pub struct MidiPortDescriptor {
     pub name: Cow<'static, str>,
 }
 
+/// Strategy for selecting one of multiple detected devices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceSelection<'a> {
+    /// The single device that matches the descriptor
+    Descriptor(&'a DeviceDescriptor),
+    /// The device at the given position
+    Index(usize),
+    /// The only device, fails if multiple devices are available
+    Unambiguous,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SelectDeviceError {
+    #[error("no devices available")]
+    NoDevices,
+    #[error("ambiguous selection among {count} devices")]
+    Ambiguous { count: usize },
+    #[error("no matching device found")]
+    NotFound,
+    #[error("device index {index} out of range ({count} devices available)")]
+    IndexOutOfRange { index: usize, count: usize },
+}
+
+/// Select a single device from the detected devices
+///
+/// The selected device is removed from `devices`. All devices are
+/// retained on failure, e.g. to let the user decide if the selection
+/// is ambiguous.
+pub fn select_device<T>(
+    devices: &mut Vec<(MidiDeviceDescriptor, T)>,
+    selection: DeviceSelection<'_>,
+) -> Result<(MidiDeviceDescriptor, T), SelectDeviceError> {
+    let count = devices.len();
+    if count == 0 {
+        return Err(SelectDeviceError::NoDevices);
+    }
+    let index = match selection {
+        DeviceSelection::Descriptor(device) => {
+            let mut matching = devices
+                .iter()
+                .enumerate()
+                .filter(|(_, (descriptor, _))| descriptor.device == *device)
+                .map(|(index, _)| index);
+            let Some(index) = matching.next() else {
+                return Err(SelectDeviceError::NotFound);
+            };
+            let count = 1 + matching.count();
+            if count > 1 {
+                return Err(SelectDeviceError::Ambiguous { count });
+            }
+            index
+        }
+        DeviceSelection::Index(index) => {
+            if index >= count {
+                return Err(SelectDeviceError::IndexOutOfRange { index, count });
+            }
+            index
+        }
+        DeviceSelection::Unambiguous => {
+            if count > 1 {
+                return Err(SelectDeviceError::Ambiguous { count });
+            }
+            0
+        }
+    };
+    Ok(devices.remove(index))
+}
+
 pub trait MidiInputConnector {
     /// Invoked before (re-)connecting the input port.
     fn connect_midi_input_port(
@@ -52,8 +204,27 @@ where
     }
 }
 
-#[derive(Debug)]
-pub struct MidiInputDecodeError;
+/// Reasons for failing to decode a MIDI message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum MidiInputDecodeError {
+    /// Missing, invalid, or unexpected status byte
+    #[error("unknown status byte 0x{0:02x}")]
+    UnknownStatus(u8),
+    #[error("unexpected message length {got} (expected {expected})")]
+    UnexpectedLength { got: usize, expected: usize },
+    /// A data byte that is not in the expected range
+    #[error("data byte 0x{0:02x} out of range")]
+    DataOutOfRange(u8),
+    /// System Exclusive message without the trailing `0xf7`
+    #[error("unterminated system exclusive message")]
+    UnterminatedSysEx,
+    /// Well-formed, but not supported by the decoder
+    #[error("unsupported message")]
+    Unsupported,
+}
+
+const MIDI_STATUS_SYSEX_START: u8 = 0xf0;
+const MIDI_STATUS_SYSEX_END: u8 = 0xf7;
 
 /// Decode and map received MIDI messages into [`ControlInputEvent`]s.
 pub trait MidiInputEventDecoder {
@@ -66,6 +237,20 @@ pub trait MidiInputEventDecoder {
         ts: TimeStamp,
         input: &[u8],
     ) -> Result<Option<ControlInputEvent>, MidiInputDecodeError>;
+
+    /// Decode a System Exclusive message
+    ///
+    /// The `payload` contains the bytes between the leading `0xf0` and
+    /// the trailing `0xf7`.
+    ///
+    /// System Exclusive messages are ignored by default.
+    fn try_decode_sysex(
+        &mut self,
+        _ts: TimeStamp,
+        _payload: &[u8],
+    ) -> Result<Option<ControlInputEvent>, MidiInputDecodeError> {
+        Ok(None)
+    }
 }
 
 impl<F> MidiInputEventDecoder for F
@@ -81,6 +266,148 @@ where
     }
 }
 
+pub type BoxedMidiInputEventDecoder = Box<dyn MidiInputEventDecoder + Send + 'static>;
+
+/// Tries multiple decoders in sequence
+///
+/// Returns the first event that has been decoded successfully. Decoders
+/// that fail are skipped. Fails only if all decoders failed, returning
+/// the error of the last decoder.
+///
+/// Useful if multiple devices share a single port, e.g. a controller and
+/// a separate MIDI foot-switch.
+pub struct ChainedDecoder {
+    decoders: Vec<BoxedMidiInputEventDecoder>,
+}
+
+impl ChainedDecoder {
+    #[must_use]
+    pub const fn new(decoders: Vec<BoxedMidiInputEventDecoder>) -> Self {
+        Self { decoders }
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> Vec<BoxedMidiInputEventDecoder> {
+        let Self { decoders } = self;
+        decoders
+    }
+
+    fn try_decode_with(
+        &mut self,
+        mut decode: impl FnMut(
+            &mut BoxedMidiInputEventDecoder,
+        ) -> Result<Option<ControlInputEvent>, MidiInputDecodeError>,
+    ) -> Result<Option<ControlInputEvent>, MidiInputDecodeError> {
+        let mut result = Err(MidiInputDecodeError::Unsupported);
+        for decoder in &mut self.decoders {
+            match decode(decoder) {
+                Ok(Some(event)) => return Ok(Some(event)),
+                Ok(None) => result = Ok(None),
+                Err(err) => {
+                    // Report the error of the last decoder that failed
+                    if result.is_err() {
+                        result = Err(err);
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+impl fmt::Debug for ChainedDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChainedDecoder")
+            .field("decoders", &self.decoders.len())
+            .finish()
+    }
+}
+
+impl MidiInputEventDecoder for ChainedDecoder {
+    fn try_decode_midi_input_event(
+        &mut self,
+        ts: TimeStamp,
+        input: &[u8],
+    ) -> Result<Option<ControlInputEvent>, MidiInputDecodeError> {
+        self.try_decode_with(|decoder| decoder.try_decode_midi_input_event(ts, input))
+    }
+
+    fn try_decode_sysex(
+        &mut self,
+        ts: TimeStamp,
+        payload: &[u8],
+    ) -> Result<Option<ControlInputEvent>, MidiInputDecodeError> {
+        self.try_decode_with(|decoder| decoder.try_decode_sysex(ts, payload))
+    }
+}
+
+/// Validate the structure of a MIDI message
+///
+/// The message must start with a status byte followed only by data bytes
+/// in the range `0x00..=0x7f`. Channel voice messages must have the exact
+/// length and system real-time messages must consist of a single byte.
+/// System Exclusive messages must be terminated by `0xf7`.
+///
+/// Device decoders could rely on these invariants and don't need to
+/// handle malformed messages individually.
+pub fn validate_midi_message(input: &[u8]) -> Result<(), MidiInputDecodeError> {
+    let Some((&status, data)) = input.split_first() else {
+        return Err(MidiInputDecodeError::UnexpectedLength {
+            got: 0,
+            expected: 1,
+        });
+    };
+    let data = match status {
+        0x00..=0x7f => return Err(MidiInputDecodeError::UnknownStatus(status)),
+        0x80..=0xef => {
+            let expected = 1 + channel_message_data_len(status);
+            if input.len() != expected {
+                return Err(MidiInputDecodeError::UnexpectedLength {
+                    got: input.len(),
+                    expected,
+                });
+            }
+            data
+        }
+        MIDI_STATUS_SYSEX_START => data
+            .strip_suffix(&[MIDI_STATUS_SYSEX_END])
+            .ok_or(MidiInputDecodeError::UnterminatedSysEx)?,
+        0xf1..=0xf7 => data,
+        0xf8..=0xff => {
+            if !data.is_empty() {
+                return Err(MidiInputDecodeError::UnexpectedLength {
+                    got: input.len(),
+                    expected: 1,
+                });
+            }
+            data
+        }
+    };
+    if let Some(&byte) = data.iter().find(|&&byte| byte > 0x7f) {
+        return Err(MidiInputDecodeError::DataOutOfRange(byte));
+    }
+    Ok(())
+}
+
+/// Validate and decode a MIDI message
+///
+/// Malformed messages are rejected by [`validate_midi_message()`] before
+/// they are passed to the decoder. Together with the validation of System
+/// Exclusive messages in [`consume_midi_input_event()`] this is the only
+/// place where messages are validated, i.e. decoders could assume
+/// well-formed messages.
+pub fn validate_and_decode_midi_input_event<D>(
+    decoder: &mut D,
+    ts: TimeStamp,
+    input: &[u8],
+) -> Result<Option<ControlInputEvent>, MidiInputDecodeError>
+where
+    D: MidiInputEventDecoder + ?Sized,
+{
+    validate_midi_message(input)?;
+    decoder.try_decode_midi_input_event(ts, input)
+}
+
 /// Passive callback for sinking MIDI input messages
 pub trait MidiInputHandler {
     /// Invoked for each incoming message.
@@ -111,22 +438,163 @@ where
     D: MidiInputEventDecoder + ?Sized,
     E: ControlInputEventSink + ?Sized,
 {
-    match decoder.try_decode_midi_input_event(ts, input) {
+    let result = match input {
+        [MIDI_STATUS_SYSEX_START, payload @ .., MIDI_STATUS_SYSEX_END] => {
+            validate_midi_message(input).and_then(|()| decoder.try_decode_sysex(ts, payload))
+        }
+        _ => validate_and_decode_midi_input_event(decoder, ts, input),
+    };
+    match result {
         Ok(Some(event)) => {
             event_sink.sink_control_input_events(&[event]);
             true
         }
         Ok(None) => true,
-        Err(MidiInputDecodeError) => {
-            log::warn!("Failed to decode MIDI input: {ts} {input:x?}");
+        Err(err) => {
+            log::warn!("Failed to decode MIDI input: {ts} {input:x?}: {err}");
             false
         }
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+struct Cc14BitHalf {
+    ts: TimeStamp,
+    value: u8,
+}
+
+#[derive(Debug, Clone)]
+struct Cc14BitControl {
+    channel: u8,
+    msb_cc: u8,
+    lsb_cc: u8,
+    msb: Option<Cc14BitHalf>,
+    lsb: Option<Cc14BitHalf>,
+}
+
+/// Assembles 14-bit values from pairs of 7-bit MIDI CC messages.
+///
+/// Each 14-bit control is registered by its MIDI channel and the
+/// control numbers of both the most significant (MSB) and the least
+/// significant (LSB) half. The halves may arrive in any order.
+///
+/// A half that has not been completed within the timeout is considered
+/// stale and discarded.
+#[derive(Debug, Clone)]
+pub struct Cc14BitAccumulator {
+    timeout: Duration,
+    controls: Vec<Cc14BitControl>,
+}
+
+impl Cc14BitAccumulator {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(100);
+
+    #[must_use]
+    pub const fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            controls: Vec::new(),
+        }
+    }
+
+    /// Register a 14-bit control
+    ///
+    /// Registering the same control multiple times has no effect.
+    pub fn register(&mut self, channel: u8, msb_cc: u8, lsb_cc: u8) {
+        debug_assert!(channel <= 0x0f);
+        debug_assert!(msb_cc <= 0x7f);
+        debug_assert!(lsb_cc <= 0x7f);
+        debug_assert_ne!(msb_cc, lsb_cc);
+        if self.controls.iter().any(|control| {
+            control.channel == channel && control.msb_cc == msb_cc && control.lsb_cc == lsb_cc
+        }) {
+            return;
+        }
+        self.controls.push(Cc14BitControl {
+            channel,
+            msb_cc,
+            lsb_cc,
+            msb: None,
+            lsb: None,
+        });
+    }
+
+    /// Check if a control number belongs to a registered 14-bit control
+    #[must_use]
+    pub fn is_registered(&self, channel: u8, cc: u8) -> bool {
+        self.controls.iter().any(|control| {
+            control.channel == channel && (control.msb_cc == cc || control.lsb_cc == cc)
+        })
+    }
+
+    /// Accumulate a 7-bit CC value
+    ///
+    /// Returns the assembled 14-bit value after both halves have arrived
+    /// and `None` otherwise, i.e. if only a single half is available or if
+    /// the control number has not been registered.
+    pub fn try_accumulate(&mut self, ts: TimeStamp, channel: u8, cc: u8, value: u8) -> Option<u16> {
+        let timeout = self.timeout;
+        let control = self.controls.iter_mut().find(|control| {
+            control.channel == channel && (control.msb_cc == cc || control.lsb_cc == cc)
+        })?;
+        let half = Some(Cc14BitHalf {
+            ts,
+            value: value & 0x7f,
+        });
+        if cc == control.msb_cc {
+            control.msb = half;
+        } else {
+            debug_assert_eq!(cc, control.lsb_cc);
+            control.lsb = half;
+        }
+        let is_stale = |half: &Cc14BitHalf| {
+            Duration::from_micros(ts.to_micros().saturating_sub(half.ts.to_micros())) > timeout
+        };
+        if control.msb.as_ref().is_some_and(is_stale) {
+            log::debug!("Discarding stale MSB of 14-bit CC {cc}");
+            control.msb = None;
+        }
+        if control.lsb.as_ref().is_some_and(is_stale) {
+            log::debug!("Discarding stale LSB of 14-bit CC {cc}");
+            control.lsb = None;
+        }
+        let (Some(msb), Some(lsb)) = (control.msb, control.lsb) else {
+            return None;
+        };
+        control.msb = None;
+        control.lsb = None;
+        Some(u7_be_to_u14(msb.value, lsb.value))
+    }
+
+    /// Discard all pending halves
+    pub fn reset(&mut self) {
+        for control in &mut self.controls {
+            control.msb = None;
+            control.lsb = None;
+        }
+    }
+}
+
+impl Default for Cc14BitAccumulator {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TIMEOUT)
+    }
+}
+
 pub trait MidiOutputConnection {
     fn send_midi_output(&mut self, output: &[u8]) -> OutputResult<()>;
 
+    /// Send multiple messages at once
+    ///
+    /// Stops on the first error. Transports could override this method
+    /// to send all messages more efficiently.
+    fn send_midi_outputs(&mut self, messages: &[&[u8]]) -> OutputResult<()> {
+        for output in messages {
+            self.send_midi_output(output)?;
+        }
+        Ok(())
+    }
+
     fn send_midi_system_reset(&mut self) -> OutputResult<()> {
         self.send_midi_output(MIDI_OUTPUT_SYSTEM_RESET)
     }
@@ -141,6 +609,158 @@ where
     fn send_midi_output(&mut self, output: &[u8]) -> OutputResult<()> {
         self.as_mut().send_midi_output(output)
     }
+
+    fn send_midi_outputs(&mut self, messages: &[&[u8]]) -> OutputResult<()> {
+        self.as_mut().send_midi_outputs(messages)
+    }
+}
+
+/// Discards all messages
+///
+/// Only counts the messages, e.g. for assertions in tests.
+#[derive(Debug, Default)]
+pub struct NullMidiOutputConnection {
+    sent: usize,
+}
+
+impl NullMidiOutputConnection {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { sent: 0 }
+    }
+
+    /// The number of messages that have been sent
+    #[must_use]
+    pub const fn sent(&self) -> usize {
+        self.sent
+    }
+}
+
+impl MidiOutputConnection for NullMidiOutputConnection {
+    fn send_midi_output(&mut self, _output: &[u8]) -> OutputResult<()> {
+        self.sent += 1;
+        Ok(())
+    }
+}
+
+/// Captures all messages
+///
+/// For verifying the messages that have been sent in tests.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug, Default)]
+pub struct CaptureMidiOutput {
+    messages: Vec<Vec<u8>>,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl CaptureMidiOutput {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            messages: Vec::new(),
+        }
+    }
+
+    /// All messages in the order they have been sent
+    #[must_use]
+    pub fn messages(&self) -> &[Vec<u8>] {
+        &self.messages
+    }
+
+    /// Take all captured messages
+    pub fn take_messages(&mut self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut self.messages)
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl MidiOutputConnection for CaptureMidiOutput {
+    fn send_midi_output(&mut self, output: &[u8]) -> OutputResult<()> {
+        self.messages.push(output.to_vec());
+        Ok(())
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+type LoopbackMidiQueue = std::sync::Arc<std::sync::Mutex<std::collections::VecDeque<Vec<u8>>>>;
+
+/// In-memory MIDI transport that loops outputs back as inputs
+///
+/// Messages that are sent through a [`LoopbackMidiOutput`] or that are
+/// fed in directly are queued until they are delivered to a
+/// [`MidiInputHandler`]. Allows to test the full stack of gateways
+/// without any hardware.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug, Clone, Default)]
+pub struct LoopbackMidi {
+    queue: LoopbackMidiQueue,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl LoopbackMidi {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock_queue(&self) -> std::sync::MutexGuard<'_, std::collections::VecDeque<Vec<u8>>> {
+        self.queue
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// A connection for sending messages into the loop
+    #[must_use]
+    pub fn output(&self) -> LoopbackMidiOutput {
+        LoopbackMidiOutput {
+            queue: LoopbackMidiQueue::clone(&self.queue),
+        }
+    }
+
+    /// Feed a crafted message into the loop
+    pub fn feed(&self, input: &[u8]) {
+        self.lock_queue().push_back(input.to_vec());
+    }
+
+    /// The number of messages that are waiting to be delivered
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.lock_queue().len()
+    }
+
+    /// Deliver all pending messages with the same time stamp
+    ///
+    /// Returns the number of messages that have been accepted by the handler.
+    pub fn deliver<H>(&self, ts: TimeStamp, handler: &mut H) -> usize
+    where
+        H: MidiInputHandler + ?Sized,
+    {
+        // Don't hold the lock while handling the messages that might
+        // send new messages into the loop.
+        let messages = std::mem::take(&mut *self.lock_queue());
+        messages
+            .iter()
+            .filter(|input| handler.handle_midi_input(ts, input))
+            .count()
+    }
+}
+
+/// Sends messages into a [`LoopbackMidi`]
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug, Clone)]
+pub struct LoopbackMidiOutput {
+    queue: LoopbackMidiQueue,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl MidiOutputConnection for LoopbackMidiOutput {
+    fn send_midi_output(&mut self, output: &[u8]) -> OutputResult<()> {
+        self.queue
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push_back(output.to_vec());
+        Ok(())
+    }
 }
 
 pub trait MidiInputGateway: MidiInputConnector + MidiInputHandler {}
@@ -181,3 +801,886 @@ pub trait MidiOutputGateway<C> {
 pub trait MidiControlOutputGateway<C>: ControlOutputGateway + MidiOutputGateway<C> {}
 
 impl<T, C> MidiControlOutputGateway<C> for T where T: ControlOutputGateway + MidiOutputGateway<C> {}
+
+/// Number of data bytes following a channel voice status byte
+const fn channel_message_data_len(status: u8) -> usize {
+    match status & 0xf0 {
+        // Program change, channel pressure
+        0xc0 | 0xd0 => 1,
+        _ => 2,
+    }
+}
+
+/// Restores omitted status bytes of MIDI messages.
+///
+/// Repeated channel voice messages might be sent with _running status_,
+/// i.e. the status byte is omitted if it equals the status byte of the
+/// preceding message. The reassembler remembers the last status byte
+/// and prepends it to messages that start with a data byte.
+///
+/// System real-time messages are passed through without affecting the
+/// running status. All other system messages, including exclusive messages, are
+/// passed through unmodified and reset the running status.
+#[derive(Debug, Clone, Default)]
+pub struct RunningStatusReassembler {
+    running_status: Option<u8>,
+    message: Vec<u8>,
+}
+
+impl RunningStatusReassembler {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            running_status: None,
+            message: Vec::new(),
+        }
+    }
+
+    /// Forget the running status.
+    pub fn reset(&mut self) {
+        self.running_status = None;
+    }
+
+    /// Split the input into complete messages
+    ///
+    /// Invokes `handle_message` for each complete message.
+    pub fn reassemble(&mut self, mut input: &[u8], mut handle_message: impl FnMut(&[u8])) {
+        while let Some(&first) = input.first() {
+            let (status, data) = match first {
+                0xf8..=0xff => {
+                    // System real-time
+                    handle_message(input);
+                    return;
+                }
+                MIDI_STATUS_SYSEX_START..=0xf7 => {
+                    // System common or exclusive
+                    self.running_status = None;
+                    handle_message(input);
+                    return;
+                }
+                0x80..=0xef => {
+                    self.running_status = Some(first);
+                    (first, &input[1..])
+                }
+                _ => {
+                    let Some(running_status) = self.running_status else {
+                        // Nothing to restore
+                        handle_message(input);
+                        return;
+                    };
+                    (running_status, input)
+                }
+            };
+            let (data, remainder) = data.split_at(channel_message_data_len(status).min(data.len()));
+            self.message.clear();
+            self.message.push(status);
+            self.message.extend_from_slice(data);
+            handle_message(&self.message);
+            input = remainder;
+        }
+    }
+}
+
+const MIDI_STATUS_NOTE_OFF: u8 = 0x80;
+const MIDI_STATUS_NOTE_ON: u8 = 0x90;
+
+/// Normalize note on/off messages
+///
+/// Some controllers send a note off message when a button is released
+/// while others send a note on message with velocity 0. Both forms are
+/// collapsed into the tuple `(pressed, channel, note, velocity)`.
+///
+/// Returns `None` for all other or incomplete messages.
+#[must_use]
+pub fn normalize_note_message(input: &[u8]) -> Option<(bool, u8, u8, u8)> {
+    let (&status, data) = input.split_first()?;
+    let channel = status & 0x0f;
+    match (status & 0xf0, data) {
+        (MIDI_STATUS_NOTE_OFF, &[note, velocity, ..]) => Some((false, channel, note, velocity)),
+        (MIDI_STATUS_NOTE_ON, &[note, velocity, ..]) => {
+            Some((velocity > 0, channel, note, velocity))
+        }
+        _ => None,
+    }
+}
+
+const MIDI_STATUS_POLY_KEY_PRESSURE: u8 = 0xa0;
+const MIDI_STATUS_CHANNEL_PRESSURE: u8 = 0xd0;
+
+/// Pressure updates of pads that are held down
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MidiAftertouch {
+    /// Polyphonic key pressure of a single note
+    PolyKey {
+        channel: u8,
+        note: u8,
+        pressure: PadButtonInput,
+    },
+    /// Channel pressure that affects all notes
+    Channel {
+        channel: u8,
+        pressure: PadButtonInput,
+    },
+}
+
+/// Check if the status byte denotes an aftertouch message
+#[must_use]
+pub const fn is_midi_aftertouch_status(status: u8) -> bool {
+    matches!(
+        status & 0xf0,
+        MIDI_STATUS_POLY_KEY_PRESSURE | MIDI_STATUS_CHANNEL_PRESSURE
+    )
+}
+
+/// Decode a polyphonic key pressure or channel pressure message
+///
+/// Channel pressure messages don't carry a note. They are returned as
+/// [`MidiAftertouch::Channel`] and the decoder needs to apply the
+/// pressure to all pads of the channel that are currently held down.
+///
+/// Returns `None` for all other or incomplete messages.
+#[must_use]
+pub fn decode_midi_aftertouch(input: &[u8]) -> Option<MidiAftertouch> {
+    let (&status, data) = input.split_first()?;
+    let channel = status & 0x0f;
+    match (status & 0xf0, data) {
+        (MIDI_STATUS_POLY_KEY_PRESSURE, &[note, pressure, ..]) => Some(MidiAftertouch::PolyKey {
+            channel,
+            note,
+            pressure: PadButtonInput::from_u7(pressure),
+        }),
+        (MIDI_STATUS_CHANNEL_PRESSURE, &[pressure, ..]) => Some(MidiAftertouch::Channel {
+            channel,
+            pressure: PadButtonInput::from_u7(pressure),
+        }),
+        _ => None,
+    }
+}
+
+/// A parsed MIDI message for debugging purposes
+///
+/// Channels are displayed 1-based, i.e. like they are usually
+/// presented to users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidiMessage<'a> {
+    NoteOff {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    NoteOn {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    PolyKeyPressure {
+        channel: u8,
+        note: u8,
+        pressure: u8,
+    },
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+    ProgramChange {
+        channel: u8,
+        program: u8,
+    },
+    ChannelPressure {
+        channel: u8,
+        pressure: u8,
+    },
+    PitchBend {
+        channel: u8,
+        value: u16,
+    },
+    /// System Exclusive message with the payload between `0xf0` and `0xf7`
+    SysEx(&'a [u8]),
+    /// System common or real-time message
+    System {
+        status: u8,
+        data: &'a [u8],
+    },
+}
+
+impl<'a> MidiMessage<'a> {
+    /// Parse a single MIDI message
+    ///
+    /// Returns `None` if the message is invalid, see [`validate_midi_message()`].
+    #[must_use]
+    pub fn parse(input: &'a [u8]) -> Option<Self> {
+        validate_midi_message(input).ok()?;
+        let (&status, data) = input.split_first()?;
+        let channel = status & 0x0f;
+        let message = match (status & 0xf0, data) {
+            (MIDI_STATUS_NOTE_OFF, &[note, velocity]) => Self::NoteOff {
+                channel,
+                note,
+                velocity,
+            },
+            (MIDI_STATUS_NOTE_ON, &[note, velocity]) => Self::NoteOn {
+                channel,
+                note,
+                velocity,
+            },
+            (MIDI_STATUS_POLY_KEY_PRESSURE, &[note, pressure]) => Self::PolyKeyPressure {
+                channel,
+                note,
+                pressure,
+            },
+            (0xb0, &[controller, value]) => Self::ControlChange {
+                channel,
+                controller,
+                value,
+            },
+            (0xc0, &[program]) => Self::ProgramChange { channel, program },
+            (MIDI_STATUS_CHANNEL_PRESSURE, &[pressure]) => {
+                Self::ChannelPressure { channel, pressure }
+            }
+            (0xe0, &[lsb, msb]) => Self::PitchBend {
+                channel,
+                value: u7_be_to_u14(msb, lsb),
+            },
+            _ if status == MIDI_STATUS_SYSEX_START => {
+                Self::SysEx(data.strip_suffix(&[MIDI_STATUS_SYSEX_END]).unwrap_or(data))
+            }
+            _ => Self::System { status, data },
+        };
+        Some(message)
+    }
+}
+
+impl fmt::Display for MidiMessage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::NoteOff {
+                channel,
+                note,
+                velocity,
+            } => write!(
+                f,
+                "NoteOff ch{} note0x{note:02x} vel{velocity}",
+                channel + 1
+            ),
+            Self::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => write!(f, "NoteOn ch{} note0x{note:02x} vel{velocity}", channel + 1),
+            Self::PolyKeyPressure {
+                channel,
+                note,
+                pressure,
+            } => write!(
+                f,
+                "PolyKeyPressure ch{} note0x{note:02x} pressure{pressure}",
+                channel + 1
+            ),
+            Self::ControlChange {
+                channel,
+                controller,
+                value,
+            } => write!(
+                f,
+                "ControlChange ch{} cc0x{controller:02x} value{value}",
+                channel + 1
+            ),
+            Self::ProgramChange { channel, program } => {
+                write!(f, "ProgramChange ch{} program{program}", channel + 1)
+            }
+            Self::ChannelPressure { channel, pressure } => {
+                write!(f, "ChannelPressure ch{} pressure{pressure}", channel + 1)
+            }
+            Self::PitchBend { channel, value } => {
+                write!(f, "PitchBend ch{} value{value}", channel + 1)
+            }
+            Self::SysEx(payload) => write!(f, "SysEx {payload:02x?}"),
+            Self::System { status, data } => write!(f, "System 0x{status:02x} {data:02x?}"),
+        }
+    }
+}
+
+/// Logs all incoming MIDI messages
+///
+/// Useful for reverse engineering the MIDI mapping of new devices.
+#[derive(Debug, Clone, Default)]
+pub struct LoggingMidiInputHandler {
+    input_port: Option<MidiPortDescriptor>,
+}
+
+impl MidiInputConnector for LoggingMidiInputHandler {
+    fn connect_midi_input_port(
+        &mut self,
+        _device: &MidiDeviceDescriptor,
+        input_port: &MidiPortDescriptor,
+    ) {
+        self.input_port = Some(input_port.clone());
+    }
+}
+
+impl MidiInputHandler for LoggingMidiInputHandler {
+    fn handle_midi_input(&mut self, ts: TimeStamp, input: &[u8]) -> bool {
+        let port_name = self
+            .input_port
+            .as_ref()
+            .map_or("", |input_port| input_port.name.as_ref());
+        let Some(message) = MidiMessage::parse(input) else {
+            log::warn!("{port_name}: {ts} Invalid MIDI message {input:02x?}");
+            return false;
+        };
+        log::info!("{port_name}: {ts} {message}");
+        true
+    }
+}
+
+const MIDI_STATUS_CLOCK: u8 = 0xf8;
+const MIDI_STATUS_START: u8 = 0xfa;
+const MIDI_STATUS_CONTINUE: u8 = 0xfb;
+const MIDI_STATUS_STOP: u8 = 0xfc;
+
+/// Follows an external MIDI clock.
+///
+/// Estimates the tempo from the time stamps of the received timing
+/// clock messages and tracks the position within the current beat.
+#[derive(Debug, Clone)]
+pub struct MidiClock {
+    smoothing: f32,
+    running: bool,
+    last_clock_ts: Option<TimeStamp>,
+    tick_index: Option<u8>,
+    bpm: Option<f32>,
+}
+
+impl MidiClock {
+    /// Pulses (clock messages) per quarter note
+    pub const PULSES_PER_BEAT: u8 = 24;
+
+    /// Weight of the previous BPM estimate when smoothing
+    pub const DEFAULT_SMOOTHING: f32 = 0.9;
+
+    /// Maximum time span between two subsequent clock messages
+    ///
+    /// Longer intervals, i.e. tempos below 10 BPM, are considered
+    /// as gaps and are not used for estimating the tempo.
+    pub const MAX_CLOCK_INTERVAL: Duration = Duration::from_millis(250);
+
+    /// Create a new clock
+    ///
+    /// The `smoothing` coefficient in the interval [0, 1) controls the
+    /// weight of the previous BPM estimate. A value of 0.0 disables smoothing.
+    #[must_use]
+    pub fn new(smoothing: f32) -> Self {
+        debug_assert!((0.0..1.0).contains(&smoothing));
+        Self {
+            smoothing,
+            running: false,
+            last_clock_ts: None,
+            tick_index: None,
+            bpm: None,
+        }
+    }
+
+    /// Estimated tempo in beats per minute
+    ///
+    /// Returns `None` until at least two clock messages have been received.
+    #[must_use]
+    pub const fn bpm(&self) -> Option<f32> {
+        self.bpm
+    }
+
+    /// Whether the clock has been started or continued and not stopped
+    #[must_use]
+    pub const fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Position within the current beat in the interval [0, 1)
+    #[must_use]
+    pub fn beat_phase(&self) -> f32 {
+        f32::from(self.tick_index.unwrap_or(0)) / f32::from(Self::PULSES_PER_BEAT)
+    }
+
+    /// Forget all state
+    pub fn reset(&mut self) {
+        *self = Self::new(self.smoothing);
+    }
+
+    /// Handle a timing clock message
+    pub fn handle_clock(&mut self, ts: TimeStamp) {
+        if let Some(last_clock_ts) = self.last_clock_ts {
+            let interval =
+                Duration::from_micros(ts.to_micros().saturating_sub(last_clock_ts.to_micros()));
+            if !interval.is_zero() && interval <= Self::MAX_CLOCK_INTERVAL {
+                let bpm = 60.0 / (interval.as_secs_f32() * f32::from(Self::PULSES_PER_BEAT));
+                self.bpm = Some(self.bpm.map_or(bpm, |last_bpm| {
+                    self.smoothing * last_bpm + (1.0 - self.smoothing) * bpm
+                }));
+            }
+        }
+        self.last_clock_ts = Some(ts);
+        self.tick_index = Some(
+            self.tick_index
+                .map_or(0, |tick_index| (tick_index + 1) % Self::PULSES_PER_BEAT),
+        );
+    }
+
+    /// Handle a start message
+    ///
+    /// The next clock message marks the beginning of the first beat.
+    pub fn handle_start(&mut self) {
+        self.running = true;
+        self.tick_index = None;
+    }
+
+    /// Handle a continue message
+    pub fn handle_continue(&mut self) {
+        self.running = true;
+    }
+
+    /// Handle a stop message
+    pub fn handle_stop(&mut self) {
+        self.running = false;
+    }
+
+    /// Handle a MIDI message
+    ///
+    /// Returns `true` if the message is a clock-related real-time
+    /// message or `false` otherwise.
+    pub fn handle_midi_input(&mut self, ts: TimeStamp, input: &[u8]) -> bool {
+        match input {
+            [MIDI_STATUS_CLOCK] => self.handle_clock(ts),
+            [MIDI_STATUS_START] => self.handle_start(),
+            [MIDI_STATUS_CONTINUE] => self.handle_continue(),
+            [MIDI_STATUS_STOP] => self.handle_stop(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Default for MidiClock {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SMOOTHING)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_device_strategies() {
+        const fn device(product_name: &'static str) -> MidiDeviceDescriptor {
+            MidiDeviceDescriptor::new(
+                DeviceDescriptor {
+                    vendor_name: Cow::Borrowed("Vendor"),
+                    product_name: Cow::Borrowed(product_name),
+                    audio_interface: None,
+                },
+                product_name,
+            )
+        }
+        let a = device("A");
+        let b = device("B");
+
+        let mut devices = Vec::<(MidiDeviceDescriptor, ())>::new();
+        assert_eq!(
+            Err(SelectDeviceError::NoDevices),
+            select_device(&mut devices, DeviceSelection::Unambiguous)
+        );
+
+        devices = vec![(a.clone(), ()), (b.clone(), ()), (a.clone(), ())];
+        assert_eq!(
+            Err(SelectDeviceError::Ambiguous { count: 3 }),
+            select_device(&mut devices, DeviceSelection::Unambiguous)
+        );
+        assert_eq!(
+            Err(SelectDeviceError::Ambiguous { count: 2 }),
+            select_device(&mut devices, DeviceSelection::Descriptor(&a.device))
+        );
+        assert_eq!(
+            Err(SelectDeviceError::NotFound),
+            select_device(
+                &mut devices,
+                DeviceSelection::Descriptor(&device("C").device)
+            )
+        );
+        assert_eq!(
+            Err(SelectDeviceError::IndexOutOfRange { index: 3, count: 3 }),
+            select_device(&mut devices, DeviceSelection::Index(3))
+        );
+        assert_eq!(3, devices.len());
+
+        assert_eq!(
+            Ok((b.clone(), ())),
+            select_device(&mut devices, DeviceSelection::Descriptor(&b.device))
+        );
+        assert_eq!(
+            Ok((a.clone(), ())),
+            select_device(&mut devices, DeviceSelection::Index(1))
+        );
+        assert_eq!(
+            Ok((a, ())),
+            select_device(&mut devices, DeviceSelection::Unambiguous)
+        );
+        assert!(devices.is_empty());
+    }
+
+    #[test]
+    fn chained_decoder() {
+        let event = ControlInputEvent {
+            ts: TimeStamp::from_micros(0),
+            input: crate::Control {
+                index: crate::ControlIndex::new(1),
+                value: crate::ControlValue::from_bits(1),
+            },
+        };
+        let failing: BoxedMidiInputEventDecoder =
+            Box::new(|_, _: &[u8]| Err(MidiInputDecodeError::Unsupported));
+        let ignoring: BoxedMidiInputEventDecoder = Box::new(|_, _: &[u8]| Ok(None));
+        let decoding: BoxedMidiInputEventDecoder = {
+            let event = event.clone();
+            Box::new(move |_, _: &[u8]| Ok(Some(event.clone())))
+        };
+        let ts = TimeStamp::from_micros(0);
+        let input = [0x90, 0x00, 0x7f];
+        let mut decoder = ChainedDecoder::new(vec![failing, ignoring, decoding]);
+        assert_eq!(
+            Some(event),
+            decoder.try_decode_midi_input_event(ts, &input).unwrap()
+        );
+        let mut decoders = decoder.into_inner();
+        decoders.pop();
+        let mut decoder = ChainedDecoder::new(decoders);
+        assert_eq!(
+            None,
+            decoder.try_decode_midi_input_event(ts, &input).unwrap()
+        );
+        let mut decoders = decoder.into_inner();
+        decoders.pop();
+        let mut decoder = ChainedDecoder::new(decoders);
+        assert!(decoder.try_decode_midi_input_event(ts, &input).is_err());
+    }
+
+    #[test]
+    fn consume_sysex_input() {
+        #[derive(Default)]
+        struct RecordingDecoder {
+            messages: Vec<Vec<u8>>,
+            sysex_payloads: Vec<Vec<u8>>,
+        }
+
+        impl MidiInputEventDecoder for RecordingDecoder {
+            fn try_decode_midi_input_event(
+                &mut self,
+                _ts: TimeStamp,
+                input: &[u8],
+            ) -> Result<Option<ControlInputEvent>, MidiInputDecodeError> {
+                self.messages.push(input.to_vec());
+                Ok(None)
+            }
+
+            fn try_decode_sysex(
+                &mut self,
+                _ts: TimeStamp,
+                payload: &[u8],
+            ) -> Result<Option<ControlInputEvent>, MidiInputDecodeError> {
+                self.sysex_payloads.push(payload.to_vec());
+                Ok(None)
+            }
+        }
+
+        struct NoEvents;
+
+        impl ControlInputEventSink for NoEvents {
+            fn sink_control_input_events(&mut self, events: &[ControlInputEvent]) {
+                unreachable!("unexpected events: {events:?}");
+            }
+        }
+
+        let ts = TimeStamp::from_micros(0);
+        let mut decoder = RecordingDecoder::default();
+        assert!(consume_midi_input_event(
+            ts,
+            &[0xf0, 0x42, 0x40, 0xf7],
+            &mut decoder,
+            &mut NoEvents
+        ));
+        // Unterminated
+        assert!(!consume_midi_input_event(
+            ts,
+            &[0xf0, 0x42, 0x41],
+            &mut decoder,
+            &mut NoEvents
+        ));
+        // Invalid data byte
+        assert!(!consume_midi_input_event(
+            ts,
+            &[0xf0, 0x42, 0x90, 0xf7],
+            &mut decoder,
+            &mut NoEvents
+        ));
+        assert!(consume_midi_input_event(
+            ts,
+            &[0x90, 0x00, 0x7f],
+            &mut decoder,
+            &mut NoEvents
+        ));
+        assert_eq!(vec![vec![0x42, 0x40]], decoder.sysex_payloads);
+        assert_eq!(vec![vec![0x90, 0x00, 0x7f]], decoder.messages);
+    }
+
+    #[test]
+    fn cc_14bit_accumulator_in_order() {
+        let mut acc = Cc14BitAccumulator::default();
+        acc.register(0, 0x00, 0x20);
+        let ts = TimeStamp::from_micros(0);
+        assert_eq!(None, acc.try_accumulate(ts, 0, 0x00, 0x7f));
+        assert_eq!(Some(0x3fff), acc.try_accumulate(ts, 0, 0x20, 0x7f));
+        // Both halves are needed again
+        assert_eq!(None, acc.try_accumulate(ts, 0, 0x20, 0x00));
+    }
+
+    #[test]
+    fn cc_14bit_accumulator_out_of_order() {
+        let mut acc = Cc14BitAccumulator::default();
+        acc.register(1, 0x00, 0x20);
+        let ts = TimeStamp::from_micros(0);
+        assert_eq!(None, acc.try_accumulate(ts, 1, 0x20, 0x01));
+        assert_eq!(Some(0x0081), acc.try_accumulate(ts, 1, 0x00, 0x01));
+    }
+
+    #[test]
+    fn cc_14bit_accumulator_unregistered() {
+        let mut acc = Cc14BitAccumulator::default();
+        acc.register(0, 0x00, 0x20);
+        let ts = TimeStamp::from_micros(0);
+        assert_eq!(None, acc.try_accumulate(ts, 1, 0x00, 0x7f));
+        assert_eq!(None, acc.try_accumulate(ts, 1, 0x20, 0x7f));
+    }
+
+    #[test]
+    fn cc_14bit_accumulator_discards_stale_halves() {
+        let mut acc = Cc14BitAccumulator::new(Duration::from_millis(10));
+        acc.register(0, 0x00, 0x20);
+        assert_eq!(
+            None,
+            acc.try_accumulate(TimeStamp::from_micros(0), 0, 0x00, 0x7f)
+        );
+        assert_eq!(
+            None,
+            acc.try_accumulate(TimeStamp::from_micros(10_001), 0, 0x20, 0x7f)
+        );
+        assert_eq!(
+            Some(0x007f),
+            acc.try_accumulate(TimeStamp::from_micros(10_002), 0, 0x00, 0x00)
+        );
+    }
+
+    fn reassemble_all(reassembler: &mut RunningStatusReassembler, input: &[u8]) -> Vec<Vec<u8>> {
+        let mut messages = Vec::new();
+        reassembler.reassemble(input, |message| messages.push(message.to_vec()));
+        messages
+    }
+
+    #[test]
+    fn running_status_reassembler() {
+        let mut reassembler = RunningStatusReassembler::new();
+        assert_eq!(
+            vec![vec![0x90, 0x3c, 0x7f]],
+            reassemble_all(&mut reassembler, &[0x90, 0x3c, 0x7f])
+        );
+        assert_eq!(
+            vec![vec![0x90, 0x3d, 0x00]],
+            reassemble_all(&mut reassembler, &[0x3d, 0x00])
+        );
+        // Multiple messages in a single input
+        assert_eq!(
+            vec![vec![0xc1, 0x05], vec![0xc1, 0x06]],
+            reassemble_all(&mut reassembler, &[0xc1, 0x05, 0x06])
+        );
+        // Real-time messages don't affect the running status
+        assert_eq!(vec![vec![0xf8]], reassemble_all(&mut reassembler, &[0xf8]));
+        assert_eq!(
+            vec![vec![0xc1, 0x07]],
+            reassemble_all(&mut reassembler, &[0x07])
+        );
+    }
+
+    #[test]
+    fn running_status_reassembler_sysex() {
+        let mut reassembler = RunningStatusReassembler::new();
+        let sysex = [0xf0, 0x42, 0x40, 0x00, 0xf7];
+        assert_eq!(
+            vec![vec![0xb0, 0x01, 0x02]],
+            reassemble_all(&mut reassembler, &[0xb0, 0x01, 0x02])
+        );
+        assert_eq!(
+            vec![sysex.to_vec()],
+            reassemble_all(&mut reassembler, &sysex)
+        );
+        // The running status has been reset
+        assert_eq!(
+            vec![vec![0x01, 0x02]],
+            reassemble_all(&mut reassembler, &[0x01, 0x02])
+        );
+    }
+
+    #[test]
+    fn midi_clock_bpm_and_phase() {
+        let mut clock = MidiClock::new(0.0);
+        assert!(clock.handle_midi_input(TimeStamp::from_micros(0), &[MIDI_STATUS_START]));
+        assert!(clock.is_running());
+        assert_eq!(None, clock.bpm());
+        // 120 BPM = 24 pulses per 500 ms
+        let interval_micros = 500_000 / u64::from(MidiClock::PULSES_PER_BEAT);
+        for i in 0..=u64::from(MidiClock::PULSES_PER_BEAT) / 2 {
+            assert!(clock.handle_midi_input(
+                TimeStamp::from_micros(i * interval_micros),
+                &[MIDI_STATUS_CLOCK]
+            ));
+        }
+        let bpm = clock.bpm().unwrap();
+        assert!((bpm - 120.0).abs() < 0.1, "{bpm}");
+        assert!((clock.beat_phase() - 0.5).abs() < f32::EPSILON);
+        assert!(clock.handle_midi_input(TimeStamp::from_micros(0), &[MIDI_STATUS_STOP]));
+        assert!(!clock.is_running());
+        assert!(!clock.handle_midi_input(TimeStamp::from_micros(0), &[0x90, 0x00, 0x7f]));
+    }
+
+    #[test]
+    fn match_port_name() {
+        let device = DeviceDescriptor {
+            vendor_name: "Pioneer".into(),
+            product_name: "DDJ-400".into(),
+            audio_interface: None,
+        };
+        let descriptor = MidiDeviceDescriptor::new(device, "DDJ-400");
+        assert!(descriptor.matches_port_name("DDJ-400 MIDI 1"));
+        assert!(!descriptor.matches_port_name("2- DDJ-400 MIDI 1"));
+        let descriptor = descriptor.with_port_name_matching(MidiPortNameMatching::Contains);
+        assert!(descriptor.matches_port_name("DDJ-400 MIDI 1"));
+        assert!(descriptor.matches_port_name("2- DDJ-400 MIDI 1"));
+        assert!(!descriptor.matches_port_name("DDJ-SB3 MIDI 1"));
+    }
+
+    #[test]
+    fn match_port_name_lenient() {
+        let device = DeviceDescriptor {
+            vendor_name: "Pioneer".into(),
+            product_name: "DDJ-400".into(),
+            audio_interface: None,
+        };
+        let descriptor = MidiDeviceDescriptor::new(device, "DDJ-400");
+        assert!(descriptor.matches_port_name("DDJ-400 MIDI 1"));
+        assert!(!descriptor.matches_port_name(" DDJ-400"));
+        assert!(!descriptor.matches_port_name("ddj-400"));
+        let descriptor = descriptor.with_port_name_lenient(true);
+        assert!(descriptor.matches_port_name("DDJ-400 MIDI 1"));
+        assert!(descriptor.matches_port_name(" DDJ-400"));
+        assert!(descriptor.matches_port_name("ddj-400"));
+        assert!(!descriptor.matches_port_name("ddj-sb3"));
+    }
+
+    #[test]
+    fn display_midi_message() {
+        let message = MidiMessage::parse(&[0x96, 0x0b, 0x7f]).unwrap();
+        assert_eq!("NoteOn ch7 note0x0b vel127", message.to_string());
+        let message = MidiMessage::parse(&[0xe0, 0x00, 0x40]).unwrap();
+        assert_eq!(
+            MidiMessage::PitchBend {
+                channel: 0,
+                value: 0x2000
+            },
+            message
+        );
+        assert_eq!(
+            Some(MidiMessage::SysEx(&[0x42, 0x40])),
+            MidiMessage::parse(&[0xf0, 0x42, 0x40, 0xf7])
+        );
+        assert_eq!(None, MidiMessage::parse(&[0x96, 0x0b]));
+    }
+
+    #[test]
+    fn validate_message() {
+        assert!(validate_midi_message(&[0x90, 0x0b, 0x7f]).is_ok());
+        assert!(validate_midi_message(&[0xd0, 0x7f]).is_ok());
+        assert!(validate_midi_message(&[0xf8]).is_ok());
+        assert!(validate_midi_message(&[0xf0, 0x42, 0x40, 0xf7]).is_ok());
+        assert_eq!(
+            Err(MidiInputDecodeError::UnexpectedLength {
+                got: 0,
+                expected: 1
+            }),
+            validate_midi_message(&[])
+        );
+        // Missing status byte
+        assert_eq!(
+            Err(MidiInputDecodeError::UnknownStatus(0x0b)),
+            validate_midi_message(&[0x0b, 0x7f])
+        );
+        // Invalid length
+        assert_eq!(
+            Err(MidiInputDecodeError::UnexpectedLength {
+                got: 2,
+                expected: 3
+            }),
+            validate_midi_message(&[0x90, 0x0b])
+        );
+        assert!(validate_midi_message(&[0x90, 0x0b, 0x7f, 0x00]).is_err());
+        assert!(validate_midi_message(&[0xf8, 0x00]).is_err());
+        // Invalid data byte
+        assert_eq!(
+            Err(MidiInputDecodeError::DataOutOfRange(0x80)),
+            validate_midi_message(&[0x90, 0x0b, 0x80])
+        );
+        assert!(validate_midi_message(&[0xf0, 0x90, 0xf7]).is_err());
+        assert_eq!(
+            Err(MidiInputDecodeError::UnterminatedSysEx),
+            validate_midi_message(&[0xf0, 0x42, 0x40])
+        );
+    }
+
+    #[test]
+    fn normalize_note_off() {
+        assert_eq!(
+            Some((false, 1, 0x0b, 0x40)),
+            normalize_note_message(&[0x81, 0x0b, 0x40])
+        );
+        assert_eq!(
+            Some((false, 1, 0x0b, 0x00)),
+            normalize_note_message(&[0x91, 0x0b, 0x00])
+        );
+        assert_eq!(
+            Some((true, 1, 0x0b, 0x40)),
+            normalize_note_message(&[0x91, 0x0b, 0x40])
+        );
+        assert_eq!(None, normalize_note_message(&[0x91, 0x0b]));
+        assert_eq!(None, normalize_note_message(&[0xb1, 0x0b, 0x40]));
+    }
+
+    #[test]
+    fn decode_aftertouch() {
+        assert!(is_midi_aftertouch_status(0xa3));
+        assert!(is_midi_aftertouch_status(0xd0));
+        assert!(!is_midi_aftertouch_status(0x90));
+        assert_eq!(
+            Some(MidiAftertouch::PolyKey {
+                channel: 3,
+                note: 0x24,
+                pressure: PadButtonInput::from_u7(0x7f),
+            }),
+            decode_midi_aftertouch(&[0xa3, 0x24, 0x7f])
+        );
+        assert_eq!(
+            Some(MidiAftertouch::Channel {
+                channel: 0,
+                pressure: PadButtonInput::from_u7(0x40),
+            }),
+            decode_midi_aftertouch(&[0xd0, 0x40])
+        );
+        assert_eq!(None, decode_midi_aftertouch(&[0xa3, 0x24]));
+        assert_eq!(None, decode_midi_aftertouch(&[0x90, 0x24, 0x7f]));
+    }
+}
@@ -0,0 +1,153 @@
+// SPDX-FileCopyrightText: The djio authors
+// SPDX-License-Identifier: MPL-2.0
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use super::MidiOutputConnection;
+use crate::{OutputError, OutputResult};
+
+/// Enforces a minimum gap between subsequent MIDI messages
+///
+/// Messages that could not be sent immediately are queued and sent
+/// by subsequent invocations or by [`Self::drain()`] and [`Self::flush()`].
+/// Fails with [`OutputError::QueueFull`] instead of blocking if the
+/// queue is exhausted.
+///
+/// Not available on wasm targets that lack a system clock.
+#[derive(Debug)]
+pub struct RateLimitedMidiOutput<C> {
+    connection: C,
+    min_gap: Duration,
+    max_queue_len: usize,
+    queue: VecDeque<Vec<u8>>,
+    last_sent_at: Option<Instant>,
+}
+
+impl<C> RateLimitedMidiOutput<C>
+where
+    C: MidiOutputConnection,
+{
+    #[must_use]
+    pub const fn new(connection: C, min_gap: Duration, max_queue_len: usize) -> Self {
+        Self {
+            connection,
+            min_gap,
+            max_queue_len,
+            queue: VecDeque::new(),
+            last_sent_at: None,
+        }
+    }
+
+    /// Number of messages that are waiting to be sent
+    #[must_use]
+    pub fn queue_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Discards all pending messages
+    #[must_use]
+    pub fn into_inner(self) -> C {
+        let Self { connection, .. } = self;
+        connection
+    }
+
+    fn next_send_at(&self) -> Option<Instant> {
+        self.last_sent_at
+            .map(|last_sent_at| last_sent_at + self.min_gap)
+    }
+
+    fn send_now(&mut self, output: &[u8], now: Instant) -> OutputResult<()> {
+        self.connection.send_midi_output(output)?;
+        self.last_sent_at = Some(now);
+        Ok(())
+    }
+
+    /// Send pending messages without blocking
+    ///
+    /// Returns the number of messages that have been sent.
+    pub fn drain(&mut self) -> OutputResult<usize> {
+        let mut sent = 0;
+        while let Some(output) = self.queue.pop_front() {
+            let now = Instant::now();
+            if self
+                .next_send_at()
+                .is_some_and(|next_send_at| next_send_at > now)
+            {
+                self.queue.push_front(output);
+                break;
+            }
+            if let Err(err) = self.send_now(&output, now) {
+                // Retry later
+                self.queue.push_front(output);
+                return Err(err);
+            }
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    /// Send all pending messages
+    ///
+    /// Blocks the current thread until all messages have been sent.
+    pub fn flush(&mut self) -> OutputResult<()> {
+        loop {
+            self.drain()?;
+            let Some(next_send_at) = self.next_send_at().filter(|_| !self.queue.is_empty()) else {
+                return Ok(());
+            };
+            std::thread::sleep(next_send_at.saturating_duration_since(Instant::now()));
+        }
+    }
+}
+
+impl<C> MidiOutputConnection for RateLimitedMidiOutput<C>
+where
+    C: MidiOutputConnection,
+{
+    fn send_midi_output(&mut self, output: &[u8]) -> OutputResult<()> {
+        self.drain()?;
+        let now = Instant::now();
+        if self.queue.is_empty()
+            && self
+                .next_send_at()
+                .is_none_or(|next_send_at| next_send_at <= now)
+        {
+            return self.send_now(output, now);
+        }
+        if self.queue.len() >= self.max_queue_len {
+            return Err(OutputError::QueueFull);
+        }
+        self.queue.push_back(output.to_vec());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RateLimitedMidiOutput;
+    use crate::{
+        midi::{CaptureMidiOutput, MidiOutputConnection as _},
+        OutputError,
+    };
+
+    #[test]
+    fn rate_limited_output() {
+        let mut output =
+            RateLimitedMidiOutput::new(CaptureMidiOutput::default(), Duration::from_secs(60), 1);
+        output.send_midi_output(&[0x90, 0x00, 0x7f]).unwrap();
+        output.send_midi_output(&[0x90, 0x01, 0x7f]).unwrap();
+        assert_eq!(1, output.queue_len());
+        assert!(matches!(
+            output.send_midi_output(&[0x90, 0x02, 0x7f]),
+            Err(OutputError::QueueFull)
+        ));
+        assert_eq!(0, output.drain().unwrap());
+        let capture = output.into_inner();
+        assert_eq!(&[vec![0x90, 0x00, 0x7f]], capture.messages());
+    }
+}
@@ -0,0 +1,186 @@
+// SPDX-FileCopyrightText: The djio authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! MIDI transport for browsers based on the [Web MIDI API](https://www.w3.org/TR/webmidi/)
+//!
+//! Requesting access through `navigator.requestMIDIAccess()` is
+//! asynchronous and left to the application.
+
+use std::{cell::RefCell, rc::Rc};
+
+use js_sys::{Map, Uint8Array};
+use wasm_bindgen::{closure::Closure, JsCast as _, JsValue};
+use web_sys::{MidiAccess, MidiInput, MidiMessageEvent, MidiOutput, MidiPort};
+
+use super::{MidiDeviceDescriptor, MidiInputGateway, MidiPortDescriptor, NewMidiInputGateway};
+use crate::{
+    MidiInputHandler, OutputError, OutputResult, PortIndexGenerator, RunningStatusReassembler,
+    TimeStamp,
+};
+
+#[allow(missing_debug_implementations)]
+pub struct WebMidiInputPort {
+    pub descriptor: MidiPortDescriptor,
+    pub port: MidiInput,
+}
+
+#[allow(missing_debug_implementations)]
+pub struct WebMidiOutputPort {
+    pub descriptor: MidiPortDescriptor,
+    pub port: MidiOutput,
+}
+
+/// All ports of a port map whose names match the device
+fn filter_ports_by_name<P>(port_map: &JsValue, device: &MidiDeviceDescriptor) -> Vec<P>
+where
+    P: AsRef<MidiPort> + wasm_bindgen::JsCast,
+{
+    port_map
+        .unchecked_ref::<Map>()
+        .values()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter_map(|port| port.dyn_into::<P>().ok())
+        .filter(|port| {
+            AsRef::<MidiPort>::as_ref(port)
+                .name()
+                .is_some_and(|name| device.matches_port_name(&name))
+        })
+        .collect()
+}
+
+fn port_descriptor(
+    port: &MidiPort,
+    port_index_generator: &PortIndexGenerator,
+) -> MidiPortDescriptor {
+    MidiPortDescriptor {
+        index: port_index_generator.next(),
+        name: port.name().unwrap_or_default().into(),
+    }
+}
+
+/// Find the first pair of input and output ports of a device
+#[must_use]
+pub fn find_web_midi_ports(
+    access: &MidiAccess,
+    device: &MidiDeviceDescriptor,
+    port_index_generator: &PortIndexGenerator,
+) -> Option<(WebMidiInputPort, WebMidiOutputPort)> {
+    let input = filter_ports_by_name::<MidiInput>(&access.inputs(), device)
+        .into_iter()
+        .next()?;
+    let output = filter_ports_by_name::<MidiOutput>(&access.outputs(), device)
+        .into_iter()
+        .next()?;
+    let input_port = WebMidiInputPort {
+        descriptor: port_descriptor(&input, port_index_generator),
+        port: input,
+    };
+    let output_port = WebMidiOutputPort {
+        descriptor: port_descriptor(&output, port_index_generator),
+        port: output,
+    };
+    Some((input_port, output_port))
+}
+
+/// Receives MIDI input from a [`WebMidiInputPort`]
+///
+/// The input gateway is invoked from the event loop of the browser.
+/// Messages are no longer received after disconnecting or dropping
+/// the connection.
+#[allow(missing_debug_implementations)]
+pub struct WebMidiInputConnection<I> {
+    port: MidiInput,
+    input_gateway: Rc<RefCell<I>>,
+    #[allow(dead_code)] // Only needs to be kept alive
+    on_midi_message: Closure<dyn FnMut(MidiMessageEvent)>,
+}
+
+impl<I> WebMidiInputConnection<I>
+where
+    I: MidiInputGateway + 'static,
+{
+    #[must_use]
+    pub fn connect<F>(
+        device: &MidiDeviceDescriptor,
+        input_port: &WebMidiInputPort,
+        new_input_gateway: &F,
+    ) -> Self
+    where
+        F: NewMidiInputGateway<MidiInputGateway = I> + ?Sized,
+    {
+        let mut input_gateway =
+            new_input_gateway.new_midi_input_gateway(device, &input_port.descriptor);
+        input_gateway.connect_midi_input_port(device, &input_port.descriptor);
+        let input_gateway = Rc::new(RefCell::new(input_gateway));
+        let mut running_status_reassembler = RunningStatusReassembler::new();
+        let on_midi_message = Closure::<dyn FnMut(MidiMessageEvent)>::new({
+            let input_gateway = Rc::clone(&input_gateway);
+            move |event: MidiMessageEvent| {
+                let Ok(data) = event.data() else {
+                    return;
+                };
+                // The time stamp is given in milliseconds with a fractional part
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let ts = TimeStamp::from_micros((event.time_stamp() * 1000.0) as u64);
+                let mut input_gateway = input_gateway.borrow_mut();
+                running_status_reassembler.reassemble(&data, |message| {
+                    log::trace!("Received MIDI input: {ts} {message:0x?}");
+                    if !input_gateway.handle_midi_input(ts, message) {
+                        log::warn!("Unhandled MIDI input {ts} {message:x?}");
+                    }
+                });
+            }
+        });
+        let port = input_port.port.clone();
+        port.set_onmidimessage(Some(on_midi_message.as_ref().unchecked_ref()));
+        Self {
+            port,
+            input_gateway,
+            on_midi_message,
+        }
+    }
+
+    /// Stop receiving messages and return the input gateway
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Never panics
+    pub fn disconnect(self) -> I {
+        let input_gateway = Rc::clone(&self.input_gateway);
+        // Drops the closure that holds the other reference
+        drop(self);
+        Rc::into_inner(input_gateway)
+            .expect("input gateway not shared")
+            .into_inner()
+    }
+}
+
+impl<I> Drop for WebMidiInputConnection<I> {
+    fn drop(&mut self) {
+        // The closure must not be invoked after it has been dropped.
+        self.port.set_onmidimessage(None);
+    }
+}
+
+/// Sends MIDI output to a [`WebMidiOutputPort`]
+#[allow(missing_debug_implementations)]
+pub struct WebMidiOutputConnection {
+    port: MidiOutput,
+}
+
+impl WebMidiOutputConnection {
+    #[must_use]
+    pub fn connect(output_port: &WebMidiOutputPort) -> Self {
+        Self {
+            port: output_port.port.clone(),
+        }
+    }
+}
+
+impl super::MidiOutputConnection for WebMidiOutputConnection {
+    fn send_midi_output(&mut self, output: &[u8]) -> OutputResult<()> {
+        let data = Uint8Array::from(output);
+        self.port.send(&data).map_err(|err| OutputError::Send {
+            msg: format!("{err:?}").into(),
+        })
+    }
+}
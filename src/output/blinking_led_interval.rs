@@ -0,0 +1,30 @@
+// SPDX-FileCopyrightText: The djio authors
+// SPDX-License-Identifier: MPL-2.0
+
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
+use futures_timer::Delay;
+use futures_util::stream;
+
+/// Runtime-agnostic, periodic stream for driving blinking LEDs.
+///
+/// The first item is emitted immediately. Subsequent items are emitted
+/// at multiples of `period` without accumulating drift. Could be passed
+/// to [`BlinkingLedTicker::map_into_output_stream()`](crate::BlinkingLedTicker::map_into_output_stream).
+///
+/// Not available on wasm targets that lack a system clock.
+pub fn blinking_led_interval_stream(period: Duration) -> impl Stream<Item = ()> + Send + 'static {
+    debug_assert!(!period.is_zero());
+    stream::unfold(None, move |next_tick: Option<Instant>| async move {
+        let now = Instant::now();
+        let next_tick = match next_tick {
+            Some(next_tick) => {
+                Delay::new(next_tick.saturating_duration_since(now)).await;
+                next_tick
+            }
+            None => now,
+        };
+        Some(((), Some(next_tick + period)))
+    })
+}
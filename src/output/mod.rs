@@ -6,7 +6,9 @@
 
 use std::{
     borrow::Cow,
+    collections::HashMap,
     ops::{Deref, DerefMut},
+    sync::OnceLock,
     time::Duration,
 };
 
@@ -15,7 +17,9 @@ use futures_util::{stream, StreamExt as _};
 use strum::FromRepr;
 use thiserror::Error;
 
-use crate::{Control, ControlValue};
+#[cfg(feature = "experimental-param")]
+use crate::param::{RampingF32, RampingProfile};
+use crate::{Control, ControlIndex, ControlValue};
 
 #[cfg(feature = "blinking-led-task")]
 mod blinking_led_task;
@@ -24,12 +28,31 @@ pub use blinking_led_task::blinking_led_task;
 #[cfg(feature = "blinking-led-task-tokio-rt")]
 pub use blinking_led_task::spawn_blinking_led_task;
 
+#[cfg(all(feature = "blinking-led-interval", not(target_family = "wasm")))]
+mod blinking_led_interval;
+#[cfg(all(feature = "blinking-led-interval", not(target_family = "wasm")))]
+pub use blinking_led_interval::blinking_led_interval_stream;
+
+#[cfg(feature = "osc")]
+mod osc;
+#[cfg(feature = "osc")]
+pub use osc::{
+    default_osc_address_mapper, encode_osc_bundle, encode_osc_message, osc_time_tag_now,
+    OscAddressMapper, OscOutputGateway, OSC_TIME_TAG_IMMEDIATELY,
+};
+
 #[derive(Debug, Error)]
 pub enum OutputError {
     #[error("disconnected")]
     Disconnected,
     #[error("send: {msg}")]
     Send { msg: Cow<'static, str> },
+    /// The device has no output control with this index
+    #[error("unknown control index {0}")]
+    UnknownControl(ControlIndex),
+    /// Outputs are sent faster than they could be processed
+    #[error("queue full")]
+    QueueFull,
 }
 
 pub type OutputResult<T> = std::result::Result<T, OutputError>;
@@ -57,6 +80,32 @@ impl From<ControlValue> for LedOutput {
     }
 }
 
+/// Common gamma value for perceptually linear LED brightness
+pub const DEFAULT_LED_GAMMA: f32 = 2.2;
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn gamma_correct_u8_uncached(value: u8, gamma: f32) -> u8 {
+    ((f32::from(value) / 255.0).powf(gamma) * 255.0).round() as u8
+}
+
+fn gamma_correct_u8(value: u8, gamma: f32) -> u8 {
+    // Lookup table for the default gamma value, built at first use.
+    static DEFAULT_GAMMA_TABLE: OnceLock<[u8; 256]> = OnceLock::new();
+    debug_assert!(gamma > 0.0);
+    #[allow(clippy::float_cmp)]
+    if gamma != DEFAULT_LED_GAMMA {
+        return gamma_correct_u8_uncached(value, gamma);
+    }
+    let table = DEFAULT_GAMMA_TABLE.get_or_init(|| {
+        let mut table = [0; 256];
+        for (value, corrected) in (0..=u8::MAX).zip(table.iter_mut()) {
+            *corrected = gamma_correct_u8_uncached(value, DEFAULT_LED_GAMMA);
+        }
+        table
+    });
+    table[usize::from(value)]
+}
+
 /// Dimmable LED
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
@@ -64,6 +113,20 @@ pub struct DimLedOutput {
     pub brightness: u8,
 }
 
+impl DimLedOutput {
+    /// Apply gamma correction to the brightness
+    ///
+    /// Compensates the non-linear perception of LED brightness. Use
+    /// [`DEFAULT_LED_GAMMA`] if unsure, which is backed by a lookup table.
+    #[must_use]
+    pub fn gamma_correct(self, gamma: f32) -> Self {
+        let Self { brightness } = self;
+        Self {
+            brightness: gamma_correct_u8(brightness, gamma),
+        }
+    }
+}
+
 impl From<DimLedOutput> for ControlValue {
     fn from(value: DimLedOutput) -> Self {
         let DimLedOutput { brightness } = value;
@@ -86,6 +149,132 @@ pub struct RgbLedOutput {
     pub blue: u8,
 }
 
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn unit_to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn u8_to_unit(value: u8) -> f32 {
+    f32::from(value) / 255.0
+}
+
+impl RgbLedOutput {
+    pub const BLACK: Self = Self {
+        red: 0,
+        green: 0,
+        blue: 0,
+    };
+
+    pub const WHITE: Self = Self {
+        red: u8::MAX,
+        green: u8::MAX,
+        blue: u8::MAX,
+    };
+
+    /// Create a color from HSV components
+    ///
+    /// The `hue` is given in degrees and wraps around, i.e. values outside
+    /// of the interval [0, 360) are allowed. Both `saturation` and `value`
+    /// are clamped to the interval [0, 1].
+    #[must_use]
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let hue = if hue.is_finite() {
+            hue.rem_euclid(360.0)
+        } else {
+            0.0
+        };
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+        let chroma = value * saturation;
+        let sector = hue / 60.0;
+        let secondary = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+        let (red, green, blue) = match sector {
+            sector if sector < 1.0 => (chroma, secondary, 0.0),
+            sector if sector < 2.0 => (secondary, chroma, 0.0),
+            sector if sector < 3.0 => (0.0, chroma, secondary),
+            sector if sector < 4.0 => (0.0, secondary, chroma),
+            sector if sector < 5.0 => (secondary, 0.0, chroma),
+            _ => (chroma, 0.0, secondary),
+        };
+        let offset = value - chroma;
+        Self {
+            red: unit_to_u8(red + offset),
+            green: unit_to_u8(green + offset),
+            blue: unit_to_u8(blue + offset),
+        }
+    }
+
+    /// Convert the color into HSV components
+    ///
+    /// Returns the hue in degrees in the interval [0, 360) and both
+    /// saturation and value in the interval [0, 1].
+    #[must_use]
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let Self { red, green, blue } = self;
+        let max = red.max(green).max(blue);
+        let min = red.min(green).min(blue);
+        let value = u8_to_unit(max);
+        if max == min {
+            // Achromatic
+            return (0.0, 0.0, value);
+        }
+        let chroma = value - u8_to_unit(min);
+        let saturation = chroma / value;
+        let hue = if max == red {
+            60.0 * ((u8_to_unit(green) - u8_to_unit(blue)) / chroma).rem_euclid(6.0)
+        } else if max == green {
+            60.0 * ((u8_to_unit(blue) - u8_to_unit(red)) / chroma + 2.0)
+        } else {
+            60.0 * ((u8_to_unit(red) - u8_to_unit(green)) / chroma + 4.0)
+        };
+        (hue, saturation, value)
+    }
+
+    /// Scale the brightness
+    ///
+    /// The `brightness` is clamped to the interval [0, 1].
+    #[must_use]
+    pub fn scaled(self, brightness: f32) -> Self {
+        let brightness = brightness.clamp(0.0, 1.0);
+        let Self { red, green, blue } = self;
+        Self {
+            red: unit_to_u8(u8_to_unit(red) * brightness),
+            green: unit_to_u8(u8_to_unit(green) * brightness),
+            blue: unit_to_u8(u8_to_unit(blue) * brightness),
+        }
+    }
+
+    /// Apply gamma correction to all color components
+    ///
+    /// Compensates the non-linear perception of LED brightness. Use
+    /// [`DEFAULT_LED_GAMMA`] if unsure, which is backed by a lookup table.
+    #[must_use]
+    pub fn gamma_correct(self, gamma: f32) -> Self {
+        let Self { red, green, blue } = self;
+        Self {
+            red: gamma_correct_u8(red, gamma),
+            green: gamma_correct_u8(green, gamma),
+            blue: gamma_correct_u8(blue, gamma),
+        }
+    }
+
+    /// Linear interpolation between two colors
+    ///
+    /// Returns `self` for `t = 0.0` and `other` for `t = 1.0`.
+    /// The parameter `t` is clamped to the interval [0, 1].
+    #[must_use]
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let lerp_u8 =
+            |from: u8, to: u8| unit_to_u8(u8_to_unit(from) * (1.0 - t) + u8_to_unit(to) * t);
+        Self {
+            red: lerp_u8(self.red, other.red),
+            green: lerp_u8(self.green, other.green),
+            blue: lerp_u8(self.blue, other.blue),
+        }
+    }
+}
+
 impl From<RgbLedOutput> for ControlValue {
     fn from(value: RgbLedOutput) -> Self {
         let RgbLedOutput { red, green, blue } = value;
@@ -102,6 +291,25 @@ impl From<ControlValue> for RgbLedOutput {
     }
 }
 
+impl ControlValue {
+    /// Interpret the value as an [`RgbLedOutput`]
+    ///
+    /// Returns `None` if any of the upper 8 bits are set, e.g. for the
+    /// bits of a non-zero slider position.
+    #[must_use]
+    pub fn as_rgb(self) -> Option<RgbLedOutput> {
+        (self.to_bits() <= 0x00ff_ffff).then(|| self.into())
+    }
+
+    /// Interpret the value as a [`DimLedOutput`]
+    ///
+    /// Returns `None` if any bits beyond the lowest byte are set.
+    #[must_use]
+    pub fn as_dim_led(self) -> Option<DimLedOutput> {
+        (self.to_bits() <= 0xff).then(|| self.into())
+    }
+}
+
 /// First error after sending multiple outputs
 #[derive(Debug)]
 pub struct SendOutputsError {
@@ -157,11 +365,436 @@ where
     }
 }
 
+pub type BoxedControlOutputGateway = Box<dyn ControlOutputGateway + Send + 'static>;
+
+/// Discards all outputs
+///
+/// For headless testing or devices without any outputs. Only counts
+/// the outputs, e.g. for assertions in tests.
+#[derive(Debug, Default)]
+pub struct NullOutputGateway {
+    sent: usize,
+}
+
+impl NullOutputGateway {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { sent: 0 }
+    }
+
+    /// The number of outputs that have been sent
+    #[must_use]
+    pub const fn sent(&self) -> usize {
+        self.sent
+    }
+}
+
+impl ControlOutputGateway for NullOutputGateway {
+    fn send_output(&mut self, _output: &Control) -> OutputResult<()> {
+        self.sent += 1;
+        Ok(())
+    }
+
+    fn send_outputs(&mut self, outputs: &[Control]) -> Result<(), SendOutputsError> {
+        self.sent += outputs.len();
+        Ok(())
+    }
+}
+
+/// Sends all outputs to multiple gateways
+///
+/// For mirroring outputs, e.g. to multiple identical controllers or
+/// to both a controller and a virtual panel in the UI.
+///
+/// A failure of one gateway does not prevent sending the outputs to the
+/// remaining gateways. Only the first error is reported.
+#[allow(missing_debug_implementations)]
+pub struct FanOutGateway {
+    gateways: Vec<BoxedControlOutputGateway>,
+}
+
+impl FanOutGateway {
+    #[must_use]
+    pub const fn new(gateways: Vec<BoxedControlOutputGateway>) -> Self {
+        Self { gateways }
+    }
+
+    pub fn push(&mut self, gateway: BoxedControlOutputGateway) {
+        self.gateways.push(gateway);
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> Vec<BoxedControlOutputGateway> {
+        let Self { gateways } = self;
+        gateways
+    }
+}
+
+impl ControlOutputGateway for FanOutGateway {
+    fn send_output(&mut self, output: &Control) -> OutputResult<()> {
+        let mut result = Ok(());
+        for gateway in &mut self.gateways {
+            let next_result = gateway.send_output(output);
+            if result.is_ok() {
+                result = next_result;
+            }
+        }
+        result
+    }
+
+    /// Send all outputs to each gateway
+    ///
+    /// [`SendOutputsError::sent_ok`] refers to the first gateway that failed.
+    fn send_outputs(&mut self, outputs: &[Control]) -> Result<(), SendOutputsError> {
+        let mut result = Ok(());
+        for gateway in &mut self.gateways {
+            let next_result = gateway.send_outputs(outputs);
+            if result.is_ok() {
+                result = next_result;
+            }
+        }
+        result
+    }
+}
+
+/// Rewrites the indices of outputs before sending them
+///
+/// Outputs with unmapped indices are rejected with
+/// [`OutputError::UnknownControl`].
+///
+/// See also [`crate::ControlIndexRemap`].
+#[derive(Debug)]
+pub struct ControlIndexRemapOutput<G> {
+    mapping: HashMap<ControlIndex, ControlIndex>,
+    gateway: G,
+    remapped: Vec<Control>,
+}
+
+impl<G> ControlIndexRemapOutput<G> {
+    #[must_use]
+    pub const fn new(mapping: HashMap<ControlIndex, ControlIndex>, gateway: G) -> Self {
+        Self {
+            mapping,
+            gateway,
+            remapped: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn mapping(&self) -> &HashMap<ControlIndex, ControlIndex> {
+        &self.mapping
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> G {
+        let Self { gateway, .. } = self;
+        gateway
+    }
+}
+
+impl<G> ControlOutputGateway for ControlIndexRemapOutput<G>
+where
+    G: ControlOutputGateway,
+{
+    fn send_output(&mut self, output: &Control) -> OutputResult<()> {
+        let Some(&index) = self.mapping.get(&output.index) else {
+            return Err(OutputError::UnknownControl(output.index));
+        };
+        self.gateway.send_output(&Control {
+            index,
+            value: output.value,
+        })
+    }
+
+    /// Sends all outputs up to the first one with an unmapped index.
+    fn send_outputs(&mut self, outputs: &[Control]) -> Result<(), SendOutputsError> {
+        let Self {
+            mapping,
+            gateway,
+            remapped,
+        } = self;
+        debug_assert!(remapped.is_empty());
+        for &Control { index, value } in outputs {
+            let Some(&index) = mapping.get(&index) else {
+                break;
+            };
+            remapped.push(Control { index, value });
+        }
+        let result = gateway.send_outputs(remapped);
+        let sent_ok = remapped.len();
+        remapped.clear();
+        result?;
+        if let Some(unmapped) = outputs.get(sent_ok) {
+            return Err(SendOutputsError {
+                sent_ok: Some(sent_ok),
+                err: OutputError::UnknownControl(unmapped.index),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Buffers and coalesces outputs before sending them.
+///
+/// Only the latest value is kept for each [`ControlIndex`]. Buffered
+/// outputs are sent in the order they have been buffered for the
+/// first time, either explicitly by [`Self::flush()`] or implicitly
+/// when the buffer is full.
+#[derive(Debug)]
+pub struct CoalescingOutputGateway<G> {
+    gateway: G,
+    capacity: usize,
+    pending: Vec<Control>,
+    pending_positions: HashMap<ControlIndex, usize>,
+}
+
+impl<G> CoalescingOutputGateway<G> {
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    #[must_use]
+    pub fn new(gateway: G, capacity: usize) -> Self {
+        debug_assert!(capacity > 0);
+        Self {
+            gateway,
+            capacity,
+            pending: Vec::with_capacity(capacity),
+            pending_positions: HashMap::with_capacity(capacity),
+        }
+    }
+
+    #[must_use]
+    pub const fn gateway(&self) -> &G {
+        &self.gateway
+    }
+
+    #[must_use]
+    pub fn gateway_mut(&mut self) -> &mut G {
+        &mut self.gateway
+    }
+
+    /// Consume the wrapper and return the inner gateway
+    ///
+    /// Pending outputs are discarded.
+    #[must_use]
+    pub fn into_inner(self) -> G {
+        let Self { gateway, .. } = self;
+        gateway
+    }
+
+    /// The number of distinct outputs that are waiting to be sent
+    #[must_use]
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Discard all pending outputs
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.pending_positions.clear();
+    }
+
+    /// Buffer an output
+    ///
+    /// Returns `true` if the buffer is full and needs to be flushed.
+    fn push(&mut self, output: Control) -> bool {
+        let Self {
+            pending,
+            pending_positions,
+            ..
+        } = self;
+        if let Some(&position) = pending_positions.get(&output.index) {
+            pending[position].value = output.value;
+        } else {
+            pending_positions.insert(output.index, pending.len());
+            pending.push(output);
+        }
+        self.pending.len() >= self.capacity
+    }
+}
+
+impl<G> CoalescingOutputGateway<G>
+where
+    G: ControlOutputGateway,
+{
+    /// Send all pending outputs
+    ///
+    /// Returns the number of distinct outputs that have been sent.
+    ///
+    /// Outputs that have not been sent due to an error remain pending.
+    pub fn flush(&mut self) -> Result<usize, SendOutputsError> {
+        if self.pending.is_empty() {
+            return Ok(0);
+        }
+        match self.gateway.send_outputs(&self.pending) {
+            Ok(()) => {
+                let sent_ok = self.pending.len();
+                self.clear();
+                Ok(sent_ok)
+            }
+            Err(err) => {
+                if let Some(sent_ok) = err.sent_ok {
+                    self.pending.drain(..sent_ok);
+                    self.pending_positions.clear();
+                    self.pending_positions.extend(
+                        self.pending
+                            .iter()
+                            .enumerate()
+                            .map(|(position, output)| (output.index, position)),
+                    );
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+impl<G> ControlOutputGateway for CoalescingOutputGateway<G>
+where
+    G: ControlOutputGateway,
+{
+    fn send_output(&mut self, output: &Control) -> OutputResult<()> {
+        if self.push(*output) {
+            self.flush()
+                .map_err(|SendOutputsError { sent_ok: _, err }| err)?;
+        }
+        Ok(())
+    }
+
+    fn send_outputs(&mut self, outputs: &[Control]) -> Result<(), SendOutputsError> {
+        for output in outputs {
+            if self.push(*output) {
+                // Buffered outputs do not correspond to the given outputs.
+                self.flush()
+                    .map_err(|SendOutputsError { sent_ok: _, err }| SendOutputsError {
+                        sent_ok: None,
+                        err,
+                    })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Segmented level meter, e.g. for VU meters
+///
+/// Maps a level in the interval [0, 1] onto a strip of LEDs
+/// with optional peak hold and clip indication.
+///
+/// Each invocation of [`Self::update()`] counts as a single tick.
+#[derive(Debug, Clone)]
+pub struct LevelMeter {
+    segments: Vec<LedOutput>,
+    peak_hold_ticks: u32,
+    clip_indication: bool,
+    peak_lit: usize,
+    peak_remaining_ticks: u32,
+}
+
+impl LevelMeter {
+    #[must_use]
+    pub fn new(num_segments: usize) -> Self {
+        debug_assert!(num_segments > 0);
+        Self {
+            segments: vec![LedOutput::Off; num_segments],
+            peak_hold_ticks: 0,
+            clip_indication: false,
+            peak_lit: 0,
+            peak_remaining_ticks: 0,
+        }
+    }
+
+    /// Hold the peak segment for the given number of ticks
+    ///
+    /// Peak hold is disabled for 0 ticks.
+    #[must_use]
+    pub const fn with_peak_hold(mut self, ticks: u32) -> Self {
+        self.peak_hold_ticks = ticks;
+        self
+    }
+
+    /// Reserve the last segment for indicating clipping
+    ///
+    /// The last segment is only lit if the level reaches 1.0 and
+    /// the remaining segments cover the whole range below.
+    #[must_use]
+    pub const fn with_clip_indication(mut self, clip_indication: bool) -> Self {
+        self.clip_indication = clip_indication;
+        self
+    }
+
+    #[must_use]
+    pub fn num_segments(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// The current output of all segments, from bottom to top
+    #[must_use]
+    pub fn segments(&self) -> &[LedOutput] {
+        &self.segments
+    }
+
+    /// Turn off all segments and forget the peak
+    pub fn reset(&mut self) {
+        self.segments.fill(LedOutput::Off);
+        self.peak_lit = 0;
+        self.peak_remaining_ticks = 0;
+    }
+
+    /// Update the meter with the current level
+    ///
+    /// Returns the resulting output of all segments, from bottom to top.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn update(&mut self, level: f32) -> &[LedOutput] {
+        let level = if level.is_nan() {
+            0.0
+        } else {
+            level.clamp(0.0, 1.0)
+        };
+        let num_segments = self.segments.len();
+        let lit = if self.clip_indication {
+            let num_level_segments = num_segments - 1;
+            if level >= 1.0 {
+                num_segments
+            } else {
+                ((level * num_level_segments as f32).round() as usize).min(num_level_segments)
+            }
+        } else {
+            ((level * num_segments as f32).round() as usize).min(num_segments)
+        };
+        if lit >= self.peak_lit {
+            self.peak_lit = lit;
+            self.peak_remaining_ticks = self.peak_hold_ticks;
+        } else if self.peak_remaining_ticks > 0 {
+            self.peak_remaining_ticks -= 1;
+        } else {
+            self.peak_lit = lit;
+        }
+        for (index, segment) in self.segments.iter_mut().enumerate() {
+            let is_lit = index < lit || (self.peak_hold_ticks > 0 && index + 1 == self.peak_lit);
+            *segment = if is_lit {
+                LedOutput::On
+            } else {
+                LedOutput::Off
+            };
+        }
+        &self.segments
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LedState {
     Off,
     BlinkFast,
     BlinkSlow,
+    /// Blink according to a pattern of 8 ticks
+    ///
+    /// The pattern is read from the most significant to the least
+    /// significant bit, see [`BlinkingLedOutput::pattern()`].
+    BlinkPattern(u8),
     On,
 }
 
@@ -169,7 +802,7 @@ impl LedState {
     #[must_use]
     pub const fn is_blinking(self) -> bool {
         match self {
-            Self::BlinkFast | Self::BlinkSlow => true,
+            Self::BlinkFast | Self::BlinkSlow | Self::BlinkPattern(_) => true,
             Self::Off | Self::On => false,
         }
     }
@@ -190,6 +823,7 @@ impl LedState {
             Self::Off => LedOutput::Off,
             Self::BlinkFast => blinking_led_output.fast(),
             Self::BlinkSlow => blinking_led_output.slow(),
+            Self::BlinkPattern(pattern) => blinking_led_output.pattern(pattern),
             Self::On => LedOutput::On,
         }
     }
@@ -201,7 +835,28 @@ pub const DEFAULT_BLINKING_LED_PERIOD: Duration = Duration::from_millis(250);
 pub struct BlinkingLedOutput(u8);
 
 impl BlinkingLedOutput {
-    pub const ON: Self = Self(0b11);
+    pub const ON: Self = Self(!0);
+
+    /// Number of ticks in a pattern
+    pub const PATTERN_LEN: u8 = 8;
+
+    /// Position within a pattern in the range `0..PATTERN_LEN`
+    #[must_use]
+    pub const fn phase(self) -> u8 {
+        !self.0 % Self::PATTERN_LEN
+    }
+
+    /// Output of a pattern at the current phase
+    ///
+    /// The first phase is mapped to the most significant bit.
+    #[must_use]
+    pub const fn pattern(self, pattern: u8) -> LedOutput {
+        if pattern & (0x80 >> self.phase()) == 0 {
+            LedOutput::Off
+        } else {
+            LedOutput::On
+        }
+    }
 
     #[must_use]
     pub const fn fast(self) -> LedOutput {
@@ -222,13 +877,53 @@ impl BlinkingLedOutput {
     }
 }
 
+/// Periodic on/off pattern of 8 ticks
+///
+/// Each bit of the frame mask represents a single tick, starting
+/// with the most significant bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PulsePattern(u8);
+
+impl PulsePattern {
+    /// Two quick flashes followed by a pause
+    pub const DOUBLE_BLINK: Self = Self(0b1010_0000);
+
+    #[must_use]
+    pub const fn new(frame_mask: u8) -> Self {
+        Self(frame_mask)
+    }
+
+    #[must_use]
+    pub const fn frame_mask(self) -> u8 {
+        self.0
+    }
+
+    /// Output for the given tick count
+    #[must_use]
+    pub const fn output_at(self, tick: usize) -> LedOutput {
+        self.output(BlinkingLedTicker::output_from_value(tick))
+    }
+
+    /// Output for the current tick of a [`BlinkingLedTicker`]
+    #[must_use]
+    pub const fn output(self, blinking_led_output: BlinkingLedOutput) -> LedOutput {
+        blinking_led_output.pattern(self.0)
+    }
+}
+
+impl From<PulsePattern> for LedState {
+    fn from(from: PulsePattern) -> Self {
+        Self::BlinkPattern(from.frame_mask())
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct BlinkingLedTicker(usize);
 
 impl BlinkingLedTicker {
     const fn output_from_value(value: usize) -> BlinkingLedOutput {
         #[allow(clippy::cast_possible_truncation)]
-        BlinkingLedOutput(!value as u8 & 0b11)
+        BlinkingLedOutput(!value as u8)
     }
 
     #[must_use]
@@ -307,9 +1002,174 @@ impl Default for VirtualLed {
     }
 }
 
+/// Virtual LED with smoothly dimmed brightness
+///
+/// The brightness approaches the target brightness when ticked,
+/// e.g. for the decay of level meters or soft button feedback.
+#[cfg(feature = "experimental-param")]
+#[derive(Debug, Clone)]
+pub struct DimmableVirtualLed {
+    profile: RampingProfile,
+    brightness: RampingF32,
+}
+
+#[cfg(feature = "experimental-param")]
+impl DimmableVirtualLed {
+    /// Create a new, turned off LED
+    #[must_use]
+    pub const fn new(profile: RampingProfile) -> Self {
+        Self {
+            profile,
+            brightness: RampingF32::new(0.0),
+        }
+    }
+
+    /// Target brightness in the interval [0, 1]
+    #[must_use]
+    pub const fn target_brightness(&self) -> f32 {
+        self.brightness.target_value()
+    }
+
+    /// Set the target brightness in the interval [0, 1]
+    ///
+    /// Restarts the ramping if the target brightness has changed.
+    pub fn set_target_brightness(&mut self, brightness: f32) {
+        debug_assert!((0.0..=1.0).contains(&brightness));
+        let brightness = brightness.clamp(0.0, 1.0);
+        if brightness.to_bits() == self.brightness.target_value().to_bits() {
+            return;
+        }
+        self.brightness.reset_profile(brightness, self.profile);
+    }
+
+    /// Advance the brightness by a single step
+    ///
+    /// Returns the new output.
+    pub fn tick(&mut self) -> DimLedOutput {
+        self.brightness.advance(1);
+        self.output()
+    }
+
+    /// The current output
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn output(&self) -> DimLedOutput {
+        let brightness = (self.brightness.current_value() * f32::from(u8::MAX)).round() as u8;
+        DimLedOutput { brightness }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{BlinkingLedOutput, BlinkingLedTicker, LedOutput};
+    use std::sync::{Arc, Mutex};
+
+    use super::{
+        CoalescingOutputGateway, ControlIndexRemapOutput, FanOutGateway, DEFAULT_LED_GAMMA,
+    };
+    use crate::{
+        BlinkingLedOutput, BlinkingLedTicker, Control, ControlIndex, ControlOutputGateway,
+        ControlValue, DimLedOutput, LedOutput, LedState, LevelMeter, OutputError, OutputResult,
+        PulsePattern, RgbLedOutput, SliderInput,
+    };
+
+    #[test]
+    fn rgb_led_output_bit_packing() {
+        let rgb = RgbLedOutput {
+            red: 0x12,
+            green: 0x34,
+            blue: 0x56,
+        };
+        let value = ControlValue::from(rgb);
+        assert_eq!(0x0012_3456, value.to_bits());
+        assert_eq!(rgb, RgbLedOutput::from(value));
+        assert_eq!(Some(rgb), value.as_rgb());
+        assert_eq!(
+            Some(RgbLedOutput::WHITE),
+            ControlValue::from(RgbLedOutput::WHITE).as_rgb()
+        );
+        // Not a color
+        assert_eq!(None, ControlValue::from_bits(0x0100_0000).as_rgb());
+        assert_eq!(
+            None,
+            ControlValue::from(SliderInput { position: 1.0 }).as_rgb()
+        );
+    }
+
+    #[test]
+    fn dim_led_output_bit_packing() {
+        for brightness in [0, 1, 0x80, u8::MAX] {
+            let dim_led = DimLedOutput { brightness };
+            let value = ControlValue::from(dim_led);
+            assert_eq!(u32::from(brightness), value.to_bits());
+            assert_eq!(dim_led, DimLedOutput::from(value));
+            assert_eq!(Some(dim_led), value.as_dim_led());
+        }
+        // Not a brightness
+        assert_eq!(None, ControlValue::from_bits(0x0100).as_dim_led());
+        assert_eq!(
+            None,
+            ControlValue::from(SliderInput { position: 0.5 }).as_dim_led()
+        );
+    }
+
+    #[cfg(feature = "experimental-param")]
+    #[test]
+    fn dimmable_virtual_led() {
+        use super::DimmableVirtualLed;
+        use crate::param::{RampingMode, RampingProfile};
+
+        let mut led = DimmableVirtualLed::new(RampingProfile {
+            mode: RampingMode::Linear,
+            steps: 2,
+        });
+        assert_eq!(DimLedOutput { brightness: 0 }, led.output());
+        led.set_target_brightness(1.0);
+        assert_eq!(DimLedOutput { brightness: 128 }, led.tick());
+        assert_eq!(DimLedOutput { brightness: 255 }, led.tick());
+        assert_eq!(DimLedOutput { brightness: 255 }, led.tick());
+    }
+
+    #[test]
+    fn blinking_led_pattern() {
+        let mut ticker = BlinkingLedTicker::default();
+        let outputs = (0..16)
+            .map(|_| LedState::BlinkPattern(0b1100_1000).output(ticker.tick()))
+            .collect::<Vec<_>>();
+        let expected = [
+            LedOutput::On,
+            LedOutput::On,
+            LedOutput::Off,
+            LedOutput::Off,
+            LedOutput::On,
+            LedOutput::Off,
+            LedOutput::Off,
+            LedOutput::Off,
+        ];
+        assert_eq!(expected, outputs[..8]);
+        assert_eq!(expected, outputs[8..]);
+    }
+
+    #[test]
+    fn pulse_pattern() {
+        let pattern = PulsePattern::DOUBLE_BLINK;
+        let mut ticker = BlinkingLedTicker::default();
+        let expected = [
+            LedOutput::On,
+            LedOutput::Off,
+            LedOutput::On,
+            LedOutput::Off,
+            LedOutput::Off,
+            LedOutput::Off,
+            LedOutput::Off,
+            LedOutput::Off,
+        ];
+        for (tick, expected) in expected.into_iter().enumerate() {
+            assert_eq!(expected, pattern.output_at(tick));
+            assert_eq!(expected, pattern.output(ticker.tick()));
+        }
+        // The next period starts over
+        assert_eq!(LedOutput::On, pattern.output(ticker.tick()));
+    }
 
     #[test]
     fn blinking_led_output_on() {
@@ -321,4 +1181,261 @@ mod tests {
     fn blinking_led_ticker_initial_output_is_on() {
         assert_eq!(BlinkingLedOutput::ON, BlinkingLedTicker::default().output());
     }
+
+    #[test]
+    fn rgb_led_output_from_hsv() {
+        assert_eq!(
+            RgbLedOutput {
+                red: 255,
+                green: 0,
+                blue: 0
+            },
+            RgbLedOutput::from_hsv(0.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            RgbLedOutput {
+                red: 0,
+                green: 255,
+                blue: 0
+            },
+            RgbLedOutput::from_hsv(120.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            RgbLedOutput {
+                red: 0,
+                green: 0,
+                blue: 255
+            },
+            RgbLedOutput::from_hsv(-120.0, 1.0, 1.0)
+        );
+        assert_eq!(RgbLedOutput::WHITE, RgbLedOutput::from_hsv(42.0, 0.0, 1.0));
+        assert_eq!(RgbLedOutput::BLACK, RgbLedOutput::from_hsv(42.0, 1.0, -1.0));
+        assert_eq!(RgbLedOutput::WHITE, RgbLedOutput::from_hsv(42.0, -1.0, 2.0));
+    }
+
+    #[test]
+    fn rgb_led_output_hsv_round_trip() {
+        for red in (0..=255).step_by(15) {
+            for green in (0..=255).step_by(15) {
+                for blue in (0..=255).step_by(15) {
+                    let rgb = RgbLedOutput { red, green, blue };
+                    let (h, s, v) = rgb.to_hsv();
+                    assert_eq!(rgb, RgbLedOutput::from_hsv(h, s, v));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rgb_led_output_scaled() {
+        let rgb = RgbLedOutput {
+            red: 200,
+            green: 100,
+            blue: 50,
+        };
+        assert_eq!(rgb, rgb.scaled(1.0));
+        assert_eq!(rgb, rgb.scaled(2.0));
+        assert_eq!(RgbLedOutput::BLACK, rgb.scaled(0.0));
+        assert_eq!(
+            RgbLedOutput {
+                red: 100,
+                green: 50,
+                blue: 25,
+            },
+            rgb.scaled(0.5)
+        );
+    }
+
+    #[test]
+    fn rgb_led_output_lerp() {
+        let from = RgbLedOutput::BLACK;
+        let to = RgbLedOutput {
+            red: 200,
+            green: 100,
+            blue: 50,
+        };
+        assert_eq!(from, from.lerp(to, 0.0));
+        assert_eq!(from, from.lerp(to, -1.0));
+        assert_eq!(to, from.lerp(to, 1.0));
+        assert_eq!(to, from.lerp(to, 2.0));
+        assert_eq!(to.scaled(0.5), from.lerp(to, 0.5));
+    }
+
+    #[test]
+    fn dim_led_output_gamma_correct() {
+        for (brightness, expected) in [(0, 0), (1, 0), (64, 12), (128, 56), (192, 137), (255, 255)]
+        {
+            assert_eq!(
+                DimLedOutput {
+                    brightness: expected
+                },
+                DimLedOutput { brightness }.gamma_correct(DEFAULT_LED_GAMMA)
+            );
+        }
+        for (brightness, expected) in [(0, 0), (64, 5), (128, 37), (192, 115), (255, 255)] {
+            assert_eq!(
+                DimLedOutput {
+                    brightness: expected
+                },
+                DimLedOutput { brightness }.gamma_correct(2.8)
+            );
+        }
+        for brightness in 0..=255 {
+            assert_eq!(
+                DimLedOutput { brightness },
+                DimLedOutput { brightness }.gamma_correct(1.0)
+            );
+        }
+    }
+
+    #[test]
+    fn rgb_led_output_gamma_correct() {
+        assert_eq!(
+            RgbLedOutput {
+                red: 56,
+                green: 137,
+                blue: 255,
+            },
+            RgbLedOutput {
+                red: 128,
+                green: 192,
+                blue: 255,
+            }
+            .gamma_correct(DEFAULT_LED_GAMMA)
+        );
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingOutputGateway {
+        sent: Vec<Control>,
+    }
+
+    impl ControlOutputGateway for RecordingOutputGateway {
+        fn send_output(&mut self, output: &Control) -> OutputResult<()> {
+            self.sent.push(*output);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Default, Clone)]
+    struct SharedRecordingOutputGateway {
+        sent: Arc<Mutex<Vec<Control>>>,
+    }
+
+    impl ControlOutputGateway for SharedRecordingOutputGateway {
+        fn send_output(&mut self, output: &Control) -> OutputResult<()> {
+            self.sent.lock().unwrap().push(*output);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct DisconnectedOutputGateway;
+
+    impl ControlOutputGateway for DisconnectedOutputGateway {
+        fn send_output(&mut self, _output: &Control) -> OutputResult<()> {
+            Err(OutputError::Disconnected)
+        }
+    }
+
+    fn control(index: u32, value: u32) -> Control {
+        Control {
+            index: ControlIndex::new(index),
+            value: ControlValue::from_bits(value),
+        }
+    }
+
+    #[test]
+    fn coalescing_output_gateway_keeps_latest_value() {
+        let mut gateway = CoalescingOutputGateway::new(RecordingOutputGateway::default(), 8);
+        gateway
+            .send_outputs(&[control(1, 1), control(2, 1), control(1, 2)])
+            .unwrap();
+        assert!(gateway.gateway().sent.is_empty());
+        assert_eq!(2, gateway.pending_len());
+        assert_eq!(2, gateway.flush().unwrap());
+        assert_eq!(vec![control(1, 2), control(2, 1)], gateway.gateway().sent);
+        assert_eq!(0, gateway.flush().unwrap());
+    }
+
+    #[test]
+    fn coalescing_output_gateway_flushes_when_full() {
+        let mut gateway = CoalescingOutputGateway::new(RecordingOutputGateway::default(), 2);
+        gateway.send_output(&control(1, 1)).unwrap();
+        gateway.send_output(&control(1, 2)).unwrap();
+        assert!(gateway.gateway().sent.is_empty());
+        gateway.send_output(&control(2, 1)).unwrap();
+        assert_eq!(vec![control(1, 2), control(2, 1)], gateway.gateway().sent);
+        assert_eq!(0, gateway.pending_len());
+    }
+
+    #[test]
+    fn fan_out_gateway_continues_after_error() {
+        let recording = SharedRecordingOutputGateway::default();
+        let mut gateway = FanOutGateway::new(vec![
+            Box::new(recording.clone()),
+            Box::new(DisconnectedOutputGateway),
+        ]);
+        gateway.push(Box::new(recording.clone()));
+        let err = gateway
+            .send_outputs(&[control(1, 1), control(2, 1)])
+            .unwrap_err();
+        assert!(matches!(err.err, OutputError::Disconnected));
+        assert_eq!(Some(0), err.sent_ok);
+        assert_eq!(4, recording.sent.lock().unwrap().len());
+        assert!(gateway.send_output(&control(3, 1)).is_err());
+        assert_eq!(6, recording.sent.lock().unwrap().len());
+    }
+
+    #[test]
+    fn control_index_remap_output_rejects_unmapped_indices() {
+        let mapping = [(ControlIndex::new(1), ControlIndex::new(10))]
+            .into_iter()
+            .collect();
+        let mut gateway = ControlIndexRemapOutput::new(mapping, RecordingOutputGateway::default());
+        gateway.send_output(&control(1, 1)).unwrap();
+        assert!(matches!(
+            gateway.send_output(&control(2, 1)),
+            Err(OutputError::UnknownControl(index)) if index == ControlIndex::new(2)
+        ));
+        let err = gateway
+            .send_outputs(&[control(1, 2), control(2, 2), control(1, 3)])
+            .unwrap_err();
+        assert!(matches!(
+            err.err,
+            OutputError::UnknownControl(index) if index == ControlIndex::new(2)
+        ));
+        assert_eq!(Some(1), err.sent_ok);
+        assert_eq!(
+            vec![control(10, 1), control(10, 2)],
+            gateway.into_inner().sent
+        );
+    }
+
+    #[test]
+    fn level_meter_segments() {
+        use LedOutput::{Off, On};
+        let mut meter = LevelMeter::new(4);
+        assert_eq!(&[Off, Off, Off, Off], meter.update(0.0));
+        assert_eq!(&[On, On, Off, Off], meter.update(0.5));
+        assert_eq!(&[On, On, On, On], meter.update(1.0));
+        assert_eq!(&[On, On, On, On], meter.update(2.0));
+    }
+
+    #[test]
+    fn level_meter_peak_hold() {
+        use LedOutput::{Off, On};
+        let mut meter = LevelMeter::new(4).with_peak_hold(1);
+        assert_eq!(&[On, On, On, Off], meter.update(0.75));
+        assert_eq!(&[On, Off, On, Off], meter.update(0.25));
+        assert_eq!(&[On, Off, Off, Off], meter.update(0.25));
+    }
+
+    #[test]
+    fn level_meter_clip_indication() {
+        use LedOutput::{Off, On};
+        let mut meter = LevelMeter::new(4).with_clip_indication(true);
+        assert_eq!(&[On, On, On, Off], meter.update(0.99));
+        assert_eq!(&[On, On, On, On], meter.update(1.0));
+    }
 }
@@ -0,0 +1,207 @@
+// SPDX-FileCopyrightText: The djio authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Mirroring outputs as [Open Sound Control (OSC)](https://opensoundcontrol.stanford.edu/)
+//! messages over UDP.
+//!
+//! Each [`Control`] is sent as an OSC message with a single `int32`
+//! argument that contains the raw bits of the [`ControlValue`].
+
+use std::{
+    fmt,
+    net::{ToSocketAddrs, UdpSocket},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::{ControlOutputGateway, OutputError, OutputResult, SendOutputsError};
+use crate::{Control, ControlIndex, ControlValue};
+
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970)
+const NTP_UNIX_EPOCH_OFFSET_SECS: u64 = 2_208_988_800;
+
+/// Special time tag for bundles that should be processed immediately
+pub const OSC_TIME_TAG_IMMEDIATELY: u64 = 1;
+
+/// Maps a [`ControlIndex`] onto an OSC address.
+pub type OscAddressMapper = Box<dyn Fn(ControlIndex) -> String + Send>;
+
+/// Default address mapping: `<prefix>/<index>`
+#[must_use]
+pub fn default_osc_address_mapper(prefix: impl Into<String>) -> OscAddressMapper {
+    let prefix = prefix.into();
+    Box::new(move |index| format!("{prefix}/{index}"))
+}
+
+/// Current time as an NTP time tag
+#[must_use]
+pub fn osc_time_tag_now() -> u64 {
+    let Ok(since_unix_epoch) = SystemTime::now().duration_since(UNIX_EPOCH) else {
+        return OSC_TIME_TAG_IMMEDIATELY;
+    };
+    let secs = since_unix_epoch.as_secs() + NTP_UNIX_EPOCH_OFFSET_SECS;
+    let fraction = (u64::from(since_unix_epoch.subsec_nanos()) << 32) / 1_000_000_000;
+    (secs << 32) | fraction
+}
+
+fn encode_padded_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(s.as_bytes());
+    // At least one null terminator, padded to a multiple of 4 bytes
+    let padding = 4 - s.len() % 4;
+    buf.resize(buf.len() + padding, 0);
+}
+
+/// Encode a [`ControlValue`] as an OSC message
+pub fn encode_osc_message(buf: &mut Vec<u8>, address: &str, value: ControlValue) {
+    debug_assert!(address.starts_with('/'));
+    encode_padded_str(buf, address);
+    encode_padded_str(buf, ",i");
+    buf.extend_from_slice(&value.to_bits().to_be_bytes());
+}
+
+/// Encode [`Control`]s as an OSC bundle
+///
+/// All messages in the bundle share the same `time_tag`.
+///
+/// # Panics
+///
+/// Panics if the size of a single message exceeds the range of `u32`.
+pub fn encode_osc_bundle(
+    buf: &mut Vec<u8>,
+    time_tag: u64,
+    outputs: &[Control],
+    address_mapper: impl Fn(ControlIndex) -> String,
+) {
+    encode_padded_str(buf, "#bundle");
+    buf.extend_from_slice(&time_tag.to_be_bytes());
+    for output in outputs {
+        let address = address_mapper(output.index);
+        let size_offset = buf.len();
+        buf.extend_from_slice(&[0; 4]);
+        encode_osc_message(buf, &address, output.value);
+        let size = u32::try_from(buf.len() - size_offset - 4).expect("message size");
+        buf[size_offset..size_offset + 4].copy_from_slice(&size.to_be_bytes());
+    }
+}
+
+/// Sends outputs as OSC messages over UDP.
+pub struct OscOutputGateway {
+    socket: UdpSocket,
+    address_mapper: OscAddressMapper,
+    buf: Vec<u8>,
+}
+
+impl OscOutputGateway {
+    /// Connect a local UDP socket to a remote OSC receiver
+    pub fn connect(
+        local_addr: impl ToSocketAddrs,
+        remote_addr: impl ToSocketAddrs,
+        address_mapper: OscAddressMapper,
+    ) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind(local_addr)?;
+        socket.connect(remote_addr)?;
+        Ok(Self::new(socket, address_mapper))
+    }
+
+    /// Create a new gateway from a connected UDP socket
+    #[must_use]
+    pub fn new(socket: UdpSocket, address_mapper: OscAddressMapper) -> Self {
+        Self {
+            socket,
+            address_mapper,
+            buf: Vec::new(),
+        }
+    }
+
+    fn send_buf(&self) -> OutputResult<()> {
+        self.socket
+            .send(&self.buf)
+            .map(|_| ())
+            .map_err(|err| OutputError::Send {
+                msg: err.to_string().into(),
+            })
+    }
+}
+
+impl fmt::Debug for OscOutputGateway {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OscOutputGateway")
+            .field("socket", &self.socket)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ControlOutputGateway for OscOutputGateway {
+    fn send_output(&mut self, output: &Control) -> OutputResult<()> {
+        let address = (self.address_mapper)(output.index);
+        self.buf.clear();
+        encode_osc_message(&mut self.buf, &address, output.value);
+        self.send_buf()
+    }
+
+    /// Send all outputs as a single OSC bundle
+    ///
+    /// All messages in the bundle share the same time tag.
+    fn send_outputs(&mut self, outputs: &[Control]) -> Result<(), SendOutputsError> {
+        if outputs.is_empty() {
+            return Ok(());
+        }
+        let Self {
+            address_mapper,
+            buf,
+            ..
+        } = self;
+        buf.clear();
+        encode_osc_bundle(buf, osc_time_tag_now(), outputs, &**address_mapper);
+        // The bundle is sent as a single datagram, i.e. either all
+        // or none of the outputs are sent.
+        self.send_buf().map_err(|err| SendOutputsError {
+            sent_ok: Some(0),
+            err,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_osc_address_mapper, encode_osc_bundle, encode_osc_message};
+    use crate::{Control, ControlIndex, ControlValue};
+
+    #[test]
+    fn encode_message() {
+        let mut buf = Vec::new();
+        encode_osc_message(&mut buf, "/djio/1", ControlValue::from_bits(0x0102_0304));
+        assert_eq!(
+            b"/djio/1\0,i\0\0\x01\x02\x03\x04".as_slice(),
+            buf.as_slice()
+        );
+    }
+
+    #[test]
+    fn encode_bundle() {
+        let mut buf = Vec::new();
+        let outputs = [
+            Control {
+                index: ControlIndex::new(1),
+                value: ControlValue::from_bits(0x7f),
+            },
+            Control {
+                index: ControlIndex::new(23),
+                value: ControlValue::from_bits(0),
+            },
+        ];
+        encode_osc_bundle(
+            &mut buf,
+            0x0102_0304_0506_0708,
+            &outputs,
+            default_osc_address_mapper("/djio"),
+        );
+        let expected = [
+            b"#bundle\0".as_slice(),
+            b"\x01\x02\x03\x04\x05\x06\x07\x08",
+            b"\0\0\0\x10/djio/1\0,i\0\0\0\0\0\x7f",
+            b"\0\0\0\x14/djio/23\0\0\0\0,i\0\0\0\0\0\0",
+        ]
+        .concat();
+        assert_eq!(expected, buf);
+    }
+}
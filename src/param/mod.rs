@@ -23,9 +23,11 @@ mod atomic;
 pub use self::atomic::{AtomicValue, SharedAtomicValue, WeakAtomicValue};
 
 mod ramping;
-pub use ramping::{RampingF32, RampingMode, RampingProfile};
+pub use ramping::{RampedValue, RampingF32, RampingMode, RampingProfile};
 
 mod registry;
+#[cfg(feature = "param-notify")]
+pub use self::registry::Subscription;
 pub use self::registry::{
     DescriptorRegistration, RegisterError, RegisteredDescriptor, RegisteredId, RegisteredParam,
     Registration, RegistrationHeader, RegistrationStatus, Registry,
@@ -37,6 +39,7 @@ pub use self::registry::{
 /// read/load or write/store. The variant names reflect the view of
 /// the _provider_.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     /// Input
     ///
@@ -69,6 +72,11 @@ pub enum Direction {
     Debug, Clone, Copy, PartialEq, PartialOrd, EnumAsInner, EnumDiscriminants, derive_more::From,
 )]
 #[strum_discriminants(name(ValueType))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    strum_discriminants(derive(serde::Serialize, serde::Deserialize))
+)]
 pub enum Value {
     /// Boolean value
     Bool(bool),
@@ -91,6 +99,8 @@ pub enum Value {
     derive_more::Deref,
     derive_more::DerefMut,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Name<'a>(Cow<'a, str>);
 
 impl<'a> Name<'a> {
@@ -124,6 +134,8 @@ impl<'a> From<Name<'a>> for Cow<'a, str> {
     derive_more::Deref,
     derive_more::DerefMut,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Unit<'a>(Cow<'a, str>);
 
 impl<'a> Unit<'a> {
@@ -147,6 +159,7 @@ impl<'a> From<Unit<'a>> for Cow<'a, str> {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Descriptor<'a> {
     /// Display name.
     ///
@@ -183,6 +196,7 @@ impl<'a> Descriptor<'a> {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ValueDescriptor {
     /// Range restrictions
     pub range: ValueRangeDescriptor,
@@ -201,6 +215,7 @@ impl ValueDescriptor {
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ValueRangeDescriptor {
     /// Minimum value (inclusive)
     pub min: Option<Value>,
@@ -241,6 +256,34 @@ impl ValueRangeDescriptor {
         }
         true
     }
+
+    /// Clamp a value into the range.
+    ///
+    /// Values that are out of range are replaced by the nearest bound.
+    /// Values that are not comparable, i.e. NaN, are replaced by the
+    /// lower bound if available.
+    ///
+    /// Comparing values of different types is not allowed and triggers
+    /// a debug assertion.
+    #[must_use]
+    pub fn clamp(&self, value: Value) -> Value {
+        let Self { min, max } = self;
+        if let Some(min) = min {
+            debug_assert_eq!(ValueType::from(min), ValueType::from(value));
+            match value.partial_cmp(min) {
+                Some(Ordering::Equal | Ordering::Greater) => (),
+                Some(Ordering::Less) | None => return *min,
+            }
+        }
+        if let Some(max) = max {
+            debug_assert_eq!(ValueType::from(max), ValueType::from(value));
+            match value.partial_cmp(max) {
+                Some(Ordering::Equal | Ordering::Less) => (),
+                Some(Ordering::Greater) | None => return *max,
+            }
+        }
+        value
+    }
 }
 
 #[derive(
@@ -254,6 +297,8 @@ impl ValueRangeDescriptor {
     derive_more::Deref,
     derive_more::DerefMut,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Address<'a>(Cow<'a, str>);
 
 impl<'a> Address<'a> {
@@ -287,4 +332,138 @@ mod tests {
             ValueRangeDescriptor::unbounded()
         );
     }
+
+    fn output_descriptor(default: Value) -> Descriptor<'static> {
+        Descriptor {
+            name: Name::new("Level".into()),
+            unit: None,
+            direction: Direction::Output,
+            value: ValueDescriptor {
+                range: ValueRangeDescriptor::unbounded(),
+                default,
+            },
+        }
+    }
+
+    /// Register an output parameter and obtain its descriptor
+    fn register_output<'a>(
+        registry: &'a mut Registry,
+        address: &'static str,
+        descriptor: Descriptor<'static>,
+    ) -> (RegisteredId, RegisteredDescriptor<'a>) {
+        let DescriptorRegistration { header, descriptor } = registry
+            .register_descriptor(Address::new(address.into()), descriptor)
+            .unwrap();
+        (header.id, descriptor)
+    }
+
+    #[test]
+    fn registry_ready_entries() {
+        let mut registry = Registry::default();
+        registry.register_address(Address::new("/pending".into()));
+        let (ready_id, _) =
+            register_output(&mut registry, "/ready", output_descriptor(Value::F32(0.0)));
+        let ready_ids = registry
+            .ready_entries()
+            .map(|registration| registration.header.id)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![ready_id], ready_ids);
+    }
+
+    #[test]
+    fn registry_store_values() {
+        let mut registry = Registry::default();
+        let (id, _) = register_output(&mut registry, "/level", output_descriptor(Value::F32(0.0)));
+        let updates = [(id, Value::F32(0.5)), (id, Value::I32(1))];
+        assert_eq!(1, registry.store_values(&updates));
+        let (_, _, output_value) = registry
+            .find_registered(&Address::new("/level".into()))
+            .unwrap();
+        assert_eq!(Value::F32(0.5), output_value.unwrap().load());
+    }
+
+    #[test]
+    fn store_clamped() {
+        let mut descriptor = output_descriptor(Value::I32(0));
+        descriptor.value.range = ValueRangeDescriptor {
+            min: Some(Value::I32(-1)),
+            max: Some(Value::I32(1)),
+        };
+        let mut registry = Registry::default();
+        let (_, registered) = register_output(&mut registry, "/level", descriptor);
+        assert_eq!(Some(Value::I32(1)), registered.store_clamped(Value::I32(2)));
+        assert_eq!(
+            Some(Value::I32(-1)),
+            registered.store_clamped(Value::I32(-2))
+        );
+        assert_eq!(Some(Value::I32(0)), registered.store_clamped(Value::I32(0)));
+        assert_eq!(Value::I32(0), registered.output_value.unwrap().load());
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn ramped_output_value() {
+        let mut registry = Registry::default();
+        let (_, registered) =
+            register_output(&mut registry, "/level", output_descriptor(Value::F32(0.0)));
+        let shared_value = registered.output_value.unwrap();
+        let profile = RampingProfile {
+            mode: RampingMode::Linear,
+            steps: 4,
+        };
+        let mut ramped = RampedValue::new(SharedAtomicValue::clone(shared_value), profile).unwrap();
+        assert_eq!(0.0, ramped.tick(1));
+        shared_value.store(Value::F32(1.0));
+        assert_eq!(0.25, ramped.tick(1));
+        assert_eq!(0.75, ramped.tick(2));
+        assert_eq!(1.0, ramped.tick(2));
+    }
+
+    #[cfg(feature = "param-notify")]
+    #[test]
+    fn registry_notify_changed_values() {
+        let mut registry = Registry::default();
+        let (id, _) = register_output(&mut registry, "/level", output_descriptor(Value::F32(0.0)));
+        let mut subscription = registry.subscribe();
+        let mut poll_changed = |registry: &Registry| {
+            let mut changed_ids = Vec::new();
+            subscription.poll_changed(registry, |id| changed_ids.push(id));
+            changed_ids
+        };
+        let updates = [(id, Value::F32(0.0))];
+        assert_eq!(1, registry.store_values(&updates));
+        assert!(poll_changed(&registry).is_empty());
+        let updates = [(id, Value::F32(0.5)), (id, Value::F32(1.0))];
+        assert_eq!(2, registry.store_values(&updates));
+        assert_eq!(vec![id], poll_changed(&registry));
+        assert!(poll_changed(&registry).is_empty());
+        // Single stores are reported as well
+        let registered = registry.get_registered(id).unwrap().descriptor.unwrap();
+        assert_eq!(
+            Some(Value::F32(0.0)),
+            registered.store_clamped(Value::F32(0.0))
+        );
+        assert_eq!(vec![id], poll_changed(&registry));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn descriptor_json_round_trip() {
+        let descriptor = Descriptor {
+            name: Name::new("Gain".into()),
+            unit: Some(Unit::new("dB".into())),
+            direction: Direction::Input,
+            value: ValueDescriptor {
+                range: ValueRangeDescriptor {
+                    min: Some(Value::F32(-12.0)),
+                    max: Some(Value::F32(12.0)),
+                },
+                default: Value::F32(0.0),
+            },
+        };
+        let json = serde_json::to_string(&descriptor).unwrap();
+        let deserialized: Descriptor<'_> = serde_json::from_str(&json).unwrap();
+        assert_eq!(descriptor, deserialized);
+        assert_eq!(ValueType::F32, deserialized.value.value_type());
+    }
 }
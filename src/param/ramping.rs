@@ -3,6 +3,8 @@
 
 //! Interpolation of parameter values
 
+use super::SharedAtomicValue;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RampingMode {
     /// Switch to the target value with the last step
@@ -139,3 +141,58 @@ impl Iterator for RampingF32 {
         Some(current_value)
     }
 }
+
+/// Smoothed reader of a shared [`crate::param::Value::F32`] output value.
+///
+/// Ramps from the current towards the most recently stored value when ticked.
+/// Consumers don't need to track the target value themselves.
+#[derive(Debug, Clone)]
+pub struct RampedValue {
+    shared_value: SharedAtomicValue,
+    profile: RampingProfile,
+    ramping: RampingF32,
+}
+
+impl RampedValue {
+    /// Returns `None` if the shared value is not an `f32` value.
+    #[must_use]
+    pub fn new(shared_value: SharedAtomicValue, profile: RampingProfile) -> Option<Self> {
+        let value = shared_value.load_f32()?;
+        Some(Self {
+            shared_value,
+            profile,
+            ramping: RampingF32::new(value),
+        })
+    }
+
+    #[must_use]
+    pub fn current_value(&self) -> f32 {
+        self.ramping.current_value()
+    }
+
+    #[must_use]
+    pub const fn target_value(&self) -> f32 {
+        self.ramping.target_value()
+    }
+
+    /// Pick up the stored value and advance the ramping
+    ///
+    /// Restarts the ramping if the stored value has changed.
+    ///
+    /// Returns the new current value.
+    pub fn tick(&mut self, steps: usize) -> f32 {
+        let Self {
+            shared_value,
+            profile,
+            ramping,
+        } = self;
+        let target_value = shared_value
+            .load_f32()
+            .unwrap_or_else(|| ramping.target_value());
+        if target_value.to_bits() != ramping.target_value().to_bits() {
+            ramping.reset_profile(target_value, *profile);
+        }
+        ramping.advance(steps);
+        ramping.current_value()
+    }
+}
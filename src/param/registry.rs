@@ -1,6 +1,8 @@
 // SPDX-FileCopyrightText: The djio authors
 // SPDX-License-Identifier: MPL-2.0
 
+#[cfg(feature = "param-notify")]
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::{
     collections::{hash_map::Entry, HashMap},
     sync::Arc,
@@ -9,7 +11,7 @@ use std::{
 use atomic::AtomicValue;
 use thiserror::Error;
 
-use super::{atomic, Address, Descriptor, Direction, SharedAtomicValue};
+use super::{atomic, Address, Descriptor, Direction, SharedAtomicValue, Value, ValueType};
 
 const INITIAL_CAPACITY: usize = 1024;
 
@@ -103,6 +105,17 @@ struct RegistryEntry<'a> {
     address: Address<'a>,
     descriptor: Option<Descriptor<'a>>,
     output_value: Option<SharedAtomicValue>,
+    /// Incremented whenever the output value is changed through the registry
+    #[cfg(feature = "param-notify")]
+    generation: AtomicU64,
+}
+
+/// Store an output value and record if it has changed
+#[cfg(feature = "param-notify")]
+fn store_tracked(output_value: &AtomicValue, generation: &AtomicU64, value: Value) {
+    if output_value.swap(value) != value {
+        generation.fetch_add(1, Ordering::Release);
+    }
 }
 
 impl<'a> RegistryEntry<'a> {
@@ -111,11 +124,15 @@ impl<'a> RegistryEntry<'a> {
             address,
             descriptor,
             output_value,
+            #[cfg(feature = "param-notify")]
+            generation,
         } = self;
         debug_assert!(descriptor.is_some() || output_value.is_none());
         let descriptor = descriptor.as_ref().map(|descriptor| RegisteredDescriptor {
             descriptor,
             output_value: output_value.as_ref(),
+            #[cfg(feature = "param-notify")]
+            generation,
         });
         Registration {
             header: RegistrationHeader {
@@ -177,6 +194,39 @@ pub struct RegisteredDescriptor<'a> {
     /// address with [`Registry::register_address()`] after the descriptor
     /// has been registered.
     pub output_value: Option<&'a SharedAtomicValue>,
+
+    #[cfg(feature = "param-notify")]
+    generation: &'a AtomicU64,
+}
+
+impl RegisteredDescriptor<'_> {
+    /// Store an output value after clamping it into the valid range.
+    ///
+    /// Protects consumers from observing illegal values.
+    ///
+    /// Returns the actual value that has been stored or `None` if
+    /// the parameter has no output value, i.e. for input parameters.
+    ///
+    /// Bounds of the value range are inclusive. Incomparable values like
+    /// `NaN` are replaced by the lower or upper bound, if any.
+    ///
+    /// Changes are reported to subscribers, see [`Registry::subscribe()`].
+    #[must_use]
+    pub fn store_clamped(&self, value: Value) -> Option<Value> {
+        let Self {
+            descriptor,
+            output_value,
+            #[cfg(feature = "param-notify")]
+            generation,
+        } = self;
+        let output_value = (*output_value)?;
+        let value = descriptor.value.range.clamp(value);
+        #[cfg(not(feature = "param-notify"))]
+        output_value.store(value);
+        #[cfg(feature = "param-notify")]
+        store_tracked(output_value, generation, value);
+        Some(value)
+    }
 }
 
 /// Registration with mandatory descriptor
@@ -233,6 +283,8 @@ impl Registry {
                 address,
                 descriptor: None,
                 output_value: None,
+                #[cfg(feature = "param-notify")]
+                generation: AtomicU64::new(0),
             };
             self.entries.push(new_entry);
             debug_assert_eq!(self.address_to_id.len(), self.entries.len());
@@ -272,6 +324,8 @@ impl Registry {
             address,
             descriptor: registered_descriptor,
             output_value: registered_output_value,
+            #[cfg(feature = "param-notify")]
+            generation,
         } = entry;
         let descriptor = if let Some(registered_descriptor) = registered_descriptor {
             if registered_descriptor != &descriptor {
@@ -300,6 +354,8 @@ impl Registry {
             descriptor: RegisteredDescriptor {
                 descriptor,
                 output_value: registered_output_value.as_ref(),
+                #[cfg(feature = "param-notify")]
+                generation,
             },
         })
     }
@@ -321,6 +377,76 @@ impl Registry {
             .map(|entry| entry.registration(RegistrationStatus::AlreadyRegistered, id))
     }
 
+    /// Iterate over all parameters with a registered descriptor.
+    ///
+    /// Parameters whose address has been registered without a descriptor
+    /// are pending and excluded.
+    pub fn ready_entries(&self) -> impl Iterator<Item = DescriptorRegistration<'_>> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(entry_id, entry)| {
+                let Registration { header, descriptor } = entry.registration(
+                    RegistrationStatus::AlreadyRegistered,
+                    RegisteredId(entry_id),
+                );
+                descriptor.map(|descriptor| DescriptorRegistration { header, descriptor })
+            })
+    }
+
+    /// Store the values of multiple output parameters.
+    ///
+    /// Updates of unknown parameters, parameters without an output value,
+    /// and values with a mismatching type are skipped.
+    ///
+    /// Returns the number of values that have been stored.
+    #[must_use]
+    pub fn store_values(&self, updates: &[(RegisteredId, Value)]) -> usize {
+        let mut stored_count = 0;
+        for (id, value) in updates {
+            let Some(entry) = self.entries.get(registry_entry_id(*id)) else {
+                log::debug!("Unknown parameter {id}");
+                continue;
+            };
+            let Some(output_value) = &entry.output_value else {
+                log::debug!("No output value @ {address}", address = entry.address);
+                continue;
+            };
+            if output_value.value_type() != ValueType::from(value) {
+                log::debug!(
+                    "Mismatching value type @ {address}: {value:?}",
+                    address = entry.address
+                );
+                continue;
+            }
+            #[cfg(not(feature = "param-notify"))]
+            output_value.store(*value);
+            #[cfg(feature = "param-notify")]
+            store_tracked(output_value, &entry.generation, *value);
+            stored_count += 1;
+        }
+        stored_count
+    }
+
+    /// Subscribe to changes of output values.
+    ///
+    /// Only changes that are stored through the registry, i.e. by
+    /// [`Self::store_values()`] or [`RegisteredDescriptor::store_clamped()`],
+    /// are observed. Direct writes to the shared values are not.
+    ///
+    /// Storing values remains lock-free and doesn't allocate. Consumers
+    /// need to poll the subscription periodically.
+    #[cfg(feature = "param-notify")]
+    #[must_use]
+    pub fn subscribe(&self) -> Subscription {
+        let generations = self
+            .entries
+            .iter()
+            .map(|entry| entry.generation.load(Ordering::Acquire))
+            .collect();
+        Subscription { generations }
+    }
+
     /// Find the metadata of a parameter by address.
     #[must_use]
     pub fn find_registered(
@@ -343,6 +469,7 @@ impl Registry {
                     address: entry_address,
                     descriptor,
                     output_value,
+                    ..
                 } = entry;
                 debug_assert_eq!(address, entry_address);
                 (id, descriptor.as_ref(), output_value.as_ref())
@@ -359,3 +486,37 @@ impl Default for Registry {
         }
     }
 }
+
+/// Observes changes of output values in a [`Registry`]
+///
+/// Intended for non-real-time consumers like UIs. Multiple changes
+/// of the same parameter between two polls are coalesced.
+#[cfg(feature = "param-notify")]
+#[derive(Debug, Clone)]
+pub struct Subscription {
+    /// The last observed generation of each registry entry
+    generations: Vec<u64>,
+}
+
+#[cfg(feature = "param-notify")]
+impl Subscription {
+    /// Report all parameters whose value has changed since the last poll
+    ///
+    /// Parameters that have been registered after subscribing are reported
+    /// on the first change.
+    pub fn poll_changed(&mut self, registry: &Registry, mut on_changed: impl FnMut(RegisteredId)) {
+        self.generations.resize(registry.entries.len(), 0);
+        for (entry_id, (entry, last_generation)) in registry
+            .entries
+            .iter()
+            .zip(&mut self.generations)
+            .enumerate()
+        {
+            let generation = entry.generation.load(Ordering::Acquire);
+            if generation != *last_generation {
+                *last_generation = generation;
+                on_changed(RegisteredId(entry_id));
+            }
+        }
+    }
+}